@@ -69,7 +69,7 @@ cargo build --release --workspace
 %install
 # Install binaries
 install -Dm755 target/release/network-manager %{buildroot}%{_bindir}/network-manager
-install -Dm755 target/release/network-managerd %{buildroot}%{_bindir}/network-managerd
+install -Dm755 target/release/cd-network-managerd %{buildroot}%{_bindir}/cd-network-managerd
 install -Dm755 target/release/nmctl %{buildroot}%{_bindir}/nmctl
 
 # Install desktop file
@@ -97,20 +97,20 @@ install -Dm644 data/polkit/com.chrisdaggas.network-manager.policy \
     %{buildroot}%{_datadir}/polkit-1/actions/com.chrisdaggas.network-manager.policy
 
 # Install systemd service
-install -Dm644 data/systemd/network-managerd.service \
-    %{buildroot}%{_unitdir}/network-managerd.service
+install -Dm644 data/systemd/cd-network-managerd.service \
+    %{buildroot}%{_unitdir}/cd-network-managerd.service
 
 %check
 cargo test --release --workspace
 
 %post daemon
-%systemd_post network-managerd.service
+%systemd_post cd-network-managerd.service
 
 %preun daemon
-%systemd_preun network-managerd.service
+%systemd_preun cd-network-managerd.service
 
 %postun daemon
-%systemd_postun_with_restart network-managerd.service
+%systemd_postun_with_restart cd-network-managerd.service
 
 %files
 %license LICENSE
@@ -123,11 +123,11 @@ cargo test --release --workspace
 
 %files daemon
 %license LICENSE
-%{_bindir}/network-managerd
+%{_bindir}/cd-network-managerd
 %{_datadir}/dbus-1/system.d/com.chrisdaggas.NetworkManagerd.conf
 %{_datadir}/dbus-1/system-services/com.chrisdaggas.NetworkManagerd.service
 %{_datadir}/polkit-1/actions/com.chrisdaggas.network-manager.policy
-%{_unitdir}/network-managerd.service
+%{_unitdir}/cd-network-managerd.service
 
 %files cli
 %license LICENSE
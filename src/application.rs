@@ -521,7 +521,7 @@ impl Application {
 
     /// Start the auto-switch service.
     ///
-    /// Blocking network checks (`nmcli`, `ip`, `ping`) run on a
+    /// Blocking network checks (NetworkManager D-Bus, `ip`, `ping`) run on a
     /// background thread so the GTK main loop stays responsive.
     fn start_autoswitch(&self) {
         use crate::services::AutoSwitchService;
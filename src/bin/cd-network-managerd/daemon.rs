@@ -0,0 +1,346 @@
+// Network Manager Daemon - Shared State
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Daemon state shared by all D-Bus interfaces.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use network_manager::dbus_client::DaemonStatus;
+use network_manager::executor::{ActionExecutor, SystemExecutor};
+use network_manager::models::result::PendingCheckpoint;
+use network_manager::models::{
+    ActionScope, AutomationAction, Error, ExecutionResult, Profile, Result, SandboxMode, SystemdSandboxConfig,
+};
+use network_manager::services::SandboxRunner;
+
+use crate::store::ProfileStore;
+
+/// Daemon configuration file (optional).
+pub const CONFIG_FILE: &str = "/etc/cd-network-manager/daemon.toml";
+
+/// Time NetworkManager gets to finish an automatic rollback before an
+/// unanswered checkpoint is forgotten.
+const CHECKPOINT_EXPIRY_GRACE: Duration = Duration::from_secs(5);
+
+/// Daemon configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DaemonConfig {
+    /// Sandboxing mode for profile scripts.
+    #[serde(default)]
+    pub sandbox_mode: SandboxMode,
+    /// Limits for scripts in the systemd sandbox mode.
+    #[serde(default)]
+    pub systemd_sandbox: SystemdSandboxConfig,
+}
+
+impl DaemonConfig {
+    /// Load the configuration, falling back to defaults if missing or invalid.
+    pub fn load(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        match std::fs::read_to_string(path).map(|s| toml::from_str::<Self>(&s)) {
+            Ok(Ok(config)) => config,
+            Ok(Err(e)) => {
+                warn!("Invalid daemon configuration {:?}: {}", path, e);
+                Self::default()
+            }
+            Err(e) => {
+                warn!("Failed to read daemon configuration {:?}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// State shared by the Manager, Profiles and Status interfaces.
+pub struct Daemon {
+    /// Profile store.
+    store: Mutex<ProfileStore>,
+    /// Daemon configuration.
+    config: DaemonConfig,
+    /// Last activation error.
+    last_error: Mutex<Option<String>>,
+    /// Serializes profile activations.
+    activation: tokio::sync::Mutex<()>,
+    /// Network checkpoint awaiting confirmation, with its profile ID.
+    pending_checkpoint: Mutex<Option<(String, String)>>,
+}
+
+impl Daemon {
+    /// Create the daemon state.
+    pub fn new(store: ProfileStore, config: DaemonConfig) -> Self {
+        Self {
+            store: Mutex::new(store),
+            config,
+            last_error: Mutex::new(None),
+            activation: tokio::sync::Mutex::new(()),
+            pending_checkpoint: Mutex::new(None),
+        }
+    }
+
+    /// Lock the profile store, recovering from poison.
+    pub fn store(&self) -> MutexGuard<'_, ProfileStore> {
+        self.store.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Activate a profile on behalf of `initiated_by` and record the outcome.
+    ///
+    /// `requested_sandbox` is the caller's script sandbox setting; scripts
+    /// only run when the daemon's own mode matches it and is available.
+    pub async fn activate(
+        self: &Arc<Self>,
+        id: &str,
+        requested_sandbox: SandboxMode,
+        initiated_by: Option<String>,
+    ) -> Result<ExecutionResult> {
+        let _guard = self.activation.lock().await;
+
+        let profile = self.store().get(id)?;
+        check_sandbox(&profile, self.config.sandbox_mode, requested_sandbox)?;
+        // Session actions are applied by the caller once this returns.
+        let profile = profile.scoped(ActionScope::System);
+        let previous = {
+            let store = self.store();
+            store
+                .active_profile_id()
+                .filter(|active| *active != id)
+                .and_then(|active| store.get(active).ok())
+                .map(|previous| previous.scoped(ActionScope::System))
+        };
+        let sandbox_mode = self.config.sandbox_mode;
+        let systemd_sandbox = self.config.systemd_sandbox.clone();
+        let secrets_key = self.store().secrets_key_file().to_path_buf();
+
+        info!(
+            "Activating profile '{}' ({}) for {}",
+            profile.name(),
+            id,
+            initiated_by.as_deref().unwrap_or("unknown caller")
+        );
+        let mut result = tokio::task::spawn_blocking(move || {
+            let executor = SystemExecutor::new(sandbox_mode)
+                .with_systemd_sandbox(systemd_sandbox)
+                .with_secrets_key(secrets_key);
+            let result = executor.execute(&profile, previous.as_ref());
+            // Switching away from a profile removes what only it created.
+            if let (true, Some(previous)) = (result.is_success(), previous) {
+                for released in executor.deactivate(&previous, Some(&profile)) {
+                    match &released.error_detail {
+                        Some(detail) => warn!("Deactivating '{}': {}: {}", previous.name(), released.message, detail),
+                        None => info!("Deactivating '{}': {}", previous.name(), released.message),
+                    }
+                }
+            }
+            result
+        })
+        .await
+        .map_err(|e| Error::Internal(e.to_string()))?;
+        result.initiated_by = initiated_by;
+        *self.pending_checkpoint.lock().unwrap_or_else(|p| p.into_inner()) =
+            result.checkpoint.as_ref().map(|c| (c.path.clone(), id.to_string()));
+        if let Some(checkpoint) = &result.checkpoint {
+            self.schedule_expiry(checkpoint.clone(), id.to_string());
+        }
+
+        let mut last_error = self.last_error.lock().unwrap_or_else(|p| p.into_inner());
+        if result.is_success() {
+            let recorded = {
+                let mut store = self.store();
+                store.mark_applied(id).and_then(|()| store.set_active_profile_id(Some(id.to_string())))
+            };
+            *last_error = recorded
+                .as_ref()
+                .err()
+                .map(|e| format!("{}: failed to record the activation: {}", result.profile_name, e));
+            recorded?;
+        } else {
+            *last_error = Some(format!("{}: {}", result.profile_name, result.message));
+        }
+        info!("Profile '{}' finished: {}", result.profile_name, result.message);
+
+        Ok(result)
+    }
+
+    /// Forget a checkpoint once NetworkManager has rolled it back by itself,
+    /// because nobody kept or reverted it in time.
+    fn schedule_expiry(self: &Arc<Self>, checkpoint: PendingCheckpoint, profile_id: String) {
+        let daemon = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let remaining = (checkpoint.expires_at - chrono::Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(remaining + CHECKPOINT_EXPIRY_GRACE).await;
+            let Some(daemon) = daemon.upgrade() else { return };
+            if let Err(e) = daemon.expire_checkpoint(&checkpoint.path, &profile_id) {
+                warn!("Failed to clear expired checkpoint {}: {}", checkpoint.path, e);
+            }
+        });
+    }
+
+    /// Clear a checkpoint that expired unanswered, and the active profile
+    /// whose network changes it reverted.
+    fn expire_checkpoint(&self, checkpoint: &str, profile_id: &str) -> Result<()> {
+        {
+            let mut pending = self.pending_checkpoint.lock().unwrap_or_else(|p| p.into_inner());
+            // Already kept, reverted or replaced by a later activation.
+            if pending.as_ref().map(|(path, _)| path.as_str()) != Some(checkpoint) {
+                return Ok(());
+            }
+            *pending = None;
+        }
+        warn!("Checkpoint {} expired; network changes of profile {} were reverted", checkpoint, profile_id);
+        let mut store = self.store();
+        if store.active_profile_id() == Some(profile_id) {
+            store.set_active_profile_id(None)?;
+        }
+        Ok(())
+    }
+
+    /// Profile ID of a checkpoint created by the last activation.
+    pub fn checkpoint_profile(&self, checkpoint: &str) -> Result<String> {
+        match &*self.pending_checkpoint.lock().unwrap_or_else(|p| p.into_inner()) {
+            Some((path, profile_id)) if path == checkpoint => Ok(profile_id.clone()),
+            _ => Err(Error::ValidationFailed(format!("No pending checkpoint {}", checkpoint))),
+        }
+    }
+
+    /// Keep (`keep = true`) or revert the network changes of a checkpoint.
+    pub async fn finish_checkpoint(&self, checkpoint: &str, keep: bool) -> Result<()> {
+        let profile_id = self.checkpoint_profile(checkpoint)?;
+        *self.pending_checkpoint.lock().unwrap_or_else(|p| p.into_inner()) = None;
+
+        let sandbox_mode = self.config.sandbox_mode;
+        let path = checkpoint.to_string();
+        tokio::task::spawn_blocking(move || {
+            let executor = SystemExecutor::new(sandbox_mode);
+            if keep {
+                executor.confirm_checkpoint(&path)
+            } else {
+                executor.rollback_checkpoint(&path)
+            }
+        })
+        .await
+        .map_err(|e| Error::Internal(e.to_string()))??;
+
+        if keep {
+            info!("Network changes of profile {} confirmed", profile_id);
+        } else {
+            info!("Network changes of profile {} reverted", profile_id);
+            let mut store = self.store();
+            if store.active_profile_id() == Some(profile_id.as_str()) {
+                store.set_active_profile_id(None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the status report.
+    ///
+    /// The daemon is healthy unless the last activation failed or the
+    /// profile store has a problem.
+    pub fn status(&self) -> DaemonStatus {
+        let store = self.store();
+        let last_error = self
+            .last_error
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .clone()
+            .or_else(|| store.problem());
+        DaemonStatus {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            healthy: last_error.is_none(),
+            active_profile_id: store.active_profile_id().map(str::to_string),
+            // Auto-switch rules are evaluated by the user session, not the daemon.
+            auto_switch_enabled: false,
+            last_error,
+        }
+    }
+}
+
+/// Refuse to run a profile's scripts under a weaker sandbox than the caller
+/// asked for, or under a sandbox that is not available.
+///
+/// The daemon's configured mode always applies; a caller asking for no
+/// sandbox accepts it, any other request must name the same mode.
+fn check_sandbox(profile: &Profile, configured: SandboxMode, requested: SandboxMode) -> Result<()> {
+    if !profile.automation_actions.iter().any(AutomationAction::is_script) {
+        return Ok(());
+    }
+    if requested != SandboxMode::None && requested != configured {
+        return Err(Error::ValidationFailed(format!(
+            "Scripts of '{}' must run with {} sandboxing, but the daemon uses {} (sandbox_mode in {})",
+            profile.name(),
+            requested.display_name(),
+            configured.display_name(),
+            CONFIG_FILE
+        )));
+    }
+    if !SandboxRunner::new(configured).is_available() {
+        return Err(Error::ValidationFailed(format!(
+            "Scripts of '{}' cannot run: {} sandboxing is not available to the daemon",
+            profile.name(),
+            configured.display_name()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::script_action;
+    use network_manager::models::actions::ExecutionPhase;
+
+    #[test]
+    fn test_check_sandbox() {
+        let mut profile = Profile::new("Office");
+        // Without scripts the sandbox does not matter.
+        assert!(check_sandbox(&profile, SandboxMode::None, SandboxMode::Firejail).is_ok());
+
+        profile.automation_actions.push(script_action("/usr/local/bin/vpn-up", ExecutionPhase::Post));
+        assert!(check_sandbox(&profile, SandboxMode::None, SandboxMode::None).is_ok());
+        assert!(check_sandbox(&profile, SandboxMode::None, SandboxMode::Bubblewrap).is_err());
+        assert!(check_sandbox(&profile, SandboxMode::Firejail, SandboxMode::Bubblewrap).is_err());
+    }
+
+    #[test]
+    fn test_expired_checkpoint_is_forgotten() {
+        let root = std::env::temp_dir().join(format!("cd-nm-daemon-{}", uuid::Uuid::new_v4()));
+        let daemon = Daemon::new(ProfileStore::open(&root).unwrap(), DaemonConfig::default());
+        let checkpoint = "/org/freedesktop/NetworkManager/Checkpoint/3";
+        daemon.store().set_active_profile_id(Some("office".to_string())).unwrap();
+        *daemon.pending_checkpoint.lock().unwrap() = Some((checkpoint.to_string(), "office".to_string()));
+
+        // A checkpoint that was since replaced is left alone.
+        daemon.expire_checkpoint("/org/freedesktop/NetworkManager/Checkpoint/2", "office").unwrap();
+        assert_eq!(daemon.checkpoint_profile(checkpoint).unwrap(), "office");
+
+        daemon.expire_checkpoint(checkpoint, "office").unwrap();
+        assert!(daemon.checkpoint_profile(checkpoint).is_err());
+        assert_eq!(daemon.store().active_profile_id(), None);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_status_reports_store_problems() {
+        let root = std::env::temp_dir().join(format!("cd-nm-daemon-{}", uuid::Uuid::new_v4()));
+        let daemon = Daemon::new(ProfileStore::open(&root).unwrap(), DaemonConfig::default());
+        assert!(daemon.status().healthy);
+
+        std::fs::write(root.join("profiles").join("broken.toml"), "not a profile").unwrap();
+        let daemon = Daemon::new(ProfileStore::open(&root).unwrap(), DaemonConfig::default());
+        let status = daemon.status();
+        assert!(!status.healthy);
+        assert!(status.last_error.unwrap().contains("could not be read"));
+
+        *daemon.last_error.lock().unwrap() = Some("Office: 1 action(s) failed".to_string());
+        assert_eq!(daemon.status().last_error.as_deref(), Some("Office: 1 action(s) failed"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,239 @@
+// Network Manager Daemon - D-Bus Interfaces
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! D-Bus interfaces served at [`DBUS_OBJECT_PATH`].
+//!
+//! Structured data (profiles, execution results, status) is exchanged as
+//! JSON strings, matching what `dbus_client::DaemonClient` expects.
+//!
+//! Methods that change system or profile state, or read stored profiles
+//! (which may hold proxy credentials), are authorized through Polkit against
+//! the caller's bus name before doing any work.
+//!
+//! [`DBUS_OBJECT_PATH`]: network_manager::models::DBUS_OBJECT_PATH
+
+use std::sync::Arc;
+use zbus::message::Header;
+use zbus::{fdo, interface, Connection};
+
+use network_manager::models::{Error, Profile, SandboxMode};
+
+use crate::daemon::Daemon;
+use crate::polkit::{self, PolkitAction};
+
+/// Map a model error to a D-Bus error reply.
+fn to_fdo(err: Error) -> fdo::Error {
+    match err {
+        Error::ProfileNotFound(_) => fdo::Error::UnknownObject(err.to_string()),
+        Error::ProfileAlreadyExists(_)
+        | Error::InvalidProfile(_)
+        | Error::ValidationFailed(_)
+        | Error::ConfigParseFailed(_) => fdo::Error::InvalidArgs(err.to_string()),
+        Error::AuthorizationDenied(_)
+        | Error::AuthorizationFailed(_)
+        | Error::PrivilegeRequired(_) => {
+            fdo::Error::AccessDenied(err.to_string())
+        }
+        _ => fdo::Error::Failed(err.to_string()),
+    }
+}
+
+/// Authorize the sender of `header` for `action`, returning its bus name.
+async fn authorize(conn: &Connection, header: &Header<'_>, action: PolkitAction) -> fdo::Result<String> {
+    let sender = header
+        .sender()
+        .map(|s| s.to_string())
+        .ok_or_else(|| fdo::Error::AccessDenied("Caller has no bus name".to_string()))?;
+    polkit::check_authorization(conn, &sender, action)
+        .await
+        .map_err(to_fdo)?;
+    Ok(sender)
+}
+
+/// Authorize the sender to settle a checkpoint, as for activating its profile.
+async fn authorize_checkpoint(
+    daemon: &Daemon,
+    conn: &Connection,
+    header: &Header<'_>,
+    checkpoint: &str,
+) -> fdo::Result<()> {
+    let profile_id = daemon.checkpoint_profile(checkpoint).map_err(to_fdo)?;
+    let profile = daemon.store().get(&profile_id).map_err(to_fdo)?;
+    authorize(conn, header, PolkitAction::for_activation(&profile)).await?;
+    Ok(())
+}
+
+/// Serialize a value to JSON for a D-Bus reply.
+fn to_json<T: serde::Serialize>(value: &T) -> fdo::Result<String> {
+    serde_json::to_string(value).map_err(|e| fdo::Error::Failed(e.to_string()))
+}
+
+/// Parse a JSON-encoded profile from a D-Bus argument.
+fn parse_profile(json: &str) -> fdo::Result<Profile> {
+    serde_json::from_str(json).map_err(|e| fdo::Error::InvalidArgs(format!("Invalid profile: {}", e)))
+}
+
+/// `com.chrisdaggas.NetworkManager.Manager` — liveness and activation.
+pub struct ManagerInterface {
+    daemon: Arc<Daemon>,
+}
+
+impl ManagerInterface {
+    pub fn new(daemon: Arc<Daemon>) -> Self {
+        Self { daemon }
+    }
+}
+
+#[interface(name = "com.chrisdaggas.NetworkManager.Manager")]
+impl ManagerInterface {
+    /// Check that the daemon is alive.
+    async fn ping(&self) -> bool {
+        true
+    }
+
+    /// Activate a profile, returning the JSON-encoded `ExecutionResult`.
+    ///
+    /// Profiles marked `approved_for_users` only require the
+    /// `activate-approved-profile` Polkit action. `sandbox` is the caller's
+    /// script sandbox mode; the activation is refused if the daemon's
+    /// differs.
+    async fn activate_profile(
+        &self,
+        id: &str,
+        sandbox: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+    ) -> fdo::Result<String> {
+        let requested = SandboxMode::from_name(sandbox)
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("Unknown sandbox mode: {}", sandbox)))?;
+        let profile = self.daemon.store().get(id).map_err(to_fdo)?;
+        let sender = authorize(conn, &header, PolkitAction::for_activation(&profile)).await?;
+        let initiated_by = polkit::caller_identity(conn, &sender).await;
+
+        let result = self.daemon.activate(id, requested, initiated_by).await.map_err(to_fdo)?;
+        to_json(&result)
+    }
+
+    /// Keep the network changes of the checkpoint returned by an activation.
+    async fn confirm_checkpoint(
+        &self,
+        checkpoint: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+    ) -> fdo::Result<()> {
+        authorize_checkpoint(&self.daemon, conn, &header, checkpoint).await?;
+        self.daemon.finish_checkpoint(checkpoint, true).await.map_err(to_fdo)
+    }
+
+    /// Revert the network changes of the checkpoint returned by an activation.
+    async fn rollback_checkpoint(
+        &self,
+        checkpoint: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+    ) -> fdo::Result<()> {
+        authorize_checkpoint(&self.daemon, conn, &header, checkpoint).await?;
+        self.daemon.finish_checkpoint(checkpoint, false).await.map_err(to_fdo)
+    }
+}
+
+/// `com.chrisdaggas.NetworkManager.Profiles` — profile CRUD.
+pub struct ProfilesInterface {
+    daemon: Arc<Daemon>,
+}
+
+impl ProfilesInterface {
+    pub fn new(daemon: Arc<Daemon>) -> Self {
+        Self { daemon }
+    }
+}
+
+#[interface(name = "com.chrisdaggas.NetworkManager.Profiles")]
+impl ProfilesInterface {
+    /// List all profiles as a JSON array.
+    async fn list(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+    ) -> fdo::Result<String> {
+        authorize(conn, &header, PolkitAction::ViewProfiles).await?;
+        to_json(&self.daemon.store().list())
+    }
+
+    /// Get a single profile as JSON.
+    async fn get(
+        &self,
+        id: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+    ) -> fdo::Result<String> {
+        authorize(conn, &header, PolkitAction::ViewProfiles).await?;
+        let profile = self.daemon.store().get(id).map_err(to_fdo)?;
+        to_json(&profile)
+    }
+
+    /// Create a profile from JSON.
+    async fn create(
+        &self,
+        json: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+    ) -> fdo::Result<()> {
+        authorize(conn, &header, PolkitAction::ManageProfiles).await?;
+        let profile = parse_profile(json)?;
+        self.daemon.store().create(profile).map_err(to_fdo)
+    }
+
+    /// Replace a profile from JSON.
+    async fn update(
+        &self,
+        id: &str,
+        json: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+    ) -> fdo::Result<()> {
+        authorize(conn, &header, PolkitAction::ManageProfiles).await?;
+        let profile = parse_profile(json)?;
+        self.daemon.store().update(id, profile).map_err(to_fdo)
+    }
+
+    /// Delete a profile.
+    async fn delete(
+        &self,
+        id: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+    ) -> fdo::Result<()> {
+        authorize(conn, &header, PolkitAction::ManageProfiles).await?;
+        self.daemon.store().delete(id).map_err(to_fdo)
+    }
+}
+
+/// `com.chrisdaggas.NetworkManager.Status` — daemon status queries.
+pub struct StatusInterface {
+    daemon: Arc<Daemon>,
+}
+
+impl StatusInterface {
+    pub fn new(daemon: Arc<Daemon>) -> Self {
+        Self { daemon }
+    }
+}
+
+#[interface(name = "com.chrisdaggas.NetworkManager.Status")]
+impl StatusInterface {
+    /// Get the JSON-encoded `DaemonStatus`.
+    async fn get(&self) -> fdo::Result<String> {
+        to_json(&self.daemon.status())
+    }
+
+    /// Get the active profile ID (empty if none).
+    async fn get_active_profile(&self) -> String {
+        self.daemon
+            .store()
+            .active_profile_id()
+            .unwrap_or_default()
+            .to_string()
+    }
+}
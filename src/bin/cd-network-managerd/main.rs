@@ -0,0 +1,130 @@
+// Network Manager Daemon - Main Entry Point
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! # cd-network-managerd
+//!
+//! Privileged system daemon for Network Manager.
+//!
+//! Owns `com.chrisdaggas.NetworkManagerd` on the system bus, stores
+//! profiles under `/var/lib/cd-network-manager` and applies them on
+//! behalf of the GUI.
+
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use network_manager::models::{DBUS_OBJECT_PATH, DBUS_SERVICE_NAME};
+
+mod daemon;
+mod interfaces;
+mod polkit;
+mod store;
+
+// Test helpers shared with the library, which name its models `crate::models`.
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../test_support.rs"]
+mod test_support;
+#[cfg(test)]
+use network_manager::models;
+
+use daemon::{Daemon, DaemonConfig, CONFIG_FILE};
+use interfaces::{ManagerInterface, ProfilesInterface, StatusInterface};
+use store::{ProfileStore, STATE_DIR};
+
+/// Daemon name.
+const DAEMON_NAME: &str = "cd-network-managerd";
+
+/// Daemon version.
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Print help information.
+fn print_help() {
+    println!("Usage: {} [OPTIONS]", DAEMON_NAME);
+    println!();
+    println!("Privileged system daemon for Network Manager.");
+    println!();
+    println!("Options:");
+    println!("  -h, --help       Show this help message and exit");
+    println!("  -v, --version    Show version information and exit");
+    println!("  -d, --debug      Enable debug logging");
+    println!();
+    println!("Files:");
+    println!("  {}   Daemon configuration", CONFIG_FILE);
+    println!("  {}/profiles/      Stored profiles", STATE_DIR);
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut debug_mode = false;
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print_help();
+                return ExitCode::SUCCESS;
+            }
+            "-v" | "--version" => {
+                println!("{} {}", DAEMON_NAME, VERSION);
+                return ExitCode::SUCCESS;
+            }
+            "-d" | "--debug" => debug_mode = true,
+            _ => {
+                eprintln!("Unknown option: {}", arg);
+                eprintln!("Try '--help' for more information.");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let log_level = if debug_mode {
+        tracing::Level::DEBUG
+    } else {
+        tracing::Level::INFO
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::from_default_env()
+                .add_directive(log_level.into()),
+        )
+        .init();
+
+    tracing::info!("Starting {} v{}", DAEMON_NAME, VERSION);
+
+    let store = match ProfileStore::open(Path::new(STATE_DIR)) {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::error!("Failed to open profile store {}: {}", STATE_DIR, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let config = DaemonConfig::load(Path::new(CONFIG_FILE));
+    let daemon = Arc::new(Daemon::new(store, config));
+
+    let connection = async {
+        zbus::connection::Builder::system()?
+            .name(DBUS_SERVICE_NAME)?
+            .serve_at(DBUS_OBJECT_PATH, ManagerInterface::new(daemon.clone()))?
+            .serve_at(DBUS_OBJECT_PATH, ProfilesInterface::new(daemon.clone()))?
+            .serve_at(DBUS_OBJECT_PATH, StatusInterface::new(daemon.clone()))?
+            .build()
+            .await
+    }
+    .await;
+
+    let _connection = match connection {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::error!("Failed to register {} on the system bus: {}", DBUS_SERVICE_NAME, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    tracing::info!("Serving {} at {}", DBUS_SERVICE_NAME, DBUS_OBJECT_PATH);
+
+    // Requests are handled by the connection's executor; systemd stops us with SIGTERM.
+    std::future::pending::<()>().await;
+    ExitCode::SUCCESS
+}
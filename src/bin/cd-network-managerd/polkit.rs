@@ -0,0 +1,166 @@
+// Network Manager Daemon - Polkit Authorization
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Caller authorization via `org.freedesktop.PolicyKit1.Authority`.
+//!
+//! Every privileged method checks the calling bus name against one of the
+//! actions defined in `data/polkit/com.chrisdaggas.network-manager.policy`.
+//! Profiles marked `approved_for_users` use the lighter
+//! `activate-approved-profile` action so non-admin users can switch to
+//! vetted profiles without administrator credentials, unless they run
+//! scripts or programs, which always need `execute-script`.
+
+use std::collections::HashMap;
+use tracing::{debug, warn};
+use zbus::zvariant::Value;
+use zbus::Connection;
+
+use network_manager::models::{AutomationAction, Error, Profile, Result};
+
+/// Polkit `CheckAuthorization` flag allowing an authentication dialog.
+const ALLOW_USER_INTERACTION: u32 = 1;
+
+/// Actions defined in the shipped Polkit policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolkitAction {
+    /// Activate any profile (admin).
+    ActivateProfile,
+    /// Activate a profile approved for non-admin users.
+    ActivateApprovedProfile,
+    /// Activate a profile that runs scripts or programs (admin, never cached).
+    ExecuteScript,
+    /// Create, edit or delete profiles (admin).
+    ManageProfiles,
+    /// Read stored profiles, which may hold credentials (admin).
+    ViewProfiles,
+}
+
+impl PolkitAction {
+    /// Polkit action ID.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::ActivateProfile => "com.chrisdaggas.network-manager.activate-profile",
+            Self::ActivateApprovedProfile => "com.chrisdaggas.network-manager.activate-approved-profile",
+            Self::ExecuteScript => "com.chrisdaggas.network-manager.execute-script",
+            Self::ManageProfiles => "com.chrisdaggas.network-manager.manage-profiles",
+            Self::ViewProfiles => "com.chrisdaggas.network-manager.view-profiles",
+        }
+    }
+
+    /// Choose the action required to activate a profile.
+    pub fn for_activation(profile: &Profile) -> Self {
+        if profile.automation_actions.iter().any(AutomationAction::runs_code) {
+            Self::ExecuteScript
+        } else if profile.approved_for_users {
+            Self::ActivateApprovedProfile
+        } else {
+            Self::ActivateProfile
+        }
+    }
+}
+
+/// Check that `sender` is authorized for `action`, prompting if needed.
+pub async fn check_authorization(conn: &Connection, sender: &str, action: PolkitAction) -> Result<()> {
+    let subject_details: HashMap<&str, Value<'_>> = HashMap::from([("name", Value::from(sender))]);
+    let subject = ("system-bus-name", subject_details);
+    let details: HashMap<&str, &str> = HashMap::new();
+
+    let reply = conn
+        .call_method(
+            Some("org.freedesktop.PolicyKit1"),
+            "/org/freedesktop/PolicyKit1/Authority",
+            Some("org.freedesktop.PolicyKit1.Authority"),
+            "CheckAuthorization",
+            &(subject, action.id(), details, ALLOW_USER_INTERACTION, ""),
+        )
+        .await
+        .map_err(|e| Error::AuthorizationFailed(e.to_string()))?;
+
+    let (is_authorized, is_challenge, _details): (bool, bool, HashMap<String, String>) = reply
+        .body()
+        .deserialize()
+        .map_err(|e| Error::AuthorizationFailed(e.to_string()))?;
+
+    debug!(
+        "Polkit {} for {}: authorized={} challenge={}",
+        action.id(),
+        sender,
+        is_authorized,
+        is_challenge
+    );
+
+    if is_authorized {
+        Ok(())
+    } else {
+        warn!("Polkit denied {} for {}", action.id(), sender);
+        Err(Error::AuthorizationDenied(action.id().to_string()))
+    }
+}
+
+/// Describe the user behind a bus name as `username (uid N)`.
+pub async fn caller_identity(conn: &Connection, sender: &str) -> Option<String> {
+    let uid: u32 = conn
+        .call_method(
+            Some("org.freedesktop.DBus"),
+            "/org/freedesktop/DBus",
+            Some("org.freedesktop.DBus"),
+            "GetConnectionUnixUser",
+            &(sender,),
+        )
+        .await
+        .ok()?
+        .body()
+        .deserialize()
+        .ok()?;
+
+    let passwd = std::fs::read_to_string("/etc/passwd").unwrap_or_default();
+    Some(match username_for_uid(&passwd, uid) {
+        Some(name) => format!("{} (uid {})", name, uid),
+        None => format!("uid {}", uid),
+    })
+}
+
+/// Look up a username in `/etc/passwd` content.
+fn username_for_uid(passwd: &str, uid: u32) -> Option<String> {
+    passwd.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        let entry_uid: u32 = fields.nth(1)?.parse().ok()?;
+        (entry_uid == uid).then(|| name.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::script_action;
+    use network_manager::models::actions::ExecutionPhase;
+
+    #[test]
+    fn test_activation_action() {
+        let mut profile = Profile::new("Office");
+        assert_eq!(PolkitAction::for_activation(&profile), PolkitAction::ActivateProfile);
+
+        profile.automation_actions.push(script_action("/usr/local/bin/vpn-up", ExecutionPhase::Pre));
+        assert_eq!(PolkitAction::for_activation(&profile), PolkitAction::ExecuteScript);
+
+        // Approval never lets a profile run code without admin authentication
+        profile.approved_for_users = true;
+        assert_eq!(PolkitAction::for_activation(&profile), PolkitAction::ExecuteScript);
+
+        profile.automation_actions.clear();
+        assert_eq!(PolkitAction::for_activation(&profile), PolkitAction::ActivateApprovedProfile);
+
+        profile.automation_actions.push(script_action("/usr/bin/vpn-helper", ExecutionPhase::Automation));
+        assert_eq!(PolkitAction::for_activation(&profile), PolkitAction::ExecuteScript);
+    }
+
+    #[test]
+    fn test_username_for_uid() {
+        let passwd = "root:x:0:0:root:/root:/bin/bash\nalice:x:1000:1000::/home/alice:/bin/zsh\n";
+        assert_eq!(username_for_uid(passwd, 0).as_deref(), Some("root"));
+        assert_eq!(username_for_uid(passwd, 1000).as_deref(), Some("alice"));
+        assert_eq!(username_for_uid(passwd, 1001), None);
+    }
+}
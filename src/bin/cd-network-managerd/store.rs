@@ -0,0 +1,249 @@
+// Network Manager Daemon - Profile Store
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! System-wide profile storage.
+//!
+//! Profiles are stored as one TOML file per profile under
+//! `/var/lib/cd-network-manager/profiles/<id>.toml`. Daemon runtime state
+//! (the active profile) is kept in `state.toml` next to them.
+//!
+//! Files are written atomically (temporary file + rename) with 0600
+//! permissions, since profiles may contain credentials. Secrets inside
+//! profiles are additionally encrypted with the daemon's own key
+//! (`secrets.key`), as clients send them in plaintext.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use network_manager::models::validation::validate_profile_name;
+use network_manager::models::{Error, Profile, Result};
+use network_manager::services::encryption::SECRETS_KEY_FILE;
+use network_manager::services::ProfileEncryption;
+use serde::{Deserialize, Serialize};
+
+/// Default state directory (created by systemd via `StateDirectory=`).
+pub const STATE_DIR: &str = "/var/lib/cd-network-manager";
+
+/// Persisted daemon runtime state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DaemonState {
+    /// ID of the most recently activated profile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    active_profile_id: Option<String>,
+}
+
+/// On-disk profile store owned by the daemon.
+#[derive(Debug)]
+pub struct ProfileStore {
+    /// Directory holding one TOML file per profile.
+    profiles_dir: PathBuf,
+    /// Daemon state file path.
+    state_file: PathBuf,
+    /// Key file for secrets inside profiles.
+    secrets_key_file: PathBuf,
+    /// Loaded profiles keyed by ID.
+    profiles: HashMap<String, Profile>,
+    /// Loaded daemon state.
+    state: DaemonState,
+    /// Profile files that could not be loaded.
+    unreadable: Vec<PathBuf>,
+}
+
+impl ProfileStore {
+    /// Open (and create if needed) the store rooted at `root`.
+    pub fn open(root: &Path) -> Result<Self> {
+        let profiles_dir = root.join("profiles");
+        fs::create_dir_all(&profiles_dir)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(root, fs::Permissions::from_mode(0o700));
+            let _ = fs::set_permissions(&profiles_dir, fs::Permissions::from_mode(0o700));
+        }
+
+        let mut store = Self {
+            state_file: root.join("state.toml"),
+            secrets_key_file: root.join(SECRETS_KEY_FILE),
+            profiles_dir,
+            profiles: HashMap::new(),
+            state: DaemonState::default(),
+            unreadable: Vec::new(),
+        };
+        store.load()?;
+        Ok(store)
+    }
+
+    /// Load all profiles and the daemon state from disk.
+    fn load(&mut self) -> Result<()> {
+        for entry in fs::read_dir(&self.profiles_dir)?.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+                continue;
+            }
+            match fs::read_to_string(&path).map_err(Error::from).and_then(|s| Ok(Profile::from_toml(&s)?)) {
+                Ok(profile) => {
+                    self.profiles.insert(profile.id().to_string(), profile);
+                }
+                Err(e) => {
+                    warn!("Skipping unreadable profile {:?}: {}", path, e);
+                    self.unreadable.push(path);
+                }
+            }
+        }
+
+        if self.state_file.exists() {
+            let content = fs::read_to_string(&self.state_file)?;
+            self.state = toml::from_str(&content)?;
+        }
+
+        info!("Loaded {} profiles from {:?}", self.profiles.len(), self.profiles_dir);
+        Ok(())
+    }
+
+    /// Get all profiles, sorted by name.
+    pub fn list(&self) -> Vec<Profile> {
+        let mut profiles: Vec<Profile> = self.profiles.values().cloned().collect();
+        profiles.sort_by_key(|p| p.name().to_lowercase());
+        profiles
+    }
+
+    /// Get a profile by ID.
+    pub fn get(&self, id: &str) -> Result<Profile> {
+        self.profiles
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Error::ProfileNotFound(id.to_string()))
+    }
+
+    /// Key file used to encrypt secrets inside profiles.
+    pub fn secrets_key_file(&self) -> &Path {
+        &self.secrets_key_file
+    }
+
+    /// Encrypt the plaintext secrets of a profile received from a client.
+    fn seal_secrets(&self, profile: &mut Profile) -> Result<()> {
+        if profile.secrets_mut().all(|secret| secret.plaintext().is_none()) {
+            return Ok(());
+        }
+        ProfileEncryption::from_key_file(&self.secrets_key_file)?.seal_secrets(profile)?;
+        Ok(())
+    }
+
+    /// Store a new profile.
+    pub fn create(&mut self, mut profile: Profile) -> Result<()> {
+        let id = profile.id().to_string();
+        if self.profiles.contains_key(&id) {
+            return Err(Error::ProfileAlreadyExists(id));
+        }
+        validate_profile_name(profile.name())?;
+        self.seal_secrets(&mut profile)?;
+        self.write_profile(&profile)?;
+        info!("Created profile '{}' ({})", profile.name(), id);
+        self.profiles.insert(id, profile);
+        Ok(())
+    }
+
+    /// Replace an existing profile.
+    pub fn update(&mut self, id: &str, mut profile: Profile) -> Result<()> {
+        if !self.profiles.contains_key(id) {
+            return Err(Error::ProfileNotFound(id.to_string()));
+        }
+        if profile.id().to_string() != id {
+            return Err(Error::InvalidProfile(format!(
+                "Profile ID {} does not match {}",
+                profile.id(),
+                id
+            )));
+        }
+        validate_profile_name(profile.name())?;
+        self.seal_secrets(&mut profile)?;
+        self.write_profile(&profile)?;
+        info!("Updated profile '{}' ({})", profile.name(), id);
+        self.profiles.insert(id.to_string(), profile);
+        Ok(())
+    }
+
+    /// Delete a profile.
+    pub fn delete(&mut self, id: &str) -> Result<()> {
+        if self.profiles.remove(id).is_none() {
+            return Err(Error::ProfileNotFound(id.to_string()));
+        }
+        let path = self.profile_path(id);
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        if self.state.active_profile_id.as_deref() == Some(id) {
+            self.set_active_profile_id(None)?;
+        }
+        info!("Deleted profile {}", id);
+        Ok(())
+    }
+
+    /// Get the ID of the active profile.
+    pub fn active_profile_id(&self) -> Option<&str> {
+        self.state.active_profile_id.as_deref()
+    }
+
+    /// Describe what is wrong with the store, if anything.
+    pub fn problem(&self) -> Option<String> {
+        if !self.unreadable.is_empty() {
+            return Some(format!(
+                "{} profile file(s) in {:?} could not be read",
+                self.unreadable.len(),
+                self.profiles_dir
+            ));
+        }
+        match self.active_profile_id() {
+            Some(id) if !self.profiles.contains_key(id) => Some(format!("Active profile {} no longer exists", id)),
+            _ => None,
+        }
+    }
+
+    /// Record the active profile and persist the daemon state.
+    pub fn set_active_profile_id(&mut self, id: Option<String>) -> Result<()> {
+        self.state.active_profile_id = id;
+        let content = toml::to_string_pretty(&self.state)?;
+        write_atomic(&self.state_file, &content)
+    }
+
+    /// Mark a profile as applied and persist it.
+    pub fn mark_applied(&mut self, id: &str) -> Result<()> {
+        let mut profile = self.get(id)?;
+        profile.mark_applied();
+        self.write_profile(&profile)?;
+        self.profiles.insert(id.to_string(), profile);
+        Ok(())
+    }
+
+    /// Path of the file backing a profile.
+    fn profile_path(&self, id: &str) -> PathBuf {
+        self.profiles_dir.join(format!("{}.toml", id))
+    }
+
+    /// Serialize and write a profile to disk.
+    fn write_profile(&self, profile: &Profile) -> Result<()> {
+        let content = profile.to_toml()?;
+        write_atomic(&self.profile_path(&profile.id().to_string()), &content)
+    }
+}
+
+/// Write a file atomically with 0600 permissions.
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let tmp = path.with_extension("tmp");
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&tmp)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
@@ -5,9 +5,31 @@ use tracing::{debug, error, info};
 use zbus::{Connection, Result as ZbusResult};
 
 use crate::models::{
-    Error, ExecutionResult, Profile, Result, DBUS_OBJECT_PATH, DBUS_SERVICE_NAME,
+    Error, ExecutionResult, Profile, Result, SandboxMode, DBUS_OBJECT_PATH, DBUS_SERVICE_NAME,
 };
 
+/// Map a failed daemon method call to a model error.
+///
+/// Polkit denials and a missing daemon get their own variants so callers can
+/// tell "not allowed" apart from "not available".
+fn method_error(err: zbus::Error, fallback: impl FnOnce(String) -> Error) -> Error {
+    if let zbus::Error::MethodError(name, detail, _) = &err {
+        let detail = detail.clone().unwrap_or_default();
+        match name.as_str() {
+            "org.freedesktop.DBus.Error.AccessDenied" => return Error::AuthorizationDenied(detail),
+            "org.freedesktop.DBus.Error.ServiceUnknown"
+            | "org.freedesktop.DBus.Error.NameHasNoOwner" => return Error::DaemonNotRunning,
+            _ => {}
+        }
+    }
+    fallback(err.to_string())
+}
+
+/// Whether a D-Bus error reply says the profile does not exist.
+fn is_not_found(err: &zbus::Error) -> bool {
+    matches!(err, zbus::Error::MethodError(name, _, _) if name.as_str() == "org.freedesktop.DBus.Error.UnknownObject")
+}
+
 /// D-Bus client for the Network Manager daemon.
 #[allow(dead_code)]
 #[derive(Clone)]
@@ -114,8 +136,11 @@ impl DaemonClient {
         }
     }
 
-    /// Activate a profile by ID.
-    pub async fn activate_profile(&self, id: &str) -> Result<ExecutionResult> {
+    /// Activate a profile by ID, asking for its scripts to run under `sandbox`.
+    ///
+    /// The daemon refuses when its own sandbox mode differs from `sandbox`
+    /// (unless that is [`SandboxMode::None`]) or is not available.
+    pub async fn activate_profile(&self, id: &str, sandbox: SandboxMode) -> Result<ExecutionResult> {
         let conn = self.connection.as_ref().ok_or(Error::DaemonNotRunning)?;
 
         info!("Requesting profile activation: {}", id);
@@ -126,7 +151,7 @@ impl DaemonClient {
                 DBUS_OBJECT_PATH,
                 Some("com.chrisdaggas.NetworkManager.Manager"),
                 "ActivateProfile",
-                &(id,),
+                &(id, sandbox.as_str()),
             )
             .await
             .map(|reply| reply.body().deserialize().unwrap_or_default());
@@ -136,16 +161,41 @@ impl DaemonClient {
                 let execution_result: ExecutionResult = serde_json::from_str(&json)?;
                 Ok(execution_result)
             }
+            Err(e) if is_not_found(&e) => Err(Error::ProfileNotFound(id.to_string())),
             Err(e) => {
                 error!("Profile activation failed: {}", e);
-                Err(Error::ActionFailed {
+                Err(method_error(e, |reason| Error::ActionFailed {
                     action: "ActivateProfile".to_string(),
-                    reason: e.to_string(),
-                })
+                    reason,
+                }))
             }
         }
     }
 
+    /// Keep (`keep = true`) or revert the network changes of a pending checkpoint.
+    pub async fn finish_checkpoint(&self, checkpoint: &str, keep: bool) -> Result<()> {
+        let conn = self.connection.as_ref().ok_or(Error::DaemonNotRunning)?;
+        let method = if keep { "ConfirmCheckpoint" } else { "RollbackCheckpoint" };
+
+        let result: ZbusResult<()> = conn
+            .call_method(
+                Some(DBUS_SERVICE_NAME),
+                DBUS_OBJECT_PATH,
+                Some("com.chrisdaggas.NetworkManager.Manager"),
+                method,
+                &(checkpoint,),
+            )
+            .await
+            .map(|_| ());
+
+        result.map_err(|e| {
+            method_error(e, |reason| Error::ActionFailed {
+                action: method.to_string(),
+                reason,
+            })
+        })
+    }
+
     /// Create a new profile.
     pub async fn create_profile(&self, profile: &Profile) -> Result<()> {
         let conn = self.connection.as_ref().ok_or(Error::DaemonNotRunning)?;
@@ -163,7 +213,7 @@ impl DaemonClient {
             .await
             .map(|_| ());
 
-        result.map_err(|e| Error::Dbus(e.to_string()))
+        result.map_err(|e| method_error(e, Error::Dbus))
     }
 
     /// Update an existing profile.
@@ -183,7 +233,13 @@ impl DaemonClient {
             .await
             .map(|_| ());
 
-        result.map_err(|e| Error::Dbus(e.to_string()))
+        result.map_err(|e| {
+            if is_not_found(&e) {
+                Error::ProfileNotFound(profile.id().to_string())
+            } else {
+                method_error(e, Error::Dbus)
+            }
+        })
     }
 
     /// Delete a profile.
@@ -201,7 +257,21 @@ impl DaemonClient {
             .await
             .map(|_| ());
 
-        result.map_err(|e| Error::Dbus(e.to_string()))
+        result.map_err(|e| method_error(e, Error::Dbus))
+    }
+
+    /// Store a profile in the daemon, creating it if the daemon has none.
+    ///
+    /// This needs the manage-profiles authorization, so it is only done
+    /// when the user saves a profile; activation uses the daemon's copy.
+    pub async fn save_profile(&self, profile: &Profile) -> Result<()> {
+        match self.update_profile(profile).await {
+            Err(Error::ProfileNotFound(_)) => {
+                info!("Registering profile '{}' with the daemon", profile.name());
+                self.create_profile(profile).await
+            }
+            result => result,
+        }
     }
 
     /// Get the current active profile ID.
@@ -0,0 +1,356 @@
+// Network Manager - Automation Action Execution
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Automation actions: scripts, programs, process signals, wait
+//! conditions and desktop notifications.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+use zbus::blocking::Connection;
+use zbus::proxy;
+use zbus::zvariant::Value;
+
+use super::{ActionOutcome, ScriptContext};
+use crate::models::actions::{Action, KillSignal, WaitCondition};
+use crate::models::plan::{ChangeKind, PlannedChange};
+use crate::models::{AutomationAction, Error, Result, APP_ID};
+use crate::nm::NmClient;
+use crate::services::integrity;
+use crate::services::sandbox::{SandboxError, ScriptCommand};
+use crate::services::SandboxRunner;
+
+/// How often wait conditions are checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications",
+    gen_async = false
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+}
+
+/// Run an automation action.
+pub(super) fn run(action: &AutomationAction, sandbox: &SandboxRunner, context: &ScriptContext) -> Result<ActionOutcome> {
+    match action {
+        AutomationAction::PreScript { path, args, env, mode, working_dir, sandbox: access, approved_sha256, .. }
+        | AutomationAction::PostScript { path, args, env, mode, working_dir, sandbox: access, approved_sha256, .. } => {
+            // The verified copy runs, never the file that was checked.
+            let verified = integrity::verify(path, approved_sha256.as_deref())?;
+            // The context comes last so a profile cannot misreport it.
+            let mut env = env.clone();
+            env.extend(context.env());
+            let label = format!("{}: {} {}", context.profile_name, action.name(), path.display());
+            let script = ScriptCommand {
+                path: &verified,
+                args,
+                env: &env,
+                working_dir: working_dir.as_deref(),
+                mode,
+                sandbox: access,
+                label: &label,
+            };
+            run_script(sandbox, path, &script)
+        }
+
+        AutomationAction::RunProgram { program, args, working_dir, pin_digest, approved_sha256, .. } => {
+            // A pinned program runs from the copy that was verified.
+            let executable = if *pin_digest {
+                let path = integrity::resolve(program).ok_or_else(|| Error::ScriptNotFound(program.clone()))?;
+                integrity::verify(&path, approved_sha256.as_deref())?
+            } else {
+                program.into()
+            };
+
+            // Programs are session actions: the application launches them
+            // as the user, so they can reach the desktop and are not sandboxed.
+            let mut cmd = Command::new(executable);
+            cmd.args(args);
+            if let Some(wd) = working_dir {
+                cmd.current_dir(wd);
+            }
+
+            let mut child = cmd
+                .spawn()
+                .map_err(|e| Error::action_failed(action.name(), format!("Failed to launch program: {}", e)))?;
+            // Reap the program once it exits so it does not linger as a zombie.
+            let program = program.clone();
+            thread::spawn(move || match child.wait() {
+                Ok(status) => info!("Program {} exited with {}", program, status),
+                Err(e) => warn!("Failed to wait for program {}: {}", program, e),
+            });
+            Ok(ActionOutcome::Applied)
+        }
+
+        AutomationAction::KillProgram { program_name, signal } => kill_program(action, program_name, signal),
+
+        AutomationAction::WaitFor { condition, timeout_seconds } => wait_for(action, condition, *timeout_seconds),
+
+        AutomationAction::Notification { title, body, icon } => notify(action, title, body, icon.as_deref()),
+    }
+}
+
+/// Run a script and keep its output for the log, reporting it as `name`.
+fn run_script(sandbox: &SandboxRunner, name: &Path, script: &ScriptCommand) -> Result<ActionOutcome> {
+    let path = name.display().to_string();
+    let output = sandbox.execute(script).map_err(|e| match e {
+        SandboxError::TimedOut(seconds) => Error::ActionTimeout(format!("{} after {}s", path, seconds)),
+        SandboxError::ScriptNotFound(path) => Error::ScriptNotFound(path),
+        e => Error::script_failed(&path, e.to_string()),
+    })?;
+
+    match output.status {
+        // Background scripts report their exit in the daemon log.
+        None => Ok(ActionOutcome::Applied),
+        Some(status) if status.success() => {
+            let combined = output.combined();
+            Ok(if combined.is_empty() { ActionOutcome::Applied } else { ActionOutcome::Output(combined) })
+        }
+        Some(status) => {
+            let combined = output.combined();
+            let reason = if combined.is_empty() { status.to_string() } else { format!("{}\n{}", status, combined) };
+            Err(Error::script_failed(&path, reason))
+        }
+    }
+}
+
+/// The signal number of a kill signal.
+fn signal_number(signal: &KillSignal) -> libc::c_int {
+    match signal {
+        KillSignal::Sigterm => libc::SIGTERM,
+        KillSignal::Sigkill => libc::SIGKILL,
+        KillSignal::Sighup => libc::SIGHUP,
+        KillSignal::Sigint => libc::SIGINT,
+    }
+}
+
+/// Whether the process in a `/proc/<pid>` directory matches a program.
+///
+/// A path is compared with the executable and `argv[0]`; a bare name with
+/// their file names and the process name (which the kernel truncates to
+/// 15 bytes).
+fn process_matches(proc_dir: &Path, program: &str) -> bool {
+    let exe = fs::read_link(proc_dir.join("exe")).ok();
+    let cmdline = fs::read(proc_dir.join("cmdline")).unwrap_or_default();
+    let argv0 = cmdline.split(|b| *b == 0).next().map(|a| String::from_utf8_lossy(a).into_owned());
+
+    if program.contains('/') {
+        return exe.is_some_and(|e| e == Path::new(program)) || argv0.as_deref() == Some(program);
+    }
+    let file_name = |path: &Path| path.file_name().is_some_and(|n| n == program);
+    let comm = fs::read_to_string(proc_dir.join("comm")).unwrap_or_default();
+    let truncated = program.get(..program.len().min(15)).unwrap_or(program);
+    exe.as_deref().is_some_and(file_name)
+        || argv0.as_deref().is_some_and(|a| file_name(Path::new(a)))
+        || comm.trim_end() == truncated
+}
+
+/// PIDs of the running processes of this user matching a program, except
+/// this one.
+fn matching_processes(program: &str) -> Vec<i32> {
+    let own = std::process::id() as i32;
+    // SAFETY: geteuid() has no preconditions.
+    let uid = unsafe { libc::geteuid() };
+    let owned = |dir: &Path| fs::metadata(dir).is_ok_and(|m| m.uid() == uid);
+    let Ok(entries) = fs::read_dir("/proc") else { return Vec::new() };
+    entries
+        .flatten()
+        .filter_map(|entry| Some((entry.file_name().to_str()?.parse::<i32>().ok()?, entry.path())))
+        .filter(|(pid, dir)| *pid != own && owned(dir) && process_matches(dir, program))
+        .map(|(pid, _)| pid)
+        .collect()
+}
+
+/// Send a signal to every process matching a program.
+fn kill_program(action: &AutomationAction, program: &str, signal: &KillSignal) -> Result<ActionOutcome> {
+    if program.trim().is_empty() {
+        return Err(Error::ValidationFailed("No program to kill".to_string()));
+    }
+    let pids = matching_processes(program);
+    if pids.is_empty() {
+        return Ok(ActionOutcome::Skipped(format!("No running process matches {}", program)));
+    }
+
+    let mut errors = Vec::new();
+    for pid in &pids {
+        // SAFETY: kill() only takes plain integers.
+        if unsafe { libc::kill(*pid, signal_number(signal)) } != 0 {
+            let e = io::Error::last_os_error();
+            // A process that exited in the meantime needs no signal.
+            if e.raw_os_error() != Some(libc::ESRCH) {
+                errors.push(format!("{}: {}", pid, e));
+            }
+        }
+    }
+    match errors.len() {
+        0 => Ok(ActionOutcome::Applied),
+        n if n == pids.len() => Err(Error::action_failed(action.name(), errors.join(", "))),
+        _ => Ok(ActionOutcome::Warning(format!("Some processes could not be signalled: {}", errors.join(", ")))),
+    }
+}
+
+/// Whether a host answers a ping.
+fn host_reachable(host: &str) -> bool {
+    Command::new("ping")
+        .args(["-c", "1", "-W", "1", "--", host])
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// Wait until a condition holds, checking it every [`POLL_INTERVAL`].
+fn wait_for(action: &AutomationAction, condition: &WaitCondition, timeout_seconds: u32) -> Result<ActionOutcome> {
+    let timeout = Duration::from_secs(u64::from(timeout_seconds));
+    let timed_out = || Error::ActionTimeout(format!("{} after {}s", action.description(), timeout_seconds));
+
+    let check: Box<dyn Fn() -> Result<bool>> = match condition {
+        WaitCondition::Duration { seconds } => {
+            let wanted = Duration::from_secs(u64::from(*seconds));
+            thread::sleep(wanted.min(timeout));
+            return if wanted > timeout { Err(timed_out()) } else { Ok(ActionOutcome::Applied) };
+        }
+        WaitCondition::NetworkUp => {
+            let nm = NmClient::connect()?;
+            Box::new(move || nm.is_online())
+        }
+        WaitCondition::HostReachable { host } => {
+            if host.trim().is_empty() {
+                return Err(Error::ValidationFailed("No host to wait for".to_string()));
+            }
+            Box::new(move || Ok(host_reachable(host)))
+        }
+        WaitCondition::FileExists { path } => Box::new(move || Ok(path.exists())),
+    };
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if check()? {
+            return Ok(ActionOutcome::Applied);
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(timed_out());
+        }
+        thread::sleep(POLL_INTERVAL.min(remaining));
+    }
+}
+
+/// Show a desktop notification in the user session.
+fn notify(action: &AutomationAction, title: &str, body: &str, icon: Option<&str>) -> Result<ActionOutcome> {
+    // Without a desktop session (e.g. at boot) the profile still applies.
+    let Ok(connection) = Connection::session() else {
+        return Ok(ActionOutcome::Warning("No desktop session to notify".to_string()));
+    };
+    let failed = |e: zbus::Error| Error::action_failed(action.name(), e.to_string());
+    let notifications = NotificationsProxy::new(&connection).map_err(failed)?;
+    let hints = HashMap::from([("desktop-entry", Value::from(APP_ID))]);
+    notifications
+        .notify("Network Manager", 0, icon.unwrap_or(APP_ID), title, body, &[], hints, -1)
+        .map_err(failed)?;
+    Ok(ActionOutcome::Applied)
+}
+
+/// Describe what an automation action would do.
+///
+/// Scripts and programs have no readable current state, so they always
+/// count as a change.
+pub(super) fn plan(action: &AutomationAction) -> PlannedChange {
+    let wrapped = Action::Automation(action.clone());
+    match action {
+        AutomationAction::PreScript { path, .. } | AutomationAction::PostScript { path, .. } if !path.exists() => {
+            PlannedChange::unsupported(wrapped, format!("Script not found: {}", path.display()))
+        }
+        AutomationAction::KillProgram { program_name, .. } => {
+            let running = matching_processes(program_name).len();
+            if running == 0 {
+                let current = format!("No running process matches {}", program_name);
+                PlannedChange::new(wrapped, ChangeKind::NoOp, Some(current), action.description())
+            } else {
+                let current = format!("{} running process(es) match {}", running, program_name);
+                PlannedChange::new(wrapped, ChangeKind::Change, Some(current), action.description())
+            }
+        }
+        _ => {
+            let desired = action.description();
+            PlannedChange::new(wrapped, ChangeKind::Change, None, desired)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_matching() {
+        let own = Path::new("/proc/self");
+        let exe = fs::read_link("/proc/self/exe").unwrap();
+        let name = exe.file_name().unwrap().to_str().unwrap();
+
+        assert!(process_matches(own, name));
+        assert!(process_matches(own, exe.to_str().unwrap()));
+        assert!(!process_matches(own, "definitely-not-running"));
+        assert!(!process_matches(own, &format!("/nonexistent/{}", name)));
+        // The test process itself is never a target.
+        assert!(matching_processes(name).is_empty());
+    }
+
+    #[test]
+    fn test_failed_script_keeps_output() {
+        use crate::models::{SandboxMode, ScriptMode, ScriptSandbox};
+        use crate::test_support::temp_script;
+
+        let path = temp_script("echo 'mounting shares'\necho 'no route' >&2\nexit 3", 0o755);
+
+        let env = HashMap::new();
+        let sandbox = ScriptSandbox::default();
+        let script = ScriptCommand {
+            path: &path,
+            args: &[],
+            env: &env,
+            working_dir: None,
+            mode: &ScriptMode::Wait,
+            sandbox: &sandbox,
+            label: "test",
+        };
+        let Err(Error::ScriptFailed { reason, .. }) = run_script(&SandboxRunner::new(SandboxMode::None), &path, &script)
+        else {
+            panic!("the script should fail");
+        };
+        assert!(reason.contains("mounting shares"));
+        assert!(reason.contains("no route"));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_wait_duration_timeout() {
+        let wait = |seconds, timeout_seconds| {
+            let condition = WaitCondition::Duration { seconds };
+            let action = AutomationAction::WaitFor { condition: condition.clone(), timeout_seconds };
+            wait_for(&action, &condition, timeout_seconds)
+        };
+        assert!(matches!(wait(0, 1), Ok(ActionOutcome::Applied)));
+        assert!(matches!(wait(5, 0), Err(Error::ActionTimeout(_))));
+    }
+}
@@ -0,0 +1,295 @@
+// Network Manager - Session Environment
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Environment variables for the user session.
+//!
+//! Variables are written to a managed file in `environment.d`, read by the
+//! systemd user manager at login, and pushed into the running session with
+//! `SetEnvironment` on the user bus so newly started programs see them
+//! right away. Switching to another profile removes them again.
+//!
+//! The daemon can reach neither the user's home nor their session bus, so
+//! these are session actions, applied by the application itself.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use zbus::blocking::Connection;
+use zbus::proxy;
+
+use super::{remove_config, write_config, ActionOutcome};
+use crate::models::actions::Action;
+use crate::models::plan::{ChangeKind, PlannedChange};
+use crate::models::validation::validate_env_variable;
+use crate::models::{Error, Result, SystemAction};
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1",
+    gen_async = false
+)]
+trait SystemdManager {
+    fn set_environment(&self, assignments: &[String]) -> zbus::Result<()>;
+
+    fn unset_environment(&self, names: &[String]) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn environment(&self) -> zbus::Result<Vec<String>>;
+}
+
+/// Build an action failure for environment variables.
+fn failed(reason: impl Into<String>) -> Error {
+    Error::action_failed("Environment Variables", reason)
+}
+
+/// The managed file in the user's `environment.d`.
+fn environment_file() -> Result<PathBuf> {
+    dirs::config_dir()
+        .map(|d| d.join("environment.d").join("70-cd-network-manager.conf"))
+        .ok_or_else(|| failed("No configuration directory"))
+}
+
+/// Validated variables of an action, sorted by name.
+fn variables(action: &SystemAction) -> Result<BTreeMap<String, String>> {
+    let SystemAction::EnvironmentVariables { variables } = action else {
+        return Err(failed("Not an environment action"));
+    };
+    for (key, value) in variables {
+        validate_env_variable(key, value)?;
+    }
+    Ok(variables.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+}
+
+/// Quote a value for `environment.d`.
+///
+/// Inside double quotes a backslash escapes `"`, `\` and `` ` ``; `$`
+/// starts a variable reference even there and is written as `$$`.
+fn quote(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' | '\\' | '`' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '$' => quoted.push_str("$$"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Reverse [`quote`].
+fn unquote(quoted: &str) -> String {
+    let inner = quoted.strip_prefix('"').and_then(|q| q.strip_suffix('"')).unwrap_or(quoted);
+    let mut value = String::new();
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.extend(chars.next()),
+            '$' if chars.peek() == Some(&'$') => {
+                chars.next();
+                value.push('$');
+            }
+            _ => value.push(c),
+        }
+    }
+    value
+}
+
+/// Parse the managed file.
+fn parse(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), unquote(v.trim())))
+        .collect()
+}
+
+/// Render the managed file.
+fn render(variables: &BTreeMap<String, String>) -> String {
+    let mut content = "# Written by CD Network Manager; changed when switching profiles.\n".to_string();
+    for (key, value) in variables {
+        content.push_str(&format!("{}={}\n", key, quote(value)));
+    }
+    content
+}
+
+/// Variables in the managed file.
+fn read_file() -> Result<BTreeMap<String, String>> {
+    let path = environment_file()?;
+    match fs::read_to_string(&path) {
+        Ok(content) => Ok(parse(&content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(failed(format!("Failed to read {}: {}", path.display(), e))),
+    }
+}
+
+/// Replace the managed file, removing it when no variables are left.
+fn write_file(variables: &BTreeMap<String, String>) -> Result<()> {
+    let path = environment_file()?;
+    let result = if variables.is_empty() {
+        remove_config(&path)
+    } else {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| failed(e.to_string()))?;
+        }
+        write_config(&path, &render(variables))
+    };
+    result.map_err(|e| failed(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// The systemd user manager, if there is a session bus.
+fn session_manager() -> Option<SystemdManagerProxy<'static>> {
+    let connection = Connection::session().ok()?;
+    SystemdManagerProxy::new(&connection).ok()
+}
+
+/// Values of variables in the running session (`None` if unset).
+fn session_values(manager: &SystemdManagerProxy, keys: &[String]) -> Result<Vec<(String, Option<String>)>> {
+    let environment = manager.environment().map_err(|e| failed(e.to_string()))?;
+    Ok(keys
+        .iter()
+        .map(|key| {
+            let prefix = format!("{}=", key);
+            let value = environment.iter().find_map(|e| e.strip_prefix(&prefix).map(str::to_string));
+            (key.clone(), value)
+        })
+        .collect())
+}
+
+/// Set and unset variables in the running session.
+fn update_session(manager: &SystemdManagerProxy, values: &[(String, Option<String>)]) -> Result<()> {
+    let set: Vec<String> =
+        values.iter().filter_map(|(k, v)| v.as_ref().map(|v| format!("{}={}", k, v))).collect();
+    let unset: Vec<String> = values.iter().filter(|(_, v)| v.is_none()).map(|(k, _)| k.clone()).collect();
+    if !set.is_empty() {
+        manager.set_environment(&set).map_err(|e| failed(e.to_string()))?;
+    }
+    if !unset.is_empty() {
+        manager.unset_environment(&unset).map_err(|e| failed(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Outcome once the file is written; the running session is updated if
+/// there is one to update.
+fn session_outcome(values: &[(String, Option<String>)]) -> Result<ActionOutcome> {
+    match session_manager() {
+        Some(manager) => {
+            update_session(&manager, values)?;
+            Ok(ActionOutcome::Applied)
+        }
+        None => Ok(ActionOutcome::Warning("No user session bus; variables take effect at the next login".to_string())),
+    }
+}
+
+/// Add an action's variables to the managed file and the running session.
+pub(super) fn apply(action: &SystemAction) -> Result<ActionOutcome> {
+    let variables = variables(action)?;
+    let mut file = read_file()?;
+    file.extend(variables.clone());
+    write_file(&file)?;
+    let values: Vec<_> = variables.into_iter().map(|(k, v)| (k, Some(v))).collect();
+    session_outcome(&values)
+}
+
+/// Remove an action's variables from the managed file and the session.
+pub(super) fn release(action: &SystemAction) -> Result<ActionOutcome> {
+    let variables = variables(action)?;
+    let mut file = read_file()?;
+    file.retain(|k, _| !variables.contains_key(k));
+    write_file(&file)?;
+    let values: Vec<_> = variables.into_keys().map(|k| (k, None)).collect();
+    session_outcome(&values)
+}
+
+/// The managed file and session values before an environment action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvironmentSnapshot {
+    /// Variables in the managed file.
+    file: BTreeMap<String, String>,
+    /// Session values of the action's variables, if there was a session.
+    session: Option<Vec<(String, Option<String>)>>,
+}
+
+impl fmt::Display for EnvironmentSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.file.is_empty() {
+            return write!(f, "No managed variables");
+        }
+        let lines: Vec<_> = self.file.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Capture the managed file and the session values an action changes.
+pub(super) fn capture(action: &SystemAction) -> Result<EnvironmentSnapshot> {
+    let keys: Vec<String> = variables(action)?.into_keys().collect();
+    let session = match session_manager() {
+        Some(manager) => Some(session_values(&manager, &keys)?),
+        None => None,
+    };
+    Ok(EnvironmentSnapshot { file: read_file()?, session })
+}
+
+/// Put back the managed file and the session values.
+pub(super) fn restore(snapshot: &EnvironmentSnapshot) -> Result<()> {
+    write_file(&snapshot.file)?;
+    if let (Some(values), Some(manager)) = (&snapshot.session, session_manager()) {
+        update_session(&manager, values)?;
+    }
+    Ok(())
+}
+
+/// Describe what an environment action would change in the managed file.
+pub(super) fn plan(action: &SystemAction) -> PlannedChange {
+    let wrapped = Action::System(action.clone());
+    let (variables, file) = match variables(action).and_then(|v| Ok((v, read_file()?))) {
+        Ok(state) => state,
+        Err(e) => return PlannedChange::unsupported(wrapped, e.to_string()),
+    };
+
+    let describe = |value: Option<&String>, key: &str| match value {
+        Some(value) => format!("{}={}", key, value),
+        None => format!("{} unset", key),
+    };
+    let current: Vec<_> = variables.keys().map(|k| describe(file.get(k), k)).collect();
+    let desired: Vec<_> = variables.iter().map(|(k, v)| describe(Some(v), k)).collect();
+    let kind = if current == desired { ChangeKind::NoOp } else { ChangeKind::Change };
+    PlannedChange::new(wrapped, kind, Some(current.join("\n")), desired.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_round_trip() {
+        for value in ["plain", "with space", "$HOME/bin", "say \"hi\"", "back\\slash`tick`", ""] {
+            assert_eq!(unquote(&quote(value)), value);
+        }
+        assert_eq!(quote("$HOME"), "\"$$HOME\"");
+
+        let variables: BTreeMap<_, _> =
+            [("EDITOR".to_string(), "vim".to_string()), ("PS_X".to_string(), "a=\"b\"".to_string())].into();
+        assert_eq!(parse(&render(&variables)), variables);
+    }
+
+    #[test]
+    fn test_variables_are_validated() {
+        let action = |key: &str, value: &str| SystemAction::EnvironmentVariables {
+            variables: [(key.to_string(), value.to_string())].into(),
+        };
+        assert!(variables(&action("GOPATH", "/opt/go")).is_ok());
+        assert!(variables(&action("1BAD", "x")).is_err());
+        assert!(variables(&action("BAD-NAME", "x")).is_err());
+        assert!(variables(&action("MULTI", "a\nb")).is_err());
+    }
+}
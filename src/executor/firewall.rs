@@ -0,0 +1,614 @@
+// Network Manager - Firewall Configuration
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Firewall configuration through the firewalld D-Bus API, or a managed
+//! nftables table on systems where firewalld is not running.
+//!
+//! firewalld changes go to the runtime configuration (except the default
+//! zone, which firewalld always persists), so a firewalld reload returns to
+//! the permanent configuration. The nftables table is replaced as a whole,
+//! and carries a digest of the ruleset it was written from.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use sha2::{Digest, Sha256};
+use zbus::blocking::fdo::DBusProxy;
+use zbus::blocking::Connection;
+use zbus::names::BusName;
+use zbus::proxy;
+
+use super::{command_stdout, ActionOutcome};
+use crate::models::actions::Action;
+use crate::models::plan::{ChangeKind, PlannedChange};
+use crate::models::validation::validate_firewall_config;
+use crate::models::{Error, FirewallConfig, FirewallPort, PortProtocol, Result, SystemAction};
+
+/// Well-known bus name of firewalld.
+const FIREWALLD_SERVICE: &str = "org.fedoraproject.FirewallD1";
+
+/// Name of the nftables table managed when firewalld is not running.
+const NFT_TABLE: &str = "cd_network_manager";
+
+/// Prefix of the table comment holding the digest of the ruleset it was
+/// written from.
+const NFT_DIGEST_PREFIX: &str = "sha256:";
+
+/// Service name database used to resolve services without firewalld.
+const SERVICES_FILE: &str = "/etc/services";
+
+#[proxy(
+    interface = "org.fedoraproject.FirewallD1",
+    default_service = "org.fedoraproject.FirewallD1",
+    default_path = "/org/fedoraproject/FirewallD1",
+    gen_async = false
+)]
+trait FirewallD1 {
+    #[zbus(name = "getDefaultZone")]
+    fn get_default_zone(&self) -> zbus::Result<String>;
+
+    #[zbus(name = "setDefaultZone")]
+    fn set_default_zone(&self, zone: &str) -> zbus::Result<()>;
+}
+
+#[proxy(
+    interface = "org.fedoraproject.FirewallD1.zone",
+    default_service = "org.fedoraproject.FirewallD1",
+    default_path = "/org/fedoraproject/FirewallD1",
+    gen_async = false
+)]
+trait FirewallZone {
+    #[zbus(name = "getZoneOfInterface")]
+    fn get_zone_of_interface(&self, interface: &str) -> zbus::Result<String>;
+
+    #[zbus(name = "changeZoneOfInterface")]
+    fn change_zone_of_interface(&self, zone: &str, interface: &str) -> zbus::Result<String>;
+
+    #[zbus(name = "removeInterface")]
+    fn remove_interface(&self, zone: &str, interface: &str) -> zbus::Result<String>;
+
+    #[zbus(name = "queryPort")]
+    fn query_port(&self, zone: &str, port: &str, protocol: &str) -> zbus::Result<bool>;
+
+    #[zbus(name = "addPort")]
+    fn add_port(&self, zone: &str, port: &str, protocol: &str, timeout: i32) -> zbus::Result<String>;
+
+    #[zbus(name = "removePort")]
+    fn remove_port(&self, zone: &str, port: &str, protocol: &str) -> zbus::Result<String>;
+
+    #[zbus(name = "queryService")]
+    fn query_service(&self, zone: &str, service: &str) -> zbus::Result<bool>;
+
+    #[zbus(name = "addService")]
+    fn add_service(&self, zone: &str, service: &str, timeout: i32) -> zbus::Result<String>;
+
+    #[zbus(name = "removeService")]
+    fn remove_service(&self, zone: &str, service: &str) -> zbus::Result<String>;
+
+    #[zbus(name = "queryRichRule")]
+    fn query_rich_rule(&self, zone: &str, rule: &str) -> zbus::Result<bool>;
+
+    #[zbus(name = "addRichRule")]
+    fn add_rich_rule(&self, zone: &str, rule: &str, timeout: i32) -> zbus::Result<String>;
+
+    #[zbus(name = "removeRichRule")]
+    fn remove_rich_rule(&self, zone: &str, rule: &str) -> zbus::Result<String>;
+}
+
+/// Map a zbus error to a firewalld error, keeping firewalld's own message.
+fn firewalld_error(err: zbus::Error) -> Error {
+    match err {
+        zbus::Error::MethodError(_, Some(detail), _) => Error::Dbus(format!("firewalld: {}", detail)),
+        other => Error::Dbus(format!("firewalld: {}", other)),
+    }
+}
+
+/// Build an action failure for a firewall action.
+fn failed(reason: impl Into<String>) -> Error {
+    Error::action_failed("Firewall Config", reason)
+}
+
+/// A rule the default zone gains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rule {
+    /// Open port or port range.
+    Port(FirewallPort),
+    /// Allowed firewalld service.
+    Service(String),
+    /// Rich rule.
+    Rich(String),
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Port(port) => write!(f, "port {}", port),
+            Self::Service(service) => write!(f, "service {}", service),
+            Self::Rich(rule) => write!(f, "{}", rule.trim()),
+        }
+    }
+}
+
+/// Rules a configuration adds to its default zone.
+fn rules(config: &FirewallConfig) -> Vec<Rule> {
+    let ports = config.open_ports.iter().cloned().map(Rule::Port);
+    let services = config.services.iter().cloned().map(Rule::Service);
+    let rich_rules = config.rich_rules.iter().cloned().map(Rule::Rich);
+    ports.chain(services).chain(rich_rules).collect()
+}
+
+/// firewalld runtime state a configuration touches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneState {
+    /// Default zone.
+    default_zone: String,
+    /// Zone of each configured interface (empty if none), sorted by interface.
+    interfaces: Vec<(String, String)>,
+    /// Zone the rules are checked in.
+    zone: String,
+    /// The configuration's rules and whether the zone has them.
+    rules: Vec<(Rule, bool)>,
+}
+
+impl fmt::Display for ZoneState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "default zone: {}", self.default_zone)?;
+        for (interface, zone) in &self.interfaces {
+            write!(f, "\n{}: {}", interface, if zone.is_empty() { "no zone" } else { zone })?;
+        }
+        for (rule, enabled) in &self.rules {
+            let state = if *enabled { "enabled" } else { "disabled" };
+            write!(f, "\n{} in {}: {}", rule, self.zone, state)?;
+        }
+        Ok(())
+    }
+}
+
+impl ZoneState {
+    /// The state a configuration leaves behind.
+    fn desired(config: &FirewallConfig) -> Self {
+        let interfaces: BTreeMap<_, _> = config.interface_zones.clone().into_iter().collect();
+        Self {
+            default_zone: config.default_zone.clone(),
+            interfaces: interfaces.into_iter().collect(),
+            zone: config.default_zone.clone(),
+            rules: rules(config).into_iter().map(|rule| (rule, true)).collect(),
+        }
+    }
+}
+
+/// Blocking firewalld client.
+struct Firewalld {
+    conn: Connection,
+}
+
+impl Firewalld {
+    /// Connect to firewalld if it is running.
+    ///
+    /// Checks for the bus name instead of calling firewalld, which would
+    /// D-Bus activate a firewalld the administrator chose not to run.
+    fn connect() -> Result<Option<Self>> {
+        let conn = Connection::system().map_err(|e| Error::DbusConnectionFailed(e.to_string()))?;
+        let name = BusName::try_from(FIREWALLD_SERVICE).map_err(|e| Error::Dbus(e.to_string()))?;
+        let running = DBusProxy::new(&conn)?.name_has_owner(name).map_err(zbus::Error::from)?;
+        Ok(running.then_some(Self { conn }))
+    }
+
+    fn main(&self) -> Result<FirewallD1Proxy<'_>> {
+        FirewallD1Proxy::new(&self.conn).map_err(firewalld_error)
+    }
+
+    fn zones(&self) -> Result<FirewallZoneProxy<'_>> {
+        FirewallZoneProxy::new(&self.conn).map_err(firewalld_error)
+    }
+
+    /// Current state of everything a configuration touches.
+    fn state(&self, config: &FirewallConfig) -> Result<ZoneState> {
+        let zones = self.zones()?;
+        let mut interfaces = Vec::new();
+        for interface in config.interface_zones.keys() {
+            let zone = zones.get_zone_of_interface(interface).map_err(firewalld_error)?;
+            interfaces.push((interface.clone(), zone));
+        }
+        interfaces.sort();
+
+        let zone = config.default_zone.clone();
+        let mut states = Vec::new();
+        for rule in rules(config) {
+            let enabled = self.has_rule(&zones, &zone, &rule)?;
+            states.push((rule, enabled));
+        }
+        Ok(ZoneState {
+            default_zone: self.main()?.get_default_zone().map_err(firewalld_error)?,
+            interfaces,
+            zone,
+            rules: states,
+        })
+    }
+
+    fn has_rule(&self, zones: &FirewallZoneProxy<'_>, zone: &str, rule: &Rule) -> Result<bool> {
+        match rule {
+            Rule::Port(port) => zones.query_port(zone, &port.range(), port.protocol.as_str()),
+            Rule::Service(service) => zones.query_service(zone, service),
+            Rule::Rich(rule) => zones.query_rich_rule(zone, rule.trim()),
+        }
+        .map_err(firewalld_error)
+    }
+
+    fn add_rule(&self, zones: &FirewallZoneProxy<'_>, zone: &str, rule: &Rule) -> Result<()> {
+        match rule {
+            Rule::Port(port) => zones.add_port(zone, &port.range(), port.protocol.as_str(), 0),
+            Rule::Service(service) => zones.add_service(zone, service, 0),
+            Rule::Rich(rule) => zones.add_rich_rule(zone, rule.trim(), 0),
+        }
+        .map(drop)
+        .map_err(firewalld_error)
+    }
+
+    fn remove_rule(&self, zones: &FirewallZoneProxy<'_>, zone: &str, rule: &Rule) -> Result<()> {
+        match rule {
+            Rule::Port(port) => zones.remove_port(zone, &port.range(), port.protocol.as_str()),
+            Rule::Service(service) => zones.remove_service(zone, service),
+            Rule::Rich(rule) => zones.remove_rich_rule(zone, rule.trim()),
+        }
+        .map(drop)
+        .map_err(firewalld_error)
+    }
+
+    /// Move the runtime configuration to a state.
+    ///
+    /// Rules marked disabled are removed only if present, so a state
+    /// captured before an action undoes exactly what the action added.
+    fn set_state(&self, state: &ZoneState) -> Result<()> {
+        let main = self.main()?;
+        let zones = self.zones()?;
+        if main.get_default_zone().map_err(firewalld_error)? != state.default_zone {
+            main.set_default_zone(&state.default_zone).map_err(firewalld_error)?;
+        }
+        for (interface, zone) in &state.interfaces {
+            let current = zones.get_zone_of_interface(interface).map_err(firewalld_error)?;
+            match (current.is_empty(), zone.is_empty()) {
+                _ if current == *zone => {}
+                (false, true) => {
+                    zones.remove_interface(&current, interface).map_err(firewalld_error)?;
+                }
+                _ => {
+                    zones.change_zone_of_interface(zone, interface).map_err(firewalld_error)?;
+                }
+            }
+        }
+        for (rule, enabled) in &state.rules {
+            match (self.has_rule(&zones, &state.zone, rule)?, *enabled) {
+                (false, true) => self.add_rule(&zones, &state.zone, rule)?,
+                (true, false) => self.remove_rule(&zones, &state.zone, rule)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How the nftables fallback treats traffic for a zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ZoneTarget {
+    /// Accept everything ("trusted").
+    Accept,
+    /// Drop everything not allowed ("drop").
+    Drop,
+    /// Reject everything not allowed ("block").
+    Reject,
+    /// ICMP and DHCPv6 replies, then reject (every other zone).
+    Filtered,
+}
+
+impl ZoneTarget {
+    fn of(zone: &str) -> Self {
+        match zone {
+            "trusted" => Self::Accept,
+            "drop" => Self::Drop,
+            "block" => Self::Reject,
+            _ => Self::Filtered,
+        }
+    }
+
+    /// Chain holding the rules of non-default zones with this target.
+    fn chain(&self) -> &'static str {
+        match self {
+            Self::Accept => "trusted_zone",
+            Self::Drop => "drop_zone",
+            Self::Reject => "block_zone",
+            Self::Filtered => "restricted_zone",
+        }
+    }
+}
+
+/// Ports a service uses, from `/etc/services`-formatted text.
+fn service_ports(service: &str, services: &str) -> Vec<FirewallPort> {
+    services
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('#').next()?.split_whitespace();
+            let name = fields.next()?;
+            let (port, protocol) = fields.next()?.split_once('/')?;
+            if name != service && !fields.any(|alias| alias == service) {
+                return None;
+            }
+            let protocol = match protocol {
+                "tcp" => PortProtocol::Tcp,
+                "udp" => PortProtocol::Udp,
+                "sctp" => PortProtocol::Sctp,
+                _ => return None,
+            };
+            Some(FirewallPort { port: port.parse().ok()?, end_port: None, protocol })
+        })
+        .collect()
+}
+
+/// nftables rules of a zone chain.
+fn zone_rules(target: ZoneTarget, ports: &[FirewallPort]) -> Vec<String> {
+    if target == ZoneTarget::Accept {
+        return vec!["accept".to_string()];
+    }
+    let mut rules = Vec::new();
+    if target == ZoneTarget::Filtered {
+        rules.push("meta l4proto { icmp, ipv6-icmp } accept".to_string());
+        rules.push("ip6 daddr fe80::/64 udp dport 546 accept".to_string());
+    }
+    for port in ports {
+        rules.push(format!("{} dport {} accept", port.protocol.as_str(), port.range()));
+    }
+    rules.push(match target {
+        ZoneTarget::Drop => "drop".to_string(),
+        _ => "reject with icmpx admin-prohibited".to_string(),
+    });
+    rules
+}
+
+/// The managed nftables table equivalent to a configuration.
+///
+/// `services` is the content of `/etc/services`, used to resolve services.
+fn nft_ruleset(config: &FirewallConfig, services: &str) -> Result<String> {
+    if !config.rich_rules.is_empty() {
+        return Err(failed("Rich rules require firewalld, which is not running"));
+    }
+    let mut ports = config.open_ports.clone();
+    for service in &config.services {
+        let resolved = service_ports(service, services);
+        if resolved.is_empty() {
+            return Err(failed(format!("Unknown service '{}' (not in {})", service, SERVICES_FILE)));
+        }
+        ports.extend(resolved);
+    }
+
+    let mut input = vec![
+        "type filter hook input priority filter; policy accept;".to_string(),
+        "ct state established,related accept".to_string(),
+        "ct state invalid drop".to_string(),
+        "iifname \"lo\" accept".to_string(),
+    ];
+    let mut chains = BTreeMap::new();
+    let interfaces: BTreeMap<_, _> = config.interface_zones.iter().collect();
+    for (interface, zone) in interfaces {
+        if *zone == config.default_zone {
+            input.push(format!("iifname \"{}\" jump default_zone", interface));
+        } else {
+            let target = ZoneTarget::of(zone);
+            input.push(format!("iifname \"{}\" jump {}", interface, target.chain()));
+            chains.insert(target.chain(), zone_rules(target, &[]));
+        }
+    }
+    input.push("jump default_zone".to_string());
+    chains.insert("default_zone", zone_rules(ZoneTarget::of(&config.default_zone), &ports));
+
+    let mut body = String::new();
+    let mut chain = |name: &str, rules: &[String]| {
+        body.push_str(&format!("\tchain {} {{\n", name));
+        for rule in rules {
+            body.push_str(&format!("\t\t{}\n", rule));
+        }
+        body.push_str("\t}\n");
+    };
+    chain("input", &input);
+    for (name, rules) in &chains {
+        chain(name, rules);
+    }
+    let digest: String = Sha256::digest(body.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!("table inet {} {{\n\tcomment \"{}{}\"\n{}}}\n", NFT_TABLE, NFT_DIGEST_PREFIX, digest, body))
+}
+
+/// The ruleset digest a managed table (or its `nft list` output) is
+/// marked with.
+///
+/// nft lists rules in its own format, so a table is matched against a
+/// configuration by this mark rather than by its text.
+fn nft_digest(table: &str) -> Option<&str> {
+    table
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("comment \"")?.strip_prefix(NFT_DIGEST_PREFIX)?.strip_suffix('"'))
+}
+
+/// Plan replacing the managed table, listed as `current`, with `desired`.
+fn nft_plan(action: Action, current: Option<String>, desired: String) -> PlannedChange {
+    let applied = current.as_deref().and_then(nft_digest).is_some_and(|d| nft_digest(&desired) == Some(d));
+    let kind = if applied { ChangeKind::NoOp } else { ChangeKind::Change };
+    PlannedChange::new(action, kind, Some(FirewallSnapshot::Nftables(current).to_string()), desired)
+}
+
+/// The managed nftables table as nft lists it, if it exists.
+fn nft_table() -> Option<String> {
+    command_stdout("nft", &["list", "table", "inet", NFT_TABLE])
+}
+
+/// Replace the managed nftables table in one transaction (`None` removes it).
+fn nft_replace(ruleset: Option<&str>) -> Result<()> {
+    // Declaring the table first makes the delete succeed even if it is absent.
+    let script = format!("table inet {0}\ndelete table inet {0}\n{1}", NFT_TABLE, ruleset.unwrap_or_default());
+    let mut child = Command::new("nft")
+        .args(["-f", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(format!("Failed to run nft: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(script.as_bytes()).map_err(|e| failed(format!("Failed to write to nft: {}", e)))?;
+    }
+    let output = child.wait_with_output().map_err(|e| failed(format!("nft failed: {}", e)))?;
+    if !output.status.success() {
+        return Err(failed(format!("nft failed: {}", String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    Ok(())
+}
+
+/// Apply a firewall configuration.
+pub(super) fn apply(config: &FirewallConfig) -> Result<ActionOutcome> {
+    validate_firewall_config(config)?;
+    match Firewalld::connect()? {
+        Some(firewalld) => firewalld.set_state(&ZoneState::desired(config))?,
+        None => {
+            let services = std::fs::read_to_string(SERVICES_FILE).unwrap_or_default();
+            nft_replace(Some(&nft_ruleset(config, &services)?))?;
+        }
+    }
+    Ok(ActionOutcome::Applied)
+}
+
+/// Firewall state before an action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FirewallSnapshot {
+    /// firewalld runtime state.
+    Firewalld(ZoneState),
+    /// The managed nftables table, or `None` if it did not exist.
+    Nftables(Option<String>),
+}
+
+impl fmt::Display for FirewallSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Firewalld(state) => state.fmt(f),
+            Self::Nftables(Some(table)) => write!(f, "{}", table),
+            Self::Nftables(None) => write!(f, "table inet {} absent", NFT_TABLE),
+        }
+    }
+}
+
+/// Capture the firewall state a configuration is about to change.
+pub(super) fn capture(config: &FirewallConfig) -> Result<FirewallSnapshot> {
+    validate_firewall_config(config)?;
+    match Firewalld::connect()? {
+        Some(firewalld) => firewalld.state(config).map(FirewallSnapshot::Firewalld),
+        None => Ok(FirewallSnapshot::Nftables(nft_table())),
+    }
+}
+
+/// Restore a captured firewall state.
+pub(super) fn restore(snapshot: &FirewallSnapshot) -> Result<()> {
+    match snapshot {
+        FirewallSnapshot::Firewalld(state) => {
+            let firewalld = Firewalld::connect()?.ok_or_else(|| failed("firewalld is no longer running"))?;
+            firewalld.set_state(state)
+        }
+        FirewallSnapshot::Nftables(table) => nft_replace(table.as_deref()),
+    }
+}
+
+/// Describe what a firewall action would change.
+pub(super) fn plan(action: &SystemAction, config: &FirewallConfig) -> PlannedChange {
+    let wrapped = Action::System(action.clone());
+    let planned = validate_firewall_config(config).and_then(|()| match Firewalld::connect()? {
+        Some(firewalld) => {
+            let current = firewalld.state(config)?;
+            Ok(PlannedChange::compare(wrapped.clone(), Some(current.to_string()), ZoneState::desired(config).to_string()))
+        }
+        None => {
+            let services = std::fs::read_to_string(SERVICES_FILE).unwrap_or_default();
+            Ok(nft_plan(wrapped.clone(), nft_table(), nft_ruleset(config, &services)?))
+        }
+    });
+    planned.unwrap_or_else(|e| PlannedChange::unsupported(wrapped, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVICES: &str = "\
+ssh             22/tcp                          # SSH Remote Login Protocol
+domain          53/tcp
+domain          53/udp
+http            80/tcp          www             # WorldWideWeb HTTP
+";
+
+    fn config() -> FirewallConfig {
+        FirewallConfig {
+            default_zone: "public".to_string(),
+            interface_zones: [
+                ("docker0".to_string(), "trusted".to_string()),
+                ("wlan0".to_string(), "public".to_string()),
+            ]
+            .into(),
+            open_ports: vec![FirewallPort { port: 8000, end_port: Some(8100), protocol: PortProtocol::Tcp }],
+            services: vec!["www".to_string()],
+            rich_rules: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_service_ports() {
+        assert_eq!(service_ports("domain", SERVICES).len(), 2);
+        assert_eq!(service_ports("www", SERVICES)[0].port, 80);
+        assert!(service_ports("gopher", SERVICES).is_empty());
+    }
+
+    #[test]
+    fn test_nft_ruleset() {
+        let ruleset = nft_ruleset(&config(), SERVICES).unwrap();
+        assert!(ruleset.starts_with("table inet cd_network_manager {"));
+        assert!(ruleset.contains("iifname \"docker0\" jump trusted_zone"));
+        assert!(ruleset.contains("iifname \"wlan0\" jump default_zone"));
+        assert!(ruleset.contains("tcp dport 8000-8100 accept"));
+        assert!(ruleset.contains("tcp dport 80 accept"));
+        assert!(ruleset.trim_end().ends_with("}\n}"));
+
+        let mut with_rich_rule = config();
+        with_rich_rule.rich_rules.push("rule service name=\"ssh\" accept".to_string());
+        assert!(nft_ruleset(&with_rich_rule, SERVICES).is_err());
+        let mut unknown = config();
+        unknown.services.push("gopher".to_string());
+        assert!(nft_ruleset(&unknown, SERVICES).is_err());
+    }
+
+    #[test]
+    fn test_applied_nft_table_is_noop() {
+        let action = Action::System(SystemAction::FirewallConfig(config()));
+        let desired = nft_ruleset(&config(), SERVICES).unwrap();
+        assert!(nft_digest(&desired).is_some_and(|d| d.len() == 64));
+
+        // nft lists the table it was given in its own format
+        let listed = desired
+            .replace("ct state established,related accept", "ct state { established, related } accept")
+            .replace("\tchain", "\n\tchain");
+        assert_eq!(nft_plan(action.clone(), Some(listed), desired.clone()).kind, ChangeKind::NoOp);
+
+        let mut other = config();
+        other.open_ports.clear();
+        let previous = nft_ruleset(&other, SERVICES).unwrap();
+        assert_eq!(nft_plan(action.clone(), Some(previous), desired.clone()).kind, ChangeKind::Change);
+        let unmarked = desired.lines().filter(|l| !l.contains("comment")).collect::<Vec<_>>().join("\n");
+        assert_eq!(nft_plan(action.clone(), Some(unmarked), desired.clone()).kind, ChangeKind::Change);
+        assert_eq!(nft_plan(action, None, desired).kind, ChangeKind::Change);
+    }
+
+    #[test]
+    fn test_zone_state_display() {
+        let state = ZoneState::desired(&config());
+        assert_eq!(
+            state.to_string(),
+            "default zone: public\ndocker0: trusted\nwlan0: public\n\
+             port 8000-8100/tcp in public: enabled\nservice www in public: enabled"
+        );
+    }
+}
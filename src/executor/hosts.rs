@@ -0,0 +1,244 @@
+// Network Manager - Managed Hosts Entries
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! The managed block in `/etc/hosts`.
+//!
+//! Profile entries live between two marker lines owned by this module.
+//! Everything outside the block is preserved byte for byte.
+
+use std::fs;
+use std::path::Path;
+
+use super::{write_config, ActionOutcome};
+use crate::models::actions::HostsEntry;
+use crate::models::validation::validate_hosts_entry;
+use crate::models::{Error, Result, SystemAction};
+
+/// Path of the hosts file.
+const HOSTS_FILE: &str = "/etc/hosts";
+
+/// First line of the managed block.
+const BEGIN_MARKER: &str = "# BEGIN cd-network-manager managed entries (do not edit)";
+
+/// Last line of the managed block.
+const END_MARKER: &str = "# END cd-network-manager managed entries";
+
+/// Build an action failure for the hosts file.
+fn failed(reason: impl Into<String>) -> Error {
+    Error::action_failed("Hosts Entries", reason)
+}
+
+/// A hosts file split around the managed block.
+#[derive(Debug, PartialEq)]
+struct HostsFile<'a> {
+    /// Everything before the block (or the whole file without one).
+    before: &'a str,
+    /// Non-empty lines of the managed block.
+    block: Vec<&'a str>,
+    /// Everything after the block.
+    after: &'a str,
+}
+
+impl<'a> HostsFile<'a> {
+    /// Split hosts file content around the managed block.
+    fn parse(content: &'a str) -> Result<Self> {
+        let mut offset = 0;
+        let mut begin = None;
+        for line in content.split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+            match begin {
+                None if line.trim_end() == BEGIN_MARKER => begin = Some((start, offset)),
+                Some((block_start, body_start)) if line.trim_end() == END_MARKER => {
+                    // A block that ends the file without a final newline
+                    // was added to a file that had none.
+                    let mut before = &content[..block_start];
+                    if !line.ends_with('\n') {
+                        before = before.strip_suffix('\n').unwrap_or(before);
+                    }
+                    return Ok(Self {
+                        before,
+                        block: content[body_start..start].lines().filter(|l| !l.trim().is_empty()).collect(),
+                        after: &content[offset..],
+                    });
+                }
+                _ => {}
+            }
+        }
+        if begin.is_some() {
+            return Err(failed(format!("The managed block in {} has no end marker", HOSTS_FILE)));
+        }
+        Ok(Self { before: content, block: Vec::new(), after: "" })
+    }
+
+    /// The file with its managed block replaced (removed if `block` is empty).
+    ///
+    /// If the file does not end in a newline, neither does the block, so
+    /// removing it restores the original ending.
+    fn render(&self, block: &[String]) -> String {
+        let mut content = self.before.to_string();
+        if !block.is_empty() {
+            let unterminated = !content.is_empty() && !content.ends_with('\n');
+            if unterminated {
+                content.push('\n');
+            }
+            content.push_str(BEGIN_MARKER);
+            content.push('\n');
+            for line in block {
+                content.push_str(line);
+                content.push('\n');
+            }
+            content.push_str(END_MARKER);
+            if !unterminated || !self.after.is_empty() {
+                content.push('\n');
+            }
+        }
+        content.push_str(self.after);
+        content
+    }
+}
+
+/// The hosts file line of an entry.
+fn entry_line(entry: &HostsEntry) -> String {
+    let line = format!("{}\t{}", entry.ip, entry.hostnames.join(" "));
+    match &entry.comment {
+        Some(comment) => format!("{}\t# {}", line, comment.trim()),
+        None => line,
+    }
+}
+
+/// Entries of a hosts action, validated.
+fn entries(action: &SystemAction) -> Result<(&[HostsEntry], bool)> {
+    let SystemAction::HostsEntries { entries, replace_managed } = action else {
+        return Err(failed("Not a hosts action"));
+    };
+    for entry in entries {
+        validate_hosts_entry(entry)?;
+    }
+    Ok((entries, *replace_managed))
+}
+
+/// The managed block after applying an action to `current`.
+///
+/// With `replace_managed` the block holds only the action's entries;
+/// otherwise they are added to the existing ones.
+pub(super) fn merged_block(action: &SystemAction, current: &[String]) -> Result<Vec<String>> {
+    let (entries, replace_managed) = entries(action)?;
+    let mut block = if replace_managed { Vec::new() } else { current.to_vec() };
+    for line in entries.iter().map(entry_line) {
+        if !block.contains(&line) {
+            block.push(line);
+        }
+    }
+    Ok(block)
+}
+
+/// Read the hosts file.
+fn read_hosts() -> Result<String> {
+    fs::read_to_string(HOSTS_FILE).map_err(|e| failed(format!("Failed to read {}: {}", HOSTS_FILE, e)))
+}
+
+/// Write the hosts file.
+fn write_hosts(content: &str) -> Result<()> {
+    write_config(Path::new(HOSTS_FILE), content).map_err(|e| failed(format!("Failed to write {}: {}", HOSTS_FILE, e)))
+}
+
+/// Set the managed block, leaving the file alone if it would not change.
+fn set_block(block: &[String]) -> Result<ActionOutcome> {
+    let content = read_hosts()?;
+    let updated = HostsFile::parse(&content)?.render(block);
+    if updated == content {
+        return Ok(ActionOutcome::Skipped(format!("{} is unchanged", HOSTS_FILE)));
+    }
+    write_hosts(&updated)?;
+    Ok(ActionOutcome::Applied)
+}
+
+/// Lines of the managed block.
+pub(super) fn managed_block() -> Result<Vec<String>> {
+    let content = read_hosts()?;
+    let block = HostsFile::parse(&content)?.block.into_iter().map(str::to_string).collect();
+    Ok(block)
+}
+
+/// Add an action's entries to the managed block.
+pub(super) fn apply(action: &SystemAction) -> Result<ActionOutcome> {
+    let block = merged_block(action, &managed_block()?)?;
+    set_block(&block)
+}
+
+/// Restore a captured managed block.
+pub(super) fn restore(block: &[String]) -> Result<()> {
+    set_block(block)?;
+    Ok(())
+}
+
+/// Remove an action's entries from the managed block.
+///
+/// The block itself is removed once it is empty.
+pub(super) fn release(action: &SystemAction) -> Result<ActionOutcome> {
+    let (entries, _) = entries(action)?;
+    let lines: Vec<String> = entries.iter().map(entry_line).collect();
+    let block: Vec<String> = managed_block()?.into_iter().filter(|line| !lines.contains(line)).collect();
+    set_block(&block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOSTS: &str = "127.0.0.1\tlocalhost\r\n# my printer\n192.168.1.20 printer";
+
+    fn action(names: &[&str], replace_managed: bool) -> SystemAction {
+        let entries = names
+            .iter()
+            .map(|name| HostsEntry {
+                ip: "10.0.0.5".parse().unwrap(),
+                hostnames: vec![name.to_string()],
+                comment: Some("lab".to_string()),
+            })
+            .collect();
+        SystemAction::HostsEntries { entries, replace_managed }
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        let file = HostsFile::parse(HOSTS).unwrap();
+        assert!(file.block.is_empty());
+
+        let block = merged_block(&action(&["nas"], false), &[]).unwrap();
+        assert_eq!(block, vec!["10.0.0.5\tnas\t# lab".to_string()]);
+        let written = file.render(&block);
+        assert!(written.starts_with("127.0.0.1\tlocalhost\r\n# my printer\n192.168.1.20 printer\n# BEGIN"));
+
+        assert!(written.ends_with(END_MARKER));
+
+        // Removing the block again restores the file exactly.
+        let parsed = HostsFile::parse(&written).unwrap();
+        assert_eq!(parsed.block, vec!["10.0.0.5\tnas\t# lab"]);
+        assert_eq!(parsed.render(&[]), HOSTS);
+        assert_eq!(parsed.render(&block), written);
+
+        let terminated = format!("{}\n", HOSTS);
+        let written = HostsFile::parse(&terminated).unwrap().render(&block);
+        assert!(written.ends_with(&format!("{}\n", END_MARKER)));
+        assert_eq!(HostsFile::parse(&written).unwrap().render(&[]), terminated);
+    }
+
+    #[test]
+    fn test_block_in_the_middle() {
+        let content = format!("{}\n{}\nold\tentry\n{}\n::1 localhost\n", "127.0.0.1 localhost", BEGIN_MARKER, END_MARKER);
+        let file = HostsFile::parse(&content).unwrap();
+        assert_eq!(file.block, vec!["old\tentry"]);
+        assert_eq!(file.after, "::1 localhost\n");
+
+        let current: Vec<String> = file.block.iter().map(|l| l.to_string()).collect();
+        assert_eq!(merged_block(&action(&["nas"], false), &current).unwrap().len(), 2);
+        assert_eq!(merged_block(&action(&["nas"], true), &current).unwrap().len(), 1);
+
+        let unterminated = format!("{}\nold\tentry\n", BEGIN_MARKER);
+        assert!(HostsFile::parse(&unterminated).is_err());
+        assert!(merged_block(&action(&["bad_name"], false), &[]).is_err());
+    }
+}
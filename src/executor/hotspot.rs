@@ -0,0 +1,274 @@
+// Network Manager - Wi-Fi Hotspots
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Wi-Fi hotspots, created as NetworkManager access point connections.
+//!
+//! The hotspot connection is bound to its adapter and only exists while a
+//! profile declares it: rolling back or switching profiles removes it and
+//! reconnects whatever was active before.
+
+use std::fmt;
+
+use super::network::restore_active;
+use super::ActionOutcome;
+use crate::models::actions::Action;
+use crate::models::plan::PlannedChange;
+use crate::models::validation::{validate_interface_name, validate_psk, validate_ssid};
+use crate::models::{AdapterType, Error, NetworkAction, Result};
+use crate::network_utils::find_adapter;
+use crate::nm::{ConnectionSettings, NmClient, SavedConnection};
+
+/// Settings of a hotspot connection an action changes (secrets excluded).
+const HOTSPOT_KEYS: &[(&str, &str)] = &[
+    ("802-11-wireless", "ssid"),
+    ("802-11-wireless", "band"),
+    ("802-11-wireless", "channel"),
+    ("ipv4", "method"),
+    ("ipv4", "address-data"),
+];
+
+/// Build an action failure for a hotspot action.
+fn failed(action: &NetworkAction, reason: impl Into<String>) -> Error {
+    Error::action_failed(action.name(), reason)
+}
+
+/// Validate a hotspot action, returning its interface.
+fn validate(action: &NetworkAction) -> Result<&str> {
+    let NetworkAction::Hotspot { interface, ssid, band, channel, passphrase, address } = action else {
+        return Err(failed(action, "Not a hotspot action"));
+    };
+    validate_interface_name(interface)?;
+    validate_ssid(ssid)?;
+    match (band, channel) {
+        (None, Some(_)) => return Err(failed(action, "A channel requires a band")),
+        (Some(band), Some(channel)) if !band.has_channel(*channel) => {
+            return Err(failed(action, format!("Channel {} is not in the {} band", channel, band)));
+        }
+        _ => {}
+    }
+    if let Some(passphrase) = passphrase.plaintext() {
+        validate_psk(passphrase)?;
+    }
+    if let Some(address) = address {
+        if !(1..=30).contains(&address.prefix) {
+            return Err(failed(action, format!("Invalid prefix for the shared network: /{}", address.prefix)));
+        }
+    }
+    Ok(interface)
+}
+
+/// Check that the hotspot interface is a Wi-Fi adapter on this machine.
+fn check_adapter(action: &NetworkAction, interface: &str) -> Result<()> {
+    match find_adapter(interface) {
+        Some(adapter) if adapter.adapter_type == AdapterType::Wifi => Ok(()),
+        Some(adapter) => Err(failed(
+            action,
+            format!("{} is a {} adapter, not Wi-Fi", interface, adapter.adapter_type.display_name()),
+        )),
+        None => Err(failed(action, format!("Wi-Fi adapter {} not found", interface))),
+    }
+}
+
+/// Find the access point connection bound to an interface.
+fn hotspot_connection(nm: &NmClient, interface: &str) -> Result<Option<SavedConnection>> {
+    Ok(nm.connections()?.into_iter().find(|c| {
+        c.settings.connection_type().as_deref() == Some("802-11-wireless")
+            && c.settings.interface_name().as_deref() == Some(interface)
+            && c.settings.get_str("802-11-wireless", "mode").as_deref() == Some("ap")
+    }))
+}
+
+/// Apply an action's hotspot settings, except the passphrase.
+fn edit_hotspot(action: &NetworkAction, settings: &mut ConnectionSettings) {
+    let NetworkAction::Hotspot { ssid, band, channel, address, .. } = action else { return };
+
+    settings.set("802-11-wireless", "ssid", ssid.as_bytes().to_vec());
+    settings.set("802-11-wireless", "mode", "ap");
+    match band {
+        Some(band) => settings.set("802-11-wireless", "band", band.as_nm()),
+        None => settings.remove("802-11-wireless", "band"),
+    }
+    match channel {
+        Some(channel) => settings.set("802-11-wireless", "channel", u32::from(*channel)),
+        None => settings.remove("802-11-wireless", "channel"),
+    }
+
+    settings.set("802-11-wireless-security", "key-mgmt", "wpa-psk");
+    settings.set("802-11-wireless-security", "proto", vec!["rsn".to_string()]);
+    settings.set("802-11-wireless-security", "pairwise", vec!["ccmp".to_string()]);
+    settings.set("802-11-wireless-security", "group", vec!["ccmp".to_string()]);
+
+    settings.set("ipv4", "method", "shared");
+    let addresses: Vec<_> = address.iter().map(|a| (a.address, a.prefix)).collect();
+    settings.set_ipv4_addresses(&addresses);
+    settings.set("ipv6", "method", "ignore");
+}
+
+/// Settings for a new hotspot connection.
+fn new_hotspot(interface: &str) -> ConnectionSettings {
+    let mut settings = ConnectionSettings::new();
+    settings.set("connection", "id", format!("Hotspot {}", interface));
+    settings.set("connection", "uuid", uuid::Uuid::new_v4().to_string());
+    settings.set("connection", "type", "802-11-wireless");
+    settings.set("connection", "interface-name", interface);
+    settings.set("connection", "autoconnect", false);
+    settings.mark_created();
+    settings
+}
+
+/// Create or update a hotspot and start it.
+pub(super) fn apply(nm: &NmClient, action: &NetworkAction) -> Result<ActionOutcome> {
+    let interface = validate(action)?;
+    check_adapter(action, interface)?;
+    let NetworkAction::Hotspot { passphrase, .. } = action else {
+        return Err(failed(action, "Not a hotspot action"));
+    };
+    let passphrase = passphrase
+        .plaintext()
+        .ok_or_else(|| failed(action, "The passphrase is encrypted and could not be decrypted"))?;
+    let device = nm.device_by_interface(interface)?;
+
+    match hotspot_connection(nm, interface)? {
+        Some(conn) if !conn.settings.is_created() => Err(failed(
+            action,
+            format!("A hotspot on {} is already configured outside of profiles", interface),
+        )),
+        Some(mut conn) => {
+            edit_hotspot(action, &mut conn.settings);
+            conn.settings.set("802-11-wireless-security", "psk", passphrase);
+            nm.update_connection(&conn)?;
+            nm.activate(&conn, Some(&device))?;
+            Ok(ActionOutcome::Applied)
+        }
+        None => {
+            let mut settings = new_hotspot(interface);
+            edit_hotspot(action, &mut settings);
+            settings.set("802-11-wireless-security", "psk", passphrase);
+            let conn = nm.load_connection(nm.add_connection(&settings)?)?;
+            if let Err(e) = nm.activate(&conn, Some(&device)) {
+                // Do not leave a hotspot behind that never started.
+                if let Err(delete_err) = nm.delete_connection(&conn) {
+                    tracing::warn!("Failed to remove hotspot connection on {}: {}", interface, delete_err);
+                }
+                return Err(e);
+            }
+            Ok(ActionOutcome::Applied)
+        }
+    }
+}
+
+/// A hotspot and the active connections before an action started it.
+#[derive(Debug, Clone)]
+pub struct HotspotSnapshot {
+    /// Wi-Fi interface.
+    interface: String,
+    /// Hotspot connection with its secrets, or `None` if it did not exist.
+    previous: Option<SavedConnection>,
+    /// IDs of the connections that were active.
+    active: Vec<String>,
+}
+
+impl fmt::Display for HotspotSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.previous {
+            None => write!(f, "Hotspot on {} absent", self.interface)?,
+            Some(conn) => write!(f, "Hotspot on {}\n{}", self.interface, conn.settings.describe(HOTSPOT_KEYS))?,
+        }
+        write!(f, "\n{}", self.active.join("\n"))
+    }
+}
+
+/// Capture the hotspot and connections an action is about to change.
+pub(super) fn capture(nm: &NmClient, action: &NetworkAction) -> Result<HotspotSnapshot> {
+    let interface = validate(action)?;
+    let mut previous = hotspot_connection(nm, interface)?;
+    if let Some(conn) = &mut previous {
+        nm.load_secrets(conn, "802-11-wireless-security")?;
+    }
+    let active = nm.active_connections()?.into_iter().map(|a| a.id).collect();
+    Ok(HotspotSnapshot { interface: interface.to_string(), previous, active })
+}
+
+/// Remove or restore the hotspot, then reconnect what was active.
+pub(super) fn restore(nm: &NmClient, snapshot: &HotspotSnapshot) -> Result<ActionOutcome> {
+    match (&snapshot.previous, hotspot_connection(nm, &snapshot.interface)?) {
+        (None, Some(conn)) if conn.settings.is_created() => nm.delete_connection(&conn)?,
+        (Some(before), Some(_)) => nm.update_connection(before)?,
+        _ => {}
+    }
+    restore_active(nm, &snapshot.active)
+}
+
+/// Describe what a hotspot action would change.
+pub(super) fn plan(nm: &NmClient, action: &NetworkAction) -> Result<PlannedChange> {
+    let wrapped = Action::Network(action.clone());
+    let interface = validate(action)?;
+    check_adapter(action, interface)?;
+    let current = hotspot_connection(nm, interface)?;
+
+    let mut desired = current.as_ref().map(|c| c.settings.clone()).unwrap_or_else(|| new_hotspot(interface));
+    edit_hotspot(action, &mut desired);
+    let desired = format!("Hotspot on {} active\n{}", interface, desired.describe(HOTSPOT_KEYS));
+
+    let Some(current) = current else {
+        let absent = format!("Hotspot on {} absent", interface);
+        return Ok(PlannedChange::compare_with_secrets(wrapped, Some(absent), desired));
+    };
+    let state = if nm.active_connection_of(&current)?.is_some() { "active" } else { "inactive" };
+    let current = format!("Hotspot on {} {}\n{}", interface, state, current.settings.describe(HOTSPOT_KEYS));
+    Ok(PlannedChange::compare_with_secrets(wrapped, Some(current), desired))
+}
+
+/// Stop and remove a hotspot created by this application.
+pub(super) fn release(nm: &NmClient, action: &NetworkAction) -> Result<ActionOutcome> {
+    let interface = validate(action)?;
+    match hotspot_connection(nm, interface)? {
+        Some(conn) if conn.settings.is_created() => {
+            nm.delete_connection(&conn)?;
+            Ok(ActionOutcome::Applied)
+        }
+        Some(_) => Ok(ActionOutcome::Skipped(format!("The hotspot on {} was not created by a profile", interface))),
+        None => Ok(ActionOutcome::Skipped(format!("No hotspot on {}", interface))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Ipv4Address, Secret, WifiBand};
+
+    fn hotspot(band: Option<WifiBand>, channel: Option<u16>) -> NetworkAction {
+        NetworkAction::Hotspot {
+            interface: "wlan0".to_string(),
+            ssid: "Field Kit".to_string(),
+            band,
+            channel,
+            passphrase: Secret::Plain("tether-me-please".to_string()),
+            address: Some(Ipv4Address { address: "192.168.50.1".parse().unwrap(), prefix: 24 }),
+        }
+    }
+
+    #[test]
+    fn test_hotspot_settings() {
+        let action = hotspot(Some(WifiBand::Ghz5), Some(36));
+        let mut settings = new_hotspot(validate(&action).unwrap());
+        edit_hotspot(&action, &mut settings);
+
+        assert_eq!(settings.get_str("802-11-wireless", "mode").as_deref(), Some("ap"));
+        assert_eq!(settings.get_str("802-11-wireless", "band").as_deref(), Some("a"));
+        assert_eq!(settings.get_u32("802-11-wireless", "channel"), Some(36));
+        assert_eq!(settings.get_str("ipv4", "method").as_deref(), Some("shared"));
+        assert!(settings.describe(HOTSPOT_KEYS).contains("192.168.50.1"));
+        assert!(settings.get("802-11-wireless-security", "psk").is_none());
+        assert!(settings.is_created());
+    }
+
+    #[test]
+    fn test_validate_hotspot() {
+        assert!(validate(&hotspot(None, None)).is_ok());
+        assert!(validate(&hotspot(None, Some(6))).is_err());
+        assert!(validate(&hotspot(Some(WifiBand::Ghz2_4), Some(36))).is_err());
+        assert!(validate(&hotspot(Some(WifiBand::Ghz2_4), Some(6))).is_ok());
+    }
+}
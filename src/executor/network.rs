@@ -0,0 +1,827 @@
+// Network Manager - Network Action Execution
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Network actions, applied through the NetworkManager D-Bus API.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use super::hotspot::{self, HotspotSnapshot};
+use super::topology::{self, TopologySnapshot};
+use super::wifi::{self, WifiSnapshot};
+use super::wireguard::{self, WireGuardSnapshot};
+use super::ActionOutcome;
+use crate::models::actions::Action;
+use crate::models::plan::{ChangeKind, PlannedChange};
+use crate::models::validation::{validate_interface_name, validate_route, validate_routing_rule, validate_vlan_id};
+use crate::models::{Error, Ipv4Method, Ipv6Method, NetworkAction, Result};
+use crate::nm::{ConnectionSettings, Device, DeviceType, NmClient, RouteData, RuleData, SavedConnection};
+
+/// Apply a network action.
+pub(super) fn apply(action: &NetworkAction) -> Result<ActionOutcome> {
+    let nm = NmClient::connect()?;
+
+    match action {
+        NetworkAction::Ipv4Config { .. }
+        | NetworkAction::Ipv6Config { .. }
+        | NetworkAction::DnsServers { .. }
+        | NetworkAction::DnsSearchDomains { .. }
+        | NetworkAction::StaticRoutes { .. }
+        | NetworkAction::RoutingRules { .. }
+        | NetworkAction::SetMtu { .. }
+        | NetworkAction::SetMacAddress { .. } => apply_settings(&nm, action),
+
+        NetworkAction::WifiConnect { .. } => wifi::apply(&nm, action),
+
+        NetworkAction::Hotspot { .. } => hotspot::apply(&nm, action),
+
+        NetworkAction::VpnConnect { connection_name } => {
+            let conn = nm
+                .connection_by_id(connection_name)?
+                .ok_or_else(|| failed(action, format!("No saved connection named '{}'", connection_name)))?;
+            if nm.active_connections()?.iter().any(|a| a.id == *connection_name) {
+                return Ok(ActionOutcome::Skipped(format!("{} is already connected", connection_name)));
+            }
+            nm.activate(&conn, None)?;
+            Ok(ActionOutcome::Applied)
+        }
+
+        NetworkAction::InterfaceEnable(state) => {
+            let device = nm.device_by_interface(&state.interface)?;
+            match (state.enabled, device.active_connection.is_some()) {
+                (true, true) => Ok(ActionOutcome::Skipped(format!("{} is already connected", state.interface))),
+                (false, false) => Ok(ActionOutcome::Skipped(format!("{} is already disconnected", state.interface))),
+                (true, false) => {
+                    nm.connect_device(&device)?;
+                    Ok(ActionOutcome::Applied)
+                }
+                (false, true) => {
+                    nm.disconnect_device(&device)?;
+                    Ok(ActionOutcome::Applied)
+                }
+            }
+        }
+
+        NetworkAction::VpnDisconnect { connection_name } => {
+            let active = nm.active_connections()?;
+            let Some(vpn) = active.iter().find(|a| a.id == *connection_name) else {
+                return Ok(ActionOutcome::Skipped(format!("{} is not connected", connection_name)));
+            };
+            nm.deactivate(vpn)?;
+            Ok(ActionOutcome::Applied)
+        }
+
+        NetworkAction::Bond { .. } | NetworkAction::Bridge { .. } => topology::apply(&nm, action),
+
+        NetworkAction::WireGuard { .. } => wireguard::apply(&nm, action),
+
+        NetworkAction::VlanConfig { parent_interface, vlan_id, .. } => {
+            validate_vlan_id(*vlan_id)?;
+            let interface = validate_interface_name(&action.created_interface().unwrap_or_default())?;
+
+            if let Some(conn) = nm.vlan_connection(parent_interface, *vlan_id)? {
+                if nm.active_connection_of(&conn)?.is_some() {
+                    return Ok(ActionOutcome::Skipped(format!("{} is already up", interface)));
+                }
+                nm.activate(&conn, None)?;
+                return Ok(ActionOutcome::Applied);
+            }
+
+            let path = nm.add_connection(&vlan_settings(parent_interface, *vlan_id, &interface))?;
+            let conn = nm.load_connection(path)?;
+            if let Err(e) = nm.activate(&conn, None) {
+                // Do not leave a half-configured VLAN behind.
+                if let Err(delete_err) = nm.delete_connection(&conn) {
+                    tracing::warn!("Failed to remove VLAN connection {}: {}", interface, delete_err);
+                }
+                return Err(e);
+            }
+            Ok(ActionOutcome::Applied)
+        }
+    }
+}
+
+/// Remove what applying a network action created.
+///
+/// Only connections created by this application are removed; a VLAN that
+/// already existed when the profile was applied is left alone.
+pub(super) fn release(action: &NetworkAction) -> Result<ActionOutcome> {
+    match action {
+        NetworkAction::VlanConfig { parent_interface, vlan_id, .. } => {
+            let interface = action.created_interface().unwrap_or_default();
+            let nm = NmClient::connect()?;
+            match nm.vlan_connection(parent_interface, *vlan_id)? {
+                Some(conn) if conn.settings.is_created() => {
+                    nm.delete_connection(&conn)?;
+                    Ok(ActionOutcome::Applied)
+                }
+                Some(_) => Ok(ActionOutcome::Skipped(format!("{} was not created by a profile", interface))),
+                None => Ok(ActionOutcome::Skipped(format!("{} no longer exists", interface))),
+            }
+        }
+        NetworkAction::Bond { .. } | NetworkAction::Bridge { .. } => topology::release(&NmClient::connect()?, action),
+        NetworkAction::WireGuard { .. } => wireguard::release(&NmClient::connect()?, action),
+        NetworkAction::Hotspot { .. } => hotspot::release(&NmClient::connect()?, action),
+        _ => Ok(ActionOutcome::Skipped("Nothing to release".to_string())),
+    }
+}
+
+/// Apply an action that edits the settings of the targeted connection.
+fn apply_settings(nm: &NmClient, action: &NetworkAction) -> Result<ActionOutcome> {
+    let interface = settings_target(action).unwrap_or_default();
+    let Some((device, mut conn)) = target(nm, interface, &[])? else {
+        return Ok(ActionOutcome::Skipped(unmanaged(interface)));
+    };
+    edit_connection(action, &device, &mut conn.settings)?;
+
+    nm.update_connection(&conn)?;
+    if needs_reconnect(action) {
+        reactivate(nm, &device, &conn)?;
+    } else {
+        reapply(nm, &device)?;
+    }
+    Ok(ActionOutcome::Applied)
+}
+
+/// Build an action failure for a network action.
+fn failed(action: &NetworkAction, reason: impl Into<String>) -> Error {
+    Error::action_failed(action.name(), reason)
+}
+
+/// Interface whose connection settings an action edits.
+///
+/// Returns `None` for actions that do not edit a connection; `Some(None)`
+/// targets the primary connection.
+fn settings_target(action: &NetworkAction) -> Option<Option<&str>> {
+    match action {
+        NetworkAction::Ipv4Config { interface, .. }
+        | NetworkAction::Ipv6Config { interface, .. }
+        | NetworkAction::DnsServers { interface, .. }
+        | NetworkAction::DnsSearchDomains { interface, .. }
+        | NetworkAction::StaticRoutes { interface, .. }
+        | NetworkAction::RoutingRules { interface, .. } => Some(interface.as_deref()),
+        NetworkAction::SetMtu { interface, .. } | NetworkAction::SetMacAddress { interface, .. } => {
+            Some(Some(interface.as_str()))
+        }
+        _ => None,
+    }
+}
+
+/// Whether an action changes link-level settings that need a reconnect.
+fn needs_reconnect(action: &NetworkAction) -> bool {
+    matches!(action, NetworkAction::SetMtu { .. } | NetworkAction::SetMacAddress { .. })
+}
+
+/// Connection settings an action changes.
+fn settings_keys(action: &NetworkAction, device: &Device) -> Vec<(&'static str, &'static str)> {
+    match action {
+        NetworkAction::Ipv4Config { .. } => vec![
+            ("ipv4", "method"),
+            ("ipv4", "address-data"),
+            ("ipv4", "gateway"),
+            ("ipv4", "dns"),
+            ("ipv4", "dns-search"),
+        ],
+        NetworkAction::Ipv6Config { .. } => vec![
+            ("ipv6", "method"),
+            ("ipv6", "address-data"),
+            ("ipv6", "gateway"),
+            ("ipv6", "dns"),
+            ("ipv6", "dns-search"),
+        ],
+        NetworkAction::DnsServers { .. } => vec![("ipv4", "dns"), ("ipv6", "dns")],
+        NetworkAction::DnsSearchDomains { .. } => vec![("ipv4", "dns-search")],
+        NetworkAction::StaticRoutes { .. } => vec![("ipv4", "route-data"), ("ipv6", "route-data")],
+        NetworkAction::RoutingRules { .. } => vec![("ipv4", "routing-rules"), ("ipv6", "routing-rules")],
+        NetworkAction::SetMtu { .. } => vec![(link_setting(device), "mtu")],
+        NetworkAction::SetMacAddress { .. } => vec![(link_setting(device), "cloned-mac-address")],
+        _ => Vec::new(),
+    }
+}
+
+/// Apply an action to connection settings in memory.
+fn edit_connection(action: &NetworkAction, device: &Device, settings: &mut ConnectionSettings) -> Result<()> {
+    match action {
+        NetworkAction::Ipv4Config { method, addresses, gateway, .. } => match method {
+            Ipv4Method::Auto => {
+                settings.set("ipv4", "method", "auto");
+                settings.set_ipv4_addresses(&[]);
+                settings.remove("ipv4", "gateway");
+            }
+            Ipv4Method::Manual => {
+                if addresses.is_empty() {
+                    return Err(failed(action, "Manual IPv4 configuration requires an address"));
+                }
+                let addresses: Vec<_> = addresses.iter().map(|a| (a.address, a.prefix)).collect();
+                settings.set("ipv4", "method", "manual");
+                settings.set_ipv4_addresses(&addresses);
+                match gateway {
+                    Some(gw) => settings.set("ipv4", "gateway", gw.to_string()),
+                    None => settings.remove("ipv4", "gateway"),
+                }
+            }
+            Ipv4Method::LinkLocal | Ipv4Method::Disabled => {
+                settings.set_ipv4_addresses(&[]);
+                settings.remove("ipv4", "gateway");
+                // NetworkManager rejects DNS settings without an IPv4 configuration.
+                settings.set_ipv4_dns(&[]);
+                settings.set("ipv4", "dns-search", Vec::<String>::new());
+                let method = if let Ipv4Method::LinkLocal = method { "link-local" } else { "disabled" };
+                settings.set("ipv4", "method", method);
+            }
+        },
+
+        NetworkAction::Ipv6Config { method, addresses, gateway, .. } => {
+            if let Ipv6Method::Manual = method {
+                if addresses.is_empty() {
+                    return Err(failed(action, "Manual IPv6 configuration requires an address"));
+                }
+                let addresses: Vec<_> = addresses.iter().map(|a| (a.address, a.prefix)).collect();
+                settings.set_ipv6_addresses(&addresses);
+                match gateway {
+                    Some(gw) => settings.set("ipv6", "gateway", gw.to_string()),
+                    None => settings.remove("ipv6", "gateway"),
+                }
+            } else {
+                settings.set_ipv6_addresses(&[]);
+                settings.remove("ipv6", "gateway");
+            }
+            // NetworkManager rejects DNS settings when IPv6 is not configured.
+            if let Ipv6Method::LinkLocal | Ipv6Method::Disabled = method {
+                settings.set_ipv6_dns(&[]);
+                settings.set("ipv6", "dns-search", Vec::<String>::new());
+            }
+            settings.set("ipv6", "method", ipv6_method(method));
+        }
+
+        NetworkAction::DnsServers { servers, .. } => {
+            let v4: Vec<_> = servers.iter().filter_map(|s| match s {
+                IpAddr::V4(a) => Some(*a),
+                IpAddr::V6(_) => None,
+            }).collect();
+            let v6: Vec<_> = servers.iter().filter_map(|s| match s {
+                IpAddr::V6(a) => Some(*a),
+                IpAddr::V4(_) => None,
+            }).collect();
+            settings.set_ipv4_dns(&v4);
+            settings.set_ipv6_dns(&v6);
+        }
+
+        NetworkAction::DnsSearchDomains { domains, .. } => {
+            settings.set("ipv4", "dns-search", domains.clone());
+        }
+
+        NetworkAction::StaticRoutes { routes, .. } => {
+            let (mut v4, mut v6) = (Vec::new(), Vec::new());
+            for route in routes {
+                let (dest, prefix) = validate_route(route)?;
+                let data = RouteData {
+                    dest,
+                    prefix,
+                    next_hop: Some(route.gateway),
+                    metric: route.metric,
+                    table: route.table,
+                };
+                if dest.is_ipv4() { v4.push(data) } else { v6.push(data) }
+            }
+            settings.set_routes("ipv4", &v4);
+            settings.set_routes("ipv6", &v6);
+        }
+
+        NetworkAction::RoutingRules { rules, .. } => {
+            let (mut v4, mut v6) = (Vec::new(), Vec::new());
+            for rule in rules {
+                let (from, to) = validate_routing_rule(rule)?;
+                let data = RuleData {
+                    priority: rule.priority,
+                    from,
+                    to,
+                    fwmark: rule.fwmark.map(|mark| (mark, rule.fwmask.unwrap_or(u32::MAX))),
+                    table: rule.table,
+                };
+                // Rules matching only a firewall mark apply to both families.
+                match from.or(to).map(|(ip, _)| ip.is_ipv4()) {
+                    Some(true) => v4.push(data),
+                    Some(false) => v6.push(data),
+                    None => {
+                        v4.push(data.clone());
+                        v6.push(data);
+                    }
+                }
+            }
+            settings.set_routing_rules("ipv4", &v4);
+            settings.set_routing_rules("ipv6", &v6);
+        }
+
+        NetworkAction::SetMtu { mtu, .. } => settings.set(link_setting(device), "mtu", *mtu),
+
+        NetworkAction::SetMacAddress { mac_address, .. } => {
+            settings.set(link_setting(device), "cloned-mac-address", mac_address.as_str());
+        }
+
+        _ => {}
+    }
+    Ok(())
+}
+
+/// NetworkManager `ipv6.method` value for a method.
+fn ipv6_method(method: &Ipv6Method) -> &'static str {
+    match method {
+        Ipv6Method::Auto => "auto",
+        Ipv6Method::Dhcp => "dhcp",
+        Ipv6Method::Manual => "manual",
+        Ipv6Method::LinkLocal => "link-local",
+        Ipv6Method::Disabled => "disabled",
+    }
+}
+
+/// Settings for a new VLAN connection.
+fn vlan_settings(parent: &str, vlan_id: u16, interface: &str) -> ConnectionSettings {
+    let mut settings = ConnectionSettings::new();
+    settings.set("connection", "id", interface);
+    settings.set("connection", "uuid", uuid::Uuid::new_v4().to_string());
+    settings.set("connection", "type", "vlan");
+    settings.set("connection", "interface-name", interface);
+    settings.set("vlan", "parent", parent);
+    settings.set("vlan", "id", u32::from(vlan_id));
+    settings.mark_created();
+    settings
+}
+
+/// State of a VLAN connection: `None` if it does not exist, otherwise
+/// whether it is active.
+fn vlan_state(nm: &NmClient, parent: &str, vlan_id: u16) -> Result<Option<bool>> {
+    match nm.vlan_connection(parent, vlan_id)? {
+        Some(conn) => Ok(Some(nm.active_connection_of(&conn)?.is_some())),
+        None => Ok(None),
+    }
+}
+
+/// Describe a VLAN state for display.
+fn describe_vlan_state(state: Option<bool>) -> &'static str {
+    match state {
+        None => "absent",
+        Some(false) => "inactive",
+        Some(true) => "active",
+    }
+}
+
+/// Check an interface NetworkManager has no device for.
+///
+/// Only an interface an earlier action creates may be missing, as it does
+/// not exist yet during a dry run.
+fn missing_interface(interface: &str, created: &[String]) -> Result<()> {
+    if created.iter().any(|c| c == interface) {
+        Ok(())
+    } else {
+        Err(Error::ActionNotSupported(format!("Interface {} does not exist", interface)))
+    }
+}
+
+/// Reason for skipping an interface without a NetworkManager connection.
+fn unmanaged(interface: Option<&str>) -> String {
+    match interface {
+        Some(interface) => {
+            tracing::debug!("Skipping unmanaged interface: {}", interface);
+            format!("Interface {} is not managed by NetworkManager", interface)
+        }
+        None => "No active network connection".to_string(),
+    }
+}
+
+/// Resolve the device and connection an action targets.
+///
+/// With no interface, the device of the primary connection is used.
+/// `created` lists the interfaces earlier actions of the profile create.
+fn target(nm: &NmClient, interface: Option<&str>, created: &[String]) -> Result<Option<(Device, SavedConnection)>> {
+    let device = match interface {
+        Some(interface) => match nm.find_device(interface)? {
+            Some(device) => device,
+            None => return missing_interface(interface, created).map(|()| None),
+        },
+        None => match nm.primary_device()? {
+            Some(device) => device,
+            None => return Ok(None),
+        },
+    };
+    let Some(conn) = nm.connection_for_interface(&device.interface)? else {
+        return Ok(None);
+    };
+    Ok(Some((device, conn)))
+}
+
+/// Setting holding link properties (MTU, MAC) for a device.
+fn link_setting(device: &Device) -> &'static str {
+    match device.device_type {
+        DeviceType::Wifi => "802-11-wireless",
+        _ => "802-3-ethernet",
+    }
+}
+
+/// Apply saved IP settings to a device without reconnecting it.
+fn reapply(nm: &NmClient, device: &Device) -> Result<()> {
+    if device.active_connection.is_some() {
+        nm.reapply(device)?;
+    }
+    Ok(())
+}
+
+/// Reconnect a device so link-level changes take effect.
+fn reactivate(nm: &NmClient, device: &Device, conn: &SavedConnection) -> Result<()> {
+    if device.active_connection.is_some() {
+        nm.activate(conn, Some(device))?;
+    }
+    Ok(())
+}
+
+/// NetworkManager state captured before a network action.
+#[derive(Debug, Clone)]
+pub enum NetworkSnapshot {
+    /// A connection as saved before the action modified it.
+    Connection {
+        /// Device the connection is applied to.
+        device: Device,
+        /// The saved connection.
+        connection: SavedConnection,
+        /// Settings the action changes.
+        keys: Vec<(&'static str, &'static str)>,
+        /// Whether restoring requires reconnecting (link-level settings).
+        reconnect: bool,
+    },
+    /// IDs of the connections that were active.
+    ActiveConnections(Vec<String>),
+    /// A VLAN connection before the action created or activated it.
+    Vlan {
+        /// Parent interface.
+        parent: String,
+        /// VLAN ID.
+        vlan_id: u16,
+        /// `None` if the connection did not exist, otherwise whether it was active.
+        state: Option<bool>,
+    },
+    /// A bond or bridge before the action created or changed it.
+    Topology(TopologySnapshot),
+    /// A WireGuard tunnel before the action created or changed it.
+    WireGuard(WireGuardSnapshot),
+    /// A Wi-Fi connection and the active connections before the action.
+    Wifi(WifiSnapshot),
+    /// A hotspot and the active connections before the action.
+    Hotspot(HotspotSnapshot),
+    /// Whether a device had an active connection.
+    DeviceConnected {
+        /// Interface name.
+        interface: String,
+        /// Whether it was connected.
+        connected: bool,
+    },
+}
+
+impl fmt::Display for NetworkSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connection { connection, keys, .. } => {
+                write!(f, "{}", connection.settings.describe(keys))
+            }
+            Self::ActiveConnections(ids) => write!(f, "{}", ids.join("\n")),
+            Self::Vlan { parent, vlan_id, state } => {
+                write!(f, "VLAN {} on {} {}", vlan_id, parent, describe_vlan_state(*state))
+            }
+            Self::Topology(snapshot) => snapshot.fmt(f),
+            Self::WireGuard(snapshot) => snapshot.fmt(f),
+            Self::Wifi(snapshot) => snapshot.fmt(f),
+            Self::Hotspot(snapshot) => snapshot.fmt(f),
+            Self::DeviceConnected { interface, connected } => {
+                write!(f, "{} {}", interface, if *connected { "connected" } else { "disconnected" })
+            }
+        }
+    }
+}
+
+/// Capture the state a network action is about to change.
+///
+/// Returns `None` for actions that cannot be rolled back, and an error if
+/// the state could not be read.
+pub(super) fn capture_state(action: &NetworkAction) -> Result<Option<NetworkSnapshot>> {
+    let nm = NmClient::connect()?;
+
+    if let Some(interface) = settings_target(action) {
+        let Some((device, connection)) = target(&nm, interface, &[])? else { return Ok(None) };
+        let keys = settings_keys(action, &device);
+        let reconnect = needs_reconnect(action);
+        return Ok(Some(NetworkSnapshot::Connection { device, connection, keys, reconnect }));
+    }
+
+    let snapshot = match action {
+        NetworkAction::InterfaceEnable(state) => {
+            let device = nm.device_by_interface(&state.interface)?;
+            NetworkSnapshot::DeviceConnected {
+                interface: state.interface.clone(),
+                connected: device.active_connection.is_some(),
+            }
+        }
+        NetworkAction::Bond { .. } | NetworkAction::Bridge { .. } => {
+            NetworkSnapshot::Topology(topology::capture(&nm, action)?)
+        }
+        NetworkAction::WireGuard { .. } => NetworkSnapshot::WireGuard(wireguard::capture(&nm, action)?),
+        NetworkAction::VlanConfig { parent_interface, vlan_id, .. } => NetworkSnapshot::Vlan {
+            parent: parent_interface.clone(),
+            vlan_id: *vlan_id,
+            state: vlan_state(&nm, parent_interface, *vlan_id)?,
+        },
+        NetworkAction::WifiConnect { .. } => NetworkSnapshot::Wifi(wifi::capture(&nm, action)?),
+        NetworkAction::Hotspot { .. } => NetworkSnapshot::Hotspot(hotspot::capture(&nm, action)?),
+        NetworkAction::VpnConnect { .. } | NetworkAction::VpnDisconnect { .. } => {
+            let active = nm.active_connections()?;
+            NetworkSnapshot::ActiveConnections(active.into_iter().map(|a| a.id).collect())
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(snapshot))
+}
+
+/// Restore network state captured before an action.
+pub(super) fn restore(snapshot: &NetworkSnapshot) -> Result<ActionOutcome> {
+    let nm = NmClient::connect()?;
+
+    match snapshot {
+        NetworkSnapshot::Connection { device, connection, reconnect, .. } => {
+            nm.update_connection(connection)?;
+            if *reconnect {
+                reactivate(&nm, device, connection)?;
+            } else {
+                reapply(&nm, device)?;
+            }
+            Ok(ActionOutcome::Applied)
+        }
+
+        NetworkSnapshot::ActiveConnections(before) => restore_active(&nm, before),
+
+        NetworkSnapshot::Topology(snapshot) => topology::restore(&nm, snapshot),
+
+        NetworkSnapshot::WireGuard(snapshot) => wireguard::restore(&nm, snapshot),
+
+        NetworkSnapshot::Wifi(snapshot) => wifi::restore(&nm, snapshot),
+
+        NetworkSnapshot::Hotspot(snapshot) => hotspot::restore(&nm, snapshot),
+
+        NetworkSnapshot::Vlan { parent, vlan_id, state } => {
+            let Some(conn) = nm.vlan_connection(parent, *vlan_id)? else {
+                return Ok(ActionOutcome::Skipped(format!("VLAN {} on {} no longer exists", vlan_id, parent)));
+            };
+            match (state, nm.active_connection_of(&conn)?) {
+                (None, _) => {
+                    nm.delete_connection(&conn)?;
+                    Ok(ActionOutcome::Applied)
+                }
+                (Some(false), Some(active)) => {
+                    nm.deactivate(&active)?;
+                    Ok(ActionOutcome::Applied)
+                }
+                _ => Ok(ActionOutcome::Skipped(format!("VLAN {} on {} is unchanged", vlan_id, parent))),
+            }
+        }
+
+        NetworkSnapshot::DeviceConnected { interface, connected } => {
+            let device = nm.device_by_interface(interface)?;
+            match (*connected, device.active_connection.is_some()) {
+                (true, false) => {
+                    nm.connect_device(&device)?;
+                    Ok(ActionOutcome::Applied)
+                }
+                (false, true) => {
+                    nm.disconnect_device(&device)?;
+                    Ok(ActionOutcome::Applied)
+                }
+                _ => Ok(ActionOutcome::Skipped(format!("{} is unchanged", interface))),
+            }
+        }
+    }
+}
+
+/// Bring the active connections back to a captured set of IDs.
+pub(super) fn restore_active(nm: &NmClient, before: &[String]) -> Result<ActionOutcome> {
+    let now = nm.active_connections()?;
+    for active in now.iter().filter(|a| !before.contains(&a.id)) {
+        nm.deactivate(active)?;
+    }
+    for id in before.iter().filter(|id| !now.iter().any(|a| a.id == **id)) {
+        match nm.connection_by_id(id)? {
+            Some(conn) => {
+                nm.activate(&conn, None)?;
+            }
+            None => tracing::warn!("Cannot reactivate '{}': connection no longer exists", id),
+        }
+    }
+    Ok(ActionOutcome::Applied)
+}
+
+/// Describe what a network action would change, given the interfaces
+/// earlier actions of the profile create.
+pub(super) fn plan(action: &NetworkAction, created: &[String]) -> PlannedChange {
+    let planned = NmClient::connect().and_then(|nm| plan_with(&nm, action, created));
+    planned.unwrap_or_else(|e| PlannedChange::unsupported(Action::Network(action.clone()), e.to_string()))
+}
+
+fn plan_with(nm: &NmClient, action: &NetworkAction, created: &[String]) -> Result<PlannedChange> {
+    let wrapped = Action::Network(action.clone());
+    let connected = |state: bool| if state { "connected" } else { "disconnected" }.to_string();
+
+    if let Some(interface) = settings_target(action) {
+        let Some((device, conn)) = target(nm, interface, created)? else {
+            // An interface created earlier in the profile does not exist yet.
+            if interface.is_some_and(|i| created.iter().any(|c| c == i)) {
+                let desired = format!("{} (created by this profile)", action.description());
+                return Ok(PlannedChange::new(wrapped, ChangeKind::Change, None, desired));
+            }
+            return Ok(PlannedChange::unsupported(wrapped, unmanaged(interface)));
+        };
+        let keys = settings_keys(action, &device);
+        let mut desired = conn.settings.clone();
+        edit_connection(action, &device, &mut desired)?;
+
+        let kind = if conn.settings.same_values(&desired, &keys) { ChangeKind::NoOp } else { ChangeKind::Change };
+        return Ok(PlannedChange::new(wrapped, kind, Some(conn.settings.describe(&keys)), desired.describe(&keys)));
+    }
+
+    let planned = match action {
+        NetworkAction::WifiConnect { .. } => wifi::plan(nm, action)?,
+        NetworkAction::Hotspot { .. } => hotspot::plan(nm, action)?,
+        NetworkAction::VpnConnect { connection_name } | NetworkAction::VpnDisconnect { connection_name } => {
+            if nm.connection_by_id(connection_name)?.is_none() {
+                return Ok(PlannedChange::unsupported(
+                    wrapped,
+                    format!("No saved connection named '{}'", connection_name),
+                ));
+            }
+            let active = nm.active_connections()?.iter().any(|a| a.id == *connection_name);
+            let wanted = matches!(action, NetworkAction::VpnConnect { .. });
+            PlannedChange::compare(wrapped, Some(connected(active)), connected(wanted))
+        }
+        NetworkAction::InterfaceEnable(state) => {
+            let device = nm.device_by_interface(&state.interface)?;
+            PlannedChange::compare(wrapped, Some(connected(device.active_connection.is_some())), connected(state.enabled))
+        }
+        NetworkAction::Bond { .. } | NetworkAction::Bridge { .. } => topology::plan(nm, action)?,
+        NetworkAction::WireGuard { .. } => wireguard::plan(nm, action)?,
+        NetworkAction::VlanConfig { parent_interface, vlan_id, .. } => {
+            let state = vlan_state(nm, parent_interface, *vlan_id)?;
+            PlannedChange::compare(wrapped, Some(describe_vlan_state(state).to_string()), "active")
+        }
+        _ => PlannedChange::unsupported(wrapped, "Not yet implemented"),
+    };
+    Ok(planned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Ipv4Address, Ipv6Address, RoutingRule, StaticRoute};
+
+    fn ethernet() -> Device {
+        Device {
+            path: "/org/freedesktop/NetworkManager/Devices/2".try_into().unwrap(),
+            interface: "enp3s0".to_string(),
+            device_type: DeviceType::Ethernet,
+            managed: true,
+            active_connection: None,
+        }
+    }
+
+    fn ipv6(method: Ipv6Method, addresses: Vec<Ipv6Address>) -> NetworkAction {
+        NetworkAction::Ipv6Config {
+            interface: Some("enp3s0".to_string()),
+            method,
+            addresses,
+            gateway: Some("fd00::1".parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_ipv6_manual_settings() {
+        let addresses = vec![
+            Ipv6Address { address: "fd00::10".parse().unwrap(), prefix: 64 },
+            Ipv6Address { address: "fd00:1::10".parse().unwrap(), prefix: 64 },
+        ];
+        let mut settings = ConnectionSettings::new();
+        edit_connection(&ipv6(Ipv6Method::Manual, addresses), &ethernet(), &mut settings).unwrap();
+
+        assert_eq!(settings.get_str("ipv6", "method").as_deref(), Some("manual"));
+        assert_eq!(settings.get_str("ipv6", "gateway").as_deref(), Some("fd00::1"));
+        let described = settings.describe(&[("ipv6", "address-data")]);
+        assert!(described.contains("fd00::10") && described.contains("fd00:1::10"));
+
+        let result = edit_connection(&ipv6(Ipv6Method::Manual, vec![]), &ethernet(), &mut settings);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_routes_split_by_family() {
+        let route = |destination: &str, prefix, gateway: &str| StaticRoute {
+            destination: destination.to_string(),
+            prefix,
+            gateway: gateway.parse().unwrap(),
+            metric: None,
+            table: Some(100),
+        };
+        let action = NetworkAction::StaticRoutes {
+            interface: None,
+            routes: vec![route("10.20.0.0", 16, "192.168.1.1"), route("fd00:20::", 64, "fd00::1")],
+        };
+        let mut settings = ConnectionSettings::new();
+        edit_connection(&action, &ethernet(), &mut settings).unwrap();
+
+        assert!(settings.describe(&[("ipv4", "route-data")]).contains("10.20.0.0"));
+        assert!(settings.describe(&[("ipv6", "route-data")]).contains("fd00:20::"));
+
+        let invalid = NetworkAction::StaticRoutes {
+            interface: None,
+            routes: vec![route("10.20.0.5", 16, "192.168.1.1")],
+        };
+        assert!(matches!(
+            edit_connection(&invalid, &ethernet(), &mut settings),
+            Err(Error::InvalidRoute(_))
+        ));
+    }
+
+    #[test]
+    fn test_fwmark_rule_applies_to_both_families() {
+        let action = NetworkAction::RoutingRules {
+            interface: None,
+            rules: vec![RoutingRule {
+                from: None,
+                to: None,
+                fwmark: Some(0x10),
+                fwmask: None,
+                table: 100,
+                priority: 1000,
+            }],
+        };
+        let mut settings = ConnectionSettings::new();
+        edit_connection(&action, &ethernet(), &mut settings).unwrap();
+
+        assert!(settings.get("ipv4", "routing-rules").is_some());
+        assert!(settings.get("ipv6", "routing-rules").is_some());
+    }
+
+    #[test]
+    fn test_vlan_settings() {
+        let vlan = |name: Option<&str>| NetworkAction::VlanConfig {
+            parent_interface: "enp3s0".to_string(),
+            vlan_id: 20,
+            name: name.map(str::to_string),
+        };
+        assert_eq!(vlan(None).created_interface().as_deref(), Some("enp3s0.20"));
+        assert_eq!(vlan(Some("lab")).created_interface().as_deref(), Some("lab"));
+
+        let settings = vlan_settings("enp3s0", 20, "enp3s0.20");
+        assert_eq!(settings.connection_type().as_deref(), Some("vlan"));
+        assert_eq!(settings.interface_name().as_deref(), Some("enp3s0.20"));
+        assert_eq!(settings.get_u32("vlan", "id"), Some(20));
+        assert!(settings.uuid().is_some());
+        assert!(settings.is_created());
+    }
+
+    #[test]
+    fn test_unknown_interface_fails() {
+        let result = missing_interface("eth9", &[]);
+        assert!(matches!(result, Err(Error::ActionNotSupported(_))));
+
+        // A VLAN created earlier in the profile only exists once applied
+        assert!(missing_interface("enp3s0.20", &["enp3s0.20".to_string()]).is_ok());
+        assert!(missing_interface("eth9", &["enp3s0.20".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_ipv4_link_local_clears_static_settings() {
+        let ipv4 = |method, addresses| NetworkAction::Ipv4Config {
+            interface: Some("enp3s0".to_string()),
+            method,
+            addresses,
+            gateway: Some("192.168.1.1".parse().unwrap()),
+        };
+        let address = Ipv4Address { address: "192.168.1.10".parse().unwrap(), prefix: 24 };
+        let mut settings = ConnectionSettings::new();
+        edit_connection(&ipv4(Ipv4Method::Manual, vec![address]), &ethernet(), &mut settings).unwrap();
+        settings.set_ipv4_dns(&["192.168.1.53".parse().unwrap()]);
+        edit_connection(&ipv4(Ipv4Method::LinkLocal, vec![]), &ethernet(), &mut settings).unwrap();
+
+        assert_eq!(settings.get_str("ipv4", "method").as_deref(), Some("link-local"));
+        assert_eq!(settings.get_str("ipv4", "gateway"), None);
+        let empty = ConnectionSettings::new();
+        assert!(settings.same_values(&empty, &[("ipv4", "address-data"), ("ipv4", "dns")]));
+    }
+
+    #[test]
+    fn test_ipv6_disabled_clears_static_settings() {
+        let mut settings = ConnectionSettings::new();
+        settings.set("ipv6", "gateway", "fd00::1");
+        settings.set_ipv6_dns(&["fd00::53".parse().unwrap()]);
+        edit_connection(&ipv6(Ipv6Method::Disabled, vec![]), &ethernet(), &mut settings).unwrap();
+
+        assert_eq!(settings.get_str("ipv6", "method").as_deref(), Some("disabled"));
+        assert_eq!(settings.get_str("ipv6", "gateway"), None);
+        let empty = ConnectionSettings::new();
+        assert!(settings.same_values(&empty, &[("ipv6", "address-data"), ("ipv6", "dns")]));
+    }
+}
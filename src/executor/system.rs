@@ -0,0 +1,284 @@
+// Network Manager - System Action Execution
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! System actions: proxy, hostname, timezone, printer, firewall, hosts
+//! entries and environment.
+
+use std::fmt;
+use std::process::Command;
+
+use super::environment::{self, EnvironmentSnapshot};
+use super::firewall::{self, FirewallSnapshot};
+use super::hosts;
+use super::proxy::{self, ProxySnapshot};
+use super::{command_stdout, ActionOutcome};
+use crate::models::actions::Action;
+use crate::models::plan::{ChangeKind, PlannedChange};
+use crate::models::{Error, Result, SystemAction};
+
+/// Apply a system action.
+pub(super) fn apply(action: &SystemAction) -> Result<ActionOutcome> {
+    match action {
+        SystemAction::ProxyConfig(config) => proxy::apply(config),
+
+        SystemAction::SetHostname { hostname, pretty_hostname } => {
+            // Set static hostname via hostnamectl
+            let output = Command::new("hostnamectl")
+                .args(["set-hostname", hostname])
+                .output()
+                .map_err(|e| failed(action, format!("Failed to set hostname: {}", e)))?;
+
+            if !output.status.success() {
+                return Err(failed(action, format!("Hostname change failed: {}", String::from_utf8_lossy(&output.stderr))));
+            }
+
+            // Set pretty hostname if provided
+            if let Some(pretty) = pretty_hostname {
+                let _ = Command::new("hostnamectl")
+                    .args(["set-hostname", "--pretty", pretty])
+                    .output();
+            }
+            Ok(ActionOutcome::Applied)
+        }
+
+        SystemAction::SetTimezone { timezone } => {
+            let output = Command::new("timedatectl")
+                .args(["set-timezone", timezone])
+                .output()
+                .map_err(|e| failed(action, format!("Failed to set timezone: {}", e)))?;
+
+            if !output.status.success() {
+                return Err(failed(action, format!("Timezone change failed: {}", String::from_utf8_lossy(&output.stderr))));
+            }
+            Ok(ActionOutcome::Applied)
+        }
+
+        SystemAction::DefaultPrinter { printer_name } => {
+            let output = Command::new("lpoptions")
+                .args(["-d", printer_name])
+                .output()
+                .map_err(|e| failed(action, format!("Failed to set default printer: {}", e)))?;
+
+            if !output.status.success() {
+                return Err(failed(action, format!("Printer change failed: {}", String::from_utf8_lossy(&output.stderr))));
+            }
+            Ok(ActionOutcome::Applied)
+        }
+
+        SystemAction::EnvironmentVariables { .. } => environment::apply(action),
+
+        SystemAction::FirewallConfig(config) => firewall::apply(config),
+
+        SystemAction::HostsEntries { .. } => hosts::apply(action),
+    }
+}
+
+/// Undo a system action when switching away from its profile.
+///
+/// Hosts entries and environment variables are removed; other settings
+/// stay until the next profile changes them.
+pub(super) fn release(action: &SystemAction) -> Result<ActionOutcome> {
+    match action {
+        SystemAction::HostsEntries { .. } => hosts::release(action),
+        SystemAction::EnvironmentVariables { .. } => environment::release(action),
+        _ => Ok(ActionOutcome::Skipped("Nothing to release".to_string())),
+    }
+}
+
+/// Build an action failure for a system action.
+fn failed(action: &SystemAction, reason: impl Into<String>) -> Error {
+    Error::action_failed(action.name(), reason)
+}
+
+/// System configuration captured before a system action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SystemSnapshot {
+    /// Proxy settings of each backend.
+    Proxy(ProxySnapshot),
+    /// Hostname and pretty hostname.
+    Hostname {
+        /// Hostname.
+        hostname: String,
+        /// Pretty hostname (empty if unset).
+        pretty: String,
+    },
+    /// Timezone name.
+    Timezone(String),
+    /// Default printer, if one was set.
+    DefaultPrinter(Option<String>),
+    /// Firewall zones and rules.
+    Firewall(FirewallSnapshot),
+    /// Lines of the managed `/etc/hosts` block.
+    Hosts(Vec<String>),
+    /// Managed session environment.
+    Environment(EnvironmentSnapshot),
+}
+
+impl fmt::Display for SystemSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Proxy(snapshot) => snapshot.fmt(f),
+            Self::Hostname { hostname, pretty } if pretty.is_empty() => write!(f, "{}", hostname),
+            Self::Hostname { hostname, pretty } => write!(f, "{} ({})", hostname, pretty),
+            Self::Timezone(timezone) => write!(f, "{}", timezone),
+            Self::DefaultPrinter(printer) => write!(f, "{}", printer.as_deref().unwrap_or("none")),
+            Self::Firewall(snapshot) => snapshot.fmt(f),
+            Self::Hosts(lines) if lines.is_empty() => write!(f, "No managed hosts entries"),
+            Self::Hosts(lines) => write!(f, "{}", lines.join("\n")),
+            Self::Environment(snapshot) => snapshot.fmt(f),
+        }
+    }
+}
+
+/// Capture the setting a system action is about to change.
+///
+/// Fails if the current setting could not be read, so the action is not
+/// applied without a way back.
+pub(super) fn capture_state(action: &SystemAction) -> Result<SystemSnapshot> {
+    Ok(match action {
+        SystemAction::ProxyConfig(config) => SystemSnapshot::Proxy(proxy::capture(config)?),
+        SystemAction::SetHostname { .. } => {
+            let hostname = std::fs::read_to_string("/proc/sys/kernel/hostname")?.trim().to_string();
+            let pretty = command_stdout("hostnamectl", &["status", "--pretty"]).unwrap_or_default();
+            SystemSnapshot::Hostname { hostname, pretty }
+        }
+        SystemAction::SetTimezone { .. } => SystemSnapshot::Timezone(
+            command_stdout("timedatectl", &["show", "-p", "Timezone", "--value"])
+                .ok_or_else(|| failed(action, "Failed to read the current timezone"))?,
+        ),
+        SystemAction::DefaultPrinter { .. } => {
+            let output = command_stdout("lpstat", &["-d"]).unwrap_or_default();
+            SystemSnapshot::DefaultPrinter(parse_default_printer(&output))
+        }
+        SystemAction::FirewallConfig(config) => SystemSnapshot::Firewall(firewall::capture(config)?),
+        SystemAction::HostsEntries { .. } => SystemSnapshot::Hosts(hosts::managed_block()?),
+        SystemAction::EnvironmentVariables { .. } => SystemSnapshot::Environment(environment::capture(action)?),
+    })
+}
+
+/// State a system action would leave behind, given the current state.
+fn desired_state(action: &SystemAction, current: Option<&SystemSnapshot>) -> Option<SystemSnapshot> {
+    match action {
+        SystemAction::SetHostname { hostname, pretty_hostname } => {
+            // Without a pretty hostname, the current one is left alone.
+            let pretty = pretty_hostname.clone().or_else(|| match current {
+                Some(SystemSnapshot::Hostname { pretty, .. }) => Some(pretty.clone()),
+                _ => None,
+            });
+            Some(SystemSnapshot::Hostname { hostname: hostname.clone(), pretty: pretty.unwrap_or_default() })
+        }
+        SystemAction::SetTimezone { timezone } => Some(SystemSnapshot::Timezone(timezone.clone())),
+        SystemAction::DefaultPrinter { printer_name } => {
+            Some(SystemSnapshot::DefaultPrinter(Some(printer_name.clone())))
+        }
+        SystemAction::HostsEntries { .. } => match current {
+            Some(SystemSnapshot::Hosts(block)) => hosts::merged_block(action, block).ok().map(SystemSnapshot::Hosts),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Describe what a system action would change.
+pub(super) fn plan(action: &SystemAction) -> PlannedChange {
+    let wrapped = Action::System(action.clone());
+    match action {
+        SystemAction::ProxyConfig(config) => return proxy::plan(action, config),
+        SystemAction::EnvironmentVariables { .. } => return environment::plan(action),
+        SystemAction::FirewallConfig(config) => return firewall::plan(action, config),
+        SystemAction::HostsEntries { .. } => {
+            if let Err(e) = hosts::merged_block(action, &[]) {
+                return PlannedChange::unsupported(wrapped, e.to_string());
+            }
+        }
+        _ => {}
+    }
+
+    let current = match capture_state(action) {
+        Ok(current) => Some(current),
+        Err(e) => return PlannedChange::unsupported(wrapped, e.to_string()),
+    };
+    let Some(desired) = desired_state(action, current.as_ref()) else {
+        return PlannedChange::unsupported(wrapped, "Not yet implemented");
+    };
+    let kind = if current.as_ref() == Some(&desired) { ChangeKind::NoOp } else { ChangeKind::Change };
+    PlannedChange::new(wrapped, kind, current.map(|c| c.to_string()), desired.to_string())
+}
+
+/// Extract the printer name from `lpstat -d` output.
+fn parse_default_printer(output: &str) -> Option<String> {
+    // "system default destination: NAME" or "no system default destination"
+    output
+        .split_once("destination:")
+        .map(|(_, name)| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Restore system configuration captured before an action.
+pub(super) fn restore(snapshot: &SystemSnapshot) -> Result<ActionOutcome> {
+    match snapshot {
+        SystemSnapshot::Proxy(snapshot) => proxy::restore(snapshot)?,
+        SystemSnapshot::Hostname { hostname, pretty } => {
+            restore_command("hostname", "hostnamectl", &["set-hostname", hostname])?;
+            restore_command("hostname", "hostnamectl", &["set-hostname", "--pretty", pretty])?;
+        }
+        SystemSnapshot::Timezone(timezone) => {
+            restore_command("timezone", "timedatectl", &["set-timezone", timezone])?;
+        }
+        SystemSnapshot::DefaultPrinter(Some(printer)) => {
+            restore_command("default printer", "lpoptions", &["-d", printer])?;
+        }
+        SystemSnapshot::DefaultPrinter(None) => {
+            return Ok(ActionOutcome::Skipped("No default printer was set before".to_string()));
+        }
+        SystemSnapshot::Firewall(snapshot) => firewall::restore(snapshot)?,
+        SystemSnapshot::Hosts(block) => hosts::restore(block)?,
+        SystemSnapshot::Environment(snapshot) => environment::restore(snapshot)?,
+    }
+    Ok(ActionOutcome::Applied)
+}
+
+/// Run a command that restores a setting, failing on a non-zero exit.
+fn restore_command(what: &str, program: &str, args: &[&str]) -> Result<()> {
+    let name = format!("Restore {}", what);
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| Error::action_failed(&name, format!("Failed to run {}: {}", program, e)))?;
+    if !output.status.success() {
+        return Err(Error::action_failed(&name, String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_default_printer() {
+        assert_eq!(
+            parse_default_printer("system default destination: Office_Laser").as_deref(),
+            Some("Office_Laser")
+        );
+        assert_eq!(parse_default_printer("no system default destination"), None);
+        assert_eq!(parse_default_printer(""), None);
+    }
+
+    #[test]
+    fn test_desired_hostname_keeps_pretty_name() {
+        let current = SystemSnapshot::Hostname { hostname: "laptop".to_string(), pretty: "Laptop".to_string() };
+        let action = SystemAction::SetHostname { hostname: "laptop".to_string(), pretty_hostname: None };
+        assert_eq!(desired_state(&action, Some(&current)), Some(current));
+    }
+
+    #[test]
+    fn test_snapshot_display() {
+        let hostname = SystemSnapshot::Hostname { hostname: "laptop".to_string(), pretty: String::new() };
+        assert_eq!(hostname.to_string(), "laptop");
+
+        let printer = SystemSnapshot::DefaultPrinter(None);
+        assert_eq!(printer.to_string(), "none");
+    }
+}
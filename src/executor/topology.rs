@@ -0,0 +1,386 @@
+// Network Manager - Bond and Bridge Topology
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Bonds and bridges, built from a master connection plus one port
+//! connection per attached interface.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::ActionOutcome;
+use crate::models::actions::Action;
+use crate::models::plan::PlannedChange;
+use crate::models::validation::{validate_forward_delay, validate_ports};
+use crate::models::{Error, NetworkAction, Result};
+use crate::nm::{ConnectionSettings, DeviceType, NmClient, SavedConnection};
+
+/// Master interface and ports declared by a bond or bridge action.
+struct Topology<'a> {
+    /// NetworkManager connection type of the master (`bond` or `bridge`).
+    kind: &'static str,
+    /// Master interface name.
+    name: &'a str,
+    /// Attached interfaces.
+    ports: &'a [String],
+}
+
+/// The topology an action declares, if it is a bond or bridge action.
+fn topology(action: &NetworkAction) -> Option<Topology<'_>> {
+    match action {
+        NetworkAction::Bond { name, slaves, .. } => Some(Topology { kind: "bond", name, ports: slaves }),
+        NetworkAction::Bridge { name, ports, .. } => Some(Topology { kind: "bridge", name, ports }),
+        _ => None,
+    }
+}
+
+/// Build an action failure for a topology action.
+fn failed(action: &NetworkAction, reason: impl Into<String>) -> Error {
+    Error::action_failed(action.name(), reason)
+}
+
+/// Validate a bond or bridge action.
+fn validate(action: &NetworkAction) -> Result<Topology<'_>> {
+    let topology = topology(action).ok_or_else(|| failed(action, "Not a bond or bridge action"))?;
+    validate_ports(topology.name, topology.ports)?;
+    match action {
+        NetworkAction::Bond { slaves, .. } if slaves.is_empty() => {
+            Err(failed(action, "A bond needs at least one slave interface"))
+        }
+        NetworkAction::Bridge { forward_delay: Some(delay), .. } => {
+            validate_forward_delay(*delay)?;
+            Ok(topology)
+        }
+        _ => Ok(topology),
+    }
+}
+
+/// Settings of the master connection an action changes.
+fn master_keys(kind: &str) -> &'static [(&'static str, &'static str)] {
+    match kind {
+        "bond" => &[("bond", "options")],
+        _ => &[("bridge", "stp"), ("bridge", "forward-delay")],
+    }
+}
+
+/// Apply an action's bond or bridge options to master settings.
+fn edit_master(action: &NetworkAction, settings: &mut ConnectionSettings) {
+    match action {
+        NetworkAction::Bond { mode, miimon, .. } => {
+            let options = HashMap::from([
+                ("mode".to_string(), mode.as_str().to_string()),
+                ("miimon".to_string(), miimon.to_string()),
+            ]);
+            settings.set("bond", "options", options);
+        }
+        NetworkAction::Bridge { stp, forward_delay, .. } => {
+            settings.set("bridge", "stp", *stp);
+            match forward_delay {
+                Some(delay) => settings.set("bridge", "forward-delay", *delay),
+                None => settings.remove("bridge", "forward-delay"),
+            }
+        }
+        _ => {}
+    }
+    // Bring the ports up whenever the master is activated.
+    settings.set("connection", "autoconnect-slaves", 1i32);
+}
+
+/// Settings for a new master connection.
+fn new_master(kind: &str, name: &str) -> ConnectionSettings {
+    let mut settings = ConnectionSettings::new();
+    settings.set("connection", "id", name);
+    settings.set("connection", "uuid", uuid::Uuid::new_v4().to_string());
+    settings.set("connection", "type", kind);
+    settings.set("connection", "interface-name", name);
+    settings.mark_created();
+    settings
+}
+
+/// Settings for a new port connection attaching `interface` to `master`.
+fn new_port(kind: &str, master: &str, interface: &str) -> ConnectionSettings {
+    let mut settings = ConnectionSettings::new();
+    settings.set("connection", "id", format!("{} port {}", master, interface));
+    settings.set("connection", "uuid", uuid::Uuid::new_v4().to_string());
+    settings.set("connection", "type", "802-3-ethernet");
+    settings.set("connection", "interface-name", interface);
+    settings.set("connection", "master", master);
+    settings.set("connection", "slave-type", kind);
+    settings.mark_created();
+    settings
+}
+
+/// Create or update a bond or bridge and attach its ports.
+pub(super) fn apply(nm: &NmClient, action: &NetworkAction) -> Result<ActionOutcome> {
+    let before = capture(nm, action)?;
+    if let Err(e) = build(nm, action) {
+        // Do not leave a half-built topology behind.
+        if let Err(restore_err) = restore(nm, &before) {
+            tracing::warn!("Failed to undo partial {}: {}", action.name(), restore_err);
+        }
+        return Err(e);
+    }
+    Ok(ActionOutcome::Applied)
+}
+
+/// Create or update the master and port connections, then activate them.
+fn build(nm: &NmClient, action: &NetworkAction) -> Result<()> {
+    let topology = validate(action)?;
+
+    let master = match nm.virtual_connection(topology.kind, topology.name)? {
+        Some(mut conn) => {
+            edit_master(action, &mut conn.settings);
+            nm.update_connection(&conn)?;
+            conn
+        }
+        None => {
+            let mut settings = new_master(topology.kind, topology.name);
+            edit_master(action, &mut settings);
+            let path = nm.add_connection(&settings)?;
+            nm.load_connection(path)?
+        }
+    };
+
+    let mut ports = Vec::new();
+    for interface in topology.ports {
+        let device = nm.device_by_interface(interface)?;
+        if device.device_type != DeviceType::Ethernet {
+            return Err(failed(action, format!("{} is not an Ethernet interface", interface)));
+        }
+        let conn = match nm.port_connection(&master, interface)? {
+            Some(conn) => conn,
+            None => {
+                let path = nm.add_connection(&new_port(topology.kind, topology.name, interface))?;
+                nm.load_connection(path)?
+            }
+        };
+        ports.push((device, conn));
+    }
+
+    nm.activate(&master, None)?;
+    for (device, conn) in &ports {
+        if nm.active_connection_of(conn)?.is_none() {
+            nm.activate(conn, Some(device))?;
+        }
+    }
+    Ok(())
+}
+
+/// An interface attached by a topology action, before the action.
+#[derive(Debug, Clone)]
+struct PortState {
+    /// Interface name.
+    interface: String,
+    /// Whether a port connection to the master already existed.
+    had_port: bool,
+    /// Connection that was active on the interface.
+    previous: Option<SavedConnection>,
+}
+
+/// A bond or bridge as it was before an action created or changed it.
+#[derive(Debug, Clone)]
+pub struct TopologySnapshot {
+    /// Master connection type.
+    kind: &'static str,
+    /// Master interface name.
+    name: String,
+    /// Master connection as saved before, or `None` if it did not exist.
+    master: Option<SavedConnection>,
+    /// Whether the master was active.
+    active: bool,
+    /// Attached interfaces.
+    ports: Vec<PortState>,
+}
+
+impl fmt::Display for TopologySnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.master {
+            None => write!(f, "{} absent", self.name)?,
+            Some(conn) => {
+                let state = if self.active { "active" } else { "inactive" };
+                write!(f, "{} {}\n{}", self.name, state, conn.settings.describe(master_keys(self.kind)))?;
+            }
+        }
+        for port in &self.ports {
+            let previous = port.previous.as_ref().map(SavedConnection::id);
+            write!(f, "\n{}: {}", port.interface, previous.as_deref().unwrap_or("disconnected"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Capture the topology an action is about to change.
+pub(super) fn capture(nm: &NmClient, action: &NetworkAction) -> Result<TopologySnapshot> {
+    let topology = validate(action)?;
+    let master = nm.virtual_connection(topology.kind, topology.name)?;
+    let active = match &master {
+        Some(conn) => nm.active_connection_of(conn)?.is_some(),
+        None => false,
+    };
+    let active_connections = nm.active_connections()?;
+
+    let mut ports = Vec::new();
+    for interface in topology.ports {
+        let had_port = match &master {
+            Some(conn) => nm.port_connection(conn, interface)?.is_some(),
+            None => false,
+        };
+        let previous = active_connections
+            .iter()
+            .find(|a| a.interfaces.contains(interface))
+            .map(|a| nm.load_connection(a.connection.clone()))
+            .transpose()?;
+        ports.push(PortState { interface: interface.clone(), had_port, previous });
+    }
+
+    Ok(TopologySnapshot {
+        kind: topology.kind,
+        name: topology.name.to_string(),
+        master,
+        active,
+        ports,
+    })
+}
+
+/// Restore a bond or bridge to its captured state.
+pub(super) fn restore(nm: &NmClient, snapshot: &TopologySnapshot) -> Result<ActionOutcome> {
+    let master = nm.virtual_connection(snapshot.kind, &snapshot.name)?;
+
+    if let Some(conn) = &master {
+        for port in snapshot.ports.iter().filter(|p| !p.had_port) {
+            if let Some(port_conn) = nm.port_connection(conn, &port.interface)? {
+                nm.delete_connection(&port_conn)?;
+            }
+        }
+    }
+
+    match (&snapshot.master, master) {
+        (None, Some(conn)) => nm.delete_connection(&conn)?,
+        (Some(before), Some(_)) => {
+            nm.update_connection(before)?;
+            if !snapshot.active {
+                if let Some(active) = nm.active_connection_of(before)? {
+                    nm.deactivate(&active)?;
+                }
+            }
+        }
+        _ => {}
+    }
+
+    // Give the interfaces back the connections they had.
+    for port in &snapshot.ports {
+        let Some(previous) = &port.previous else { continue };
+        let Ok(conn) = nm.load_connection(previous.path.clone()) else {
+            tracing::warn!("Cannot reactivate '{}': connection no longer exists", previous.id());
+            continue;
+        };
+        if nm.active_connection_of(&conn)?.is_none() {
+            let device = nm.device_by_interface(&port.interface)?;
+            nm.activate(&conn, Some(&device))?;
+        }
+    }
+    Ok(ActionOutcome::Applied)
+}
+
+/// Describe what a bond or bridge action would change.
+pub(super) fn plan(nm: &NmClient, action: &NetworkAction) -> Result<PlannedChange> {
+    let wrapped = Action::Network(action.clone());
+    let topology = validate(action)?;
+    let keys = master_keys(topology.kind);
+    let master = nm.virtual_connection(topology.kind, topology.name)?;
+
+    let mut desired = master.as_ref().map(|m| m.settings.clone()).unwrap_or_default();
+    edit_master(action, &mut desired);
+    let desired = format!(
+        "{} active\n{}\nports: {}",
+        topology.name,
+        desired.describe(keys),
+        topology.ports.join(", ")
+    );
+
+    let Some(master) = master else {
+        return Ok(PlannedChange::compare(wrapped, Some(format!("{} absent", topology.name)), desired));
+    };
+    let state = if nm.active_connection_of(&master)?.is_some() { "active" } else { "inactive" };
+    let mut attached = Vec::new();
+    for interface in topology.ports {
+        if let Some(port) = nm.port_connection(&master, interface)? {
+            if nm.active_connection_of(&port)?.is_some() {
+                attached.push(interface.as_str());
+            }
+        }
+    }
+    let current = format!(
+        "{} {}\n{}\nports: {}",
+        topology.name,
+        state,
+        master.settings.describe(keys),
+        attached.join(", ")
+    );
+    Ok(PlannedChange::compare(wrapped, Some(current), desired))
+}
+
+/// Remove a bond or bridge created by this application, with its ports.
+pub(super) fn release(nm: &NmClient, action: &NetworkAction) -> Result<ActionOutcome> {
+    let topology = validate(action)?;
+    let Some(master) = nm.virtual_connection(topology.kind, topology.name)? else {
+        return Ok(ActionOutcome::Skipped(format!("{} no longer exists", topology.name)));
+    };
+    if !master.settings.is_created() {
+        return Ok(ActionOutcome::Skipped(format!("{} was not created by a profile", topology.name)));
+    }
+    for interface in topology.ports {
+        if let Some(port) = nm.port_connection(&master, interface)? {
+            if port.settings.is_created() {
+                nm.delete_connection(&port)?;
+            }
+        }
+    }
+    nm.delete_connection(&master)?;
+    Ok(ActionOutcome::Applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BondMode;
+
+    fn bond(slaves: &[&str]) -> NetworkAction {
+        NetworkAction::Bond {
+            name: "bond0".to_string(),
+            mode: BondMode::Lacp,
+            miimon: 100,
+            slaves: slaves.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&bond(&["enp3s0", "enp4s0"])).is_ok());
+        assert!(validate(&bond(&[])).is_err());
+        assert!(validate(&bond(&["enp3s0", "enp3s0"])).is_err());
+
+        let bridge = NetworkAction::Bridge {
+            name: "br0".to_string(),
+            ports: Vec::new(),
+            stp: true,
+            forward_delay: Some(40),
+        };
+        assert!(validate(&bridge).is_err());
+    }
+
+    #[test]
+    fn test_master_and_port_settings() {
+        let mut master = new_master("bond", "bond0");
+        edit_master(&bond(&["enp3s0"]), &mut master);
+        assert_eq!(master.connection_type().as_deref(), Some("bond"));
+        let options = master.describe(master_keys("bond"));
+        assert!(options.contains("802.3ad") && options.contains("miimon"));
+        assert!(master.is_created());
+
+        let port = new_port("bond", "bond0", "enp3s0");
+        assert_eq!(port.get_str("connection", "master").as_deref(), Some("bond0"));
+        assert_eq!(port.get_str("connection", "slave-type").as_deref(), Some("bond"));
+        assert_eq!(port.interface_name().as_deref(), Some("enp3s0"));
+    }
+}
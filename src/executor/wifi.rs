@@ -0,0 +1,401 @@
+// Network Manager - Wi-Fi Connections
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Wi-Fi connections: activating saved ones, or creating and updating them
+//! from the security settings of an action.
+//!
+//! Passphrases and 802.1X passwords reach this module decrypted; they are
+//! only ever written to NetworkManager.
+
+use std::fmt;
+
+use super::network::restore_active;
+use super::ActionOutcome;
+use crate::models::actions::Action;
+use crate::models::plan::PlannedChange;
+use crate::models::validation::{validate_interface_name, validate_mac_address, validate_psk, validate_ssid};
+use crate::models::{EapMethod, Error, NetworkAction, Result, Secret, WifiSecurity};
+use crate::nm::{ConnectionSettings, DeviceType, NmClient, SavedConnection};
+
+/// Settings of a Wi-Fi connection an action changes (secrets excluded).
+const WIFI_KEYS: &[(&str, &str)] = &[
+    ("connection", "interface-name"),
+    ("802-11-wireless", "hidden"),
+    ("802-11-wireless", "bssid"),
+    ("802-11-wireless-security", "key-mgmt"),
+    ("802-1x", "eap"),
+    ("802-1x", "identity"),
+    ("802-1x", "anonymous-identity"),
+    ("802-1x", "phase2-auth"),
+    ("802-1x", "ca-cert"),
+    ("802-1x", "client-cert"),
+    ("802-1x", "private-key"),
+];
+
+/// Build an action failure for a Wi-Fi action.
+fn failed(action: &NetworkAction, reason: impl Into<String>) -> Error {
+    Error::action_failed(action.name(), reason)
+}
+
+/// Validate a Wi-Fi action, returning its SSID.
+fn validate(action: &NetworkAction) -> Result<&str> {
+    let NetworkAction::WifiConnect { ssid, interface, security, bssid, .. } = action else {
+        return Err(failed(action, "Not a Wi-Fi action"));
+    };
+    validate_ssid(ssid)?;
+    if let Some(interface) = interface {
+        validate_interface_name(interface)?;
+    }
+    if let Some(bssid) = bssid {
+        validate_mac_address(bssid)?;
+    }
+
+    match security {
+        None | Some(WifiSecurity::Open) => {}
+        Some(WifiSecurity::Wpa2Psk { psk } | WifiSecurity::Wpa3Psk { psk }) => {
+            if let Some(psk) = psk.plaintext() {
+                validate_psk(psk)?;
+            }
+        }
+        Some(WifiSecurity::Enterprise(auth)) => {
+            if auth.identity.trim().is_empty() {
+                return Err(failed(action, "An 802.1X identity is required"));
+            }
+            match auth.eap {
+                EapMethod::Peap | EapMethod::Ttls if auth.password.is_none() => {
+                    return Err(failed(action, "PEAP and TTLS need a password"));
+                }
+                EapMethod::Tls if auth.client_cert.is_none() || auth.private_key.is_none() => {
+                    return Err(failed(action, "TLS needs a client certificate and private key"));
+                }
+                _ => {}
+            }
+            let paths = [&auth.ca_cert, &auth.client_cert, &auth.private_key];
+            if let Some(path) = paths.into_iter().flatten().find(|p| !p.is_absolute()) {
+                return Err(failed(action, format!("Certificate path must be absolute: {}", path.display())));
+            }
+        }
+    }
+    Ok(ssid)
+}
+
+/// Whether an action changes the saved connection, not just activates it.
+fn edits(action: &NetworkAction) -> bool {
+    matches!(
+        action,
+        NetworkAction::WifiConnect { security, hidden, bssid, .. }
+            if security.is_some() || *hidden || bssid.is_some()
+    )
+}
+
+/// Fail if any secret of an action is still encrypted.
+fn check_decrypted(action: &NetworkAction) -> Result<()> {
+    if action.clone().secrets_mut().iter().any(|s| s.plaintext().is_none()) {
+        return Err(failed(action, "The Wi-Fi credentials are encrypted and could not be decrypted"));
+    }
+    Ok(())
+}
+
+/// Set a secret if it is available in plaintext.
+fn set_secret(settings: &mut ConnectionSettings, setting: &str, key: &str, secret: Option<&Secret>) {
+    match secret.and_then(Secret::plaintext) {
+        Some(value) => settings.set(setting, key, value),
+        None => settings.remove(setting, key),
+    }
+}
+
+/// Apply an action's Wi-Fi settings (secrets only if decrypted).
+fn edit_wifi(action: &NetworkAction, settings: &mut ConnectionSettings) -> Result<()> {
+    let NetworkAction::WifiConnect { ssid, interface, security, hidden, bssid } = action else {
+        return Err(failed(action, "Not a Wi-Fi action"));
+    };
+
+    settings.set("802-11-wireless", "ssid", ssid.as_bytes().to_vec());
+    settings.set("802-11-wireless", "mode", "infrastructure");
+    settings.set("802-11-wireless", "hidden", *hidden);
+    match bssid {
+        Some(bssid) => {
+            let octets = validate_mac_address(bssid)?
+                .split(':')
+                .map(|octet| u8::from_str_radix(octet, 16))
+                .collect::<std::result::Result<Vec<u8>, _>>()
+                .map_err(|_| Error::InvalidMacAddress(bssid.clone()))?;
+            settings.set("802-11-wireless", "bssid", octets);
+        }
+        None => settings.remove("802-11-wireless", "bssid"),
+    }
+    match interface {
+        Some(interface) => settings.set("connection", "interface-name", interface.as_str()),
+        None => settings.remove("connection", "interface-name"),
+    }
+
+    let Some(security) = security else {
+        return Ok(());
+    };
+    // Older NetworkManager releases link the security setting explicitly.
+    settings.remove("802-11-wireless", "security");
+    match security {
+        WifiSecurity::Open => {
+            settings.remove_setting("802-11-wireless-security");
+            settings.remove_setting("802-1x");
+        }
+        WifiSecurity::Wpa2Psk { psk } | WifiSecurity::Wpa3Psk { psk } => {
+            let key_mgmt = if matches!(security, WifiSecurity::Wpa3Psk { .. }) { "sae" } else { "wpa-psk" };
+            settings.remove_setting("802-1x");
+            settings.set("802-11-wireless-security", "key-mgmt", key_mgmt);
+            set_secret(settings, "802-11-wireless-security", "psk", Some(psk));
+        }
+        WifiSecurity::Enterprise(auth) => {
+            // Start from a clean 802.1X setting so no stale method options remain.
+            settings.remove_setting("802-1x");
+            settings.remove("802-11-wireless-security", "psk");
+            settings.set("802-11-wireless-security", "key-mgmt", "wpa-eap");
+            settings.set("802-1x", "eap", vec![auth.eap.as_str().to_string()]);
+            settings.set("802-1x", "identity", auth.identity.trim());
+            if let Some(anonymous) = &auth.anonymous_identity {
+                settings.set("802-1x", "anonymous-identity", anonymous.as_str());
+            }
+            if let Some(ca_cert) = &auth.ca_cert {
+                settings.set_cert_path("802-1x", "ca-cert", ca_cert);
+            }
+            match auth.eap {
+                EapMethod::Peap | EapMethod::Ttls => {
+                    let phase2 = auth.phase2_auth.as_deref().unwrap_or("mschapv2");
+                    settings.set("802-1x", "phase2-auth", phase2);
+                    set_secret(settings, "802-1x", "password", auth.password.as_ref());
+                }
+                EapMethod::Tls => {
+                    if let Some(client_cert) = &auth.client_cert {
+                        settings.set_cert_path("802-1x", "client-cert", client_cert);
+                    }
+                    if let Some(private_key) = &auth.private_key {
+                        settings.set_cert_path("802-1x", "private-key", private_key);
+                    }
+                    set_secret(settings, "802-1x", "private-key-password", auth.private_key_password.as_ref());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Settings for a new Wi-Fi connection.
+fn new_wifi(ssid: &str) -> ConnectionSettings {
+    let mut settings = ConnectionSettings::new();
+    settings.set("connection", "id", ssid);
+    settings.set("connection", "uuid", uuid::Uuid::new_v4().to_string());
+    settings.set("connection", "type", "802-11-wireless");
+    settings.set("ipv4", "method", "auto");
+    settings.set("ipv6", "method", "auto");
+    settings.mark_created();
+    settings
+}
+
+/// Connect to a Wi-Fi network, creating or updating its connection first.
+pub(super) fn apply(nm: &NmClient, action: &NetworkAction) -> Result<ActionOutcome> {
+    let ssid = validate(action)?;
+    let NetworkAction::WifiConnect { interface, security, .. } = action else {
+        return Err(failed(action, "Not a Wi-Fi action"));
+    };
+    let device = interface.as_deref().map(|i| nm.device_by_interface(i)).transpose()?;
+    if let Some(device) = &device {
+        if device.device_type != DeviceType::Wifi {
+            return Err(failed(action, format!("{} is not a Wi-Fi interface", device.interface)));
+        }
+    }
+
+    let saved = nm.wifi_connection(ssid)?;
+    if !edits(action) {
+        let conn = saved.ok_or_else(|| failed(action, format!("No saved connection for SSID '{}'", ssid)))?;
+        nm.activate(&conn, device.as_ref())?;
+        return Ok(ActionOutcome::Applied);
+    }
+    check_decrypted(action)?;
+
+    match saved {
+        Some(mut conn) => {
+            edit_wifi(action, &mut conn.settings)?;
+            nm.update_connection(&conn)?;
+            nm.activate(&conn, device.as_ref())?;
+        }
+        None if security.is_none() => {
+            return Err(failed(
+                action,
+                format!("No saved connection for SSID '{}' and no security settings to create one", ssid),
+            ));
+        }
+        None => {
+            let mut settings = new_wifi(ssid);
+            edit_wifi(action, &mut settings)?;
+            let conn = nm.load_connection(nm.add_connection(&settings)?)?;
+            if let Err(e) = nm.activate(&conn, device.as_ref()) {
+                // Do not keep a connection whose credentials never worked.
+                if let Err(delete_err) = nm.delete_connection(&conn) {
+                    tracing::warn!("Failed to remove Wi-Fi connection {}: {}", ssid, delete_err);
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(ActionOutcome::Applied)
+}
+
+/// Wi-Fi state as it was before an action connected.
+#[derive(Debug, Clone)]
+pub struct WifiSnapshot {
+    /// SSID of the action.
+    ssid: String,
+    /// Whether the action changes the saved connection.
+    edits: bool,
+    /// Saved connection with its secrets, or `None` if it did not exist.
+    previous: Option<SavedConnection>,
+    /// IDs of the connections that were active.
+    active: Vec<String>,
+}
+
+impl fmt::Display for WifiSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.active.join("\n"))?;
+        match (&self.previous, self.edits) {
+            (Some(conn), true) => write!(f, "\n{}\n{}", self.ssid, conn.settings.describe(WIFI_KEYS)),
+            (None, true) => write!(f, "\n{} absent", self.ssid),
+            (_, false) => Ok(()),
+        }
+    }
+}
+
+/// Capture the connections a Wi-Fi action is about to change.
+pub(super) fn capture(nm: &NmClient, action: &NetworkAction) -> Result<WifiSnapshot> {
+    let ssid = validate(action)?;
+    let edits = edits(action);
+    let mut previous = if edits { nm.wifi_connection(ssid)? } else { None };
+    if let Some(conn) = &mut previous {
+        for setting in ["802-11-wireless-security", "802-1x"] {
+            // Open networks have no secrets, and agent-owned secrets are not stored.
+            if let Err(e) = nm.load_secrets(conn, setting) {
+                tracing::debug!("No {} secrets for '{}': {}", setting, ssid, e);
+            }
+        }
+    }
+    let active = nm.active_connections()?.into_iter().map(|a| a.id).collect();
+    Ok(WifiSnapshot { ssid: ssid.to_string(), edits, previous, active })
+}
+
+/// Restore the saved connection and the active connections.
+pub(super) fn restore(nm: &NmClient, snapshot: &WifiSnapshot) -> Result<ActionOutcome> {
+    if snapshot.edits {
+        match (&snapshot.previous, nm.wifi_connection(&snapshot.ssid)?) {
+            (None, Some(conn)) if conn.settings.is_created() => nm.delete_connection(&conn)?,
+            (Some(before), Some(_)) => nm.update_connection(before)?,
+            _ => {}
+        }
+    }
+    restore_active(nm, &snapshot.active)
+}
+
+/// Describe what a Wi-Fi action would change.
+pub(super) fn plan(nm: &NmClient, action: &NetworkAction) -> Result<PlannedChange> {
+    let wrapped = Action::Network(action.clone());
+    let ssid = validate(action)?;
+    let NetworkAction::WifiConnect { security, .. } = action else {
+        return Err(failed(action, "Not a Wi-Fi action"));
+    };
+    let saved = nm.wifi_connection(ssid)?;
+    let current_ssid = nm.current_ssid()?;
+
+    if saved.is_none() && security.is_none() {
+        return Ok(PlannedChange::unsupported(wrapped, format!("No saved connection for SSID '{}'", ssid)));
+    }
+    if !edits(action) {
+        return Ok(PlannedChange::compare(wrapped, current_ssid, ssid));
+    }
+
+    let mut desired = saved.as_ref().map(|c| c.settings.clone()).unwrap_or_else(|| new_wifi(ssid));
+    edit_wifi(action, &mut desired)?;
+    let desired = format!("{}\n{}", ssid, desired.describe(WIFI_KEYS));
+    let current = format!(
+        "{}\n{}",
+        current_ssid.as_deref().unwrap_or("disconnected"),
+        saved.map_or_else(|| format!("{} absent", ssid), |c| c.settings.describe(WIFI_KEYS)),
+    );
+    Ok(PlannedChange::compare_with_secrets(wrapped, Some(current), desired))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::EnterpriseAuth;
+    use std::path::PathBuf;
+
+    fn wifi(security: Option<WifiSecurity>) -> NetworkAction {
+        NetworkAction::WifiConnect {
+            ssid: "Office".to_string(),
+            interface: Some("wlan0".to_string()),
+            security,
+            hidden: true,
+            bssid: Some("aa:bb:cc:dd:ee:ff".to_string()),
+        }
+    }
+
+    fn peap() -> EnterpriseAuth {
+        EnterpriseAuth {
+            eap: EapMethod::Peap,
+            identity: "alice".to_string(),
+            anonymous_identity: None,
+            password: Some(Secret::Plain("hunter22".to_string())),
+            phase2_auth: None,
+            ca_cert: Some(PathBuf::from("/etc/ssl/office-ca.pem")),
+            client_cert: None,
+            private_key: None,
+            private_key_password: None,
+        }
+    }
+
+    #[test]
+    fn test_psk_settings() {
+        let action = wifi(Some(WifiSecurity::Wpa3Psk { psk: Secret::Plain("correct horse".to_string()) }));
+        let mut settings = new_wifi(validate(&action).unwrap());
+        edit_wifi(&action, &mut settings).unwrap();
+
+        assert_eq!(settings.ssid().as_deref(), Some(b"Office".as_slice()));
+        assert_eq!(settings.get_str("connection", "interface-name").as_deref(), Some("wlan0"));
+        assert_eq!(settings.get_str("802-11-wireless-security", "key-mgmt").as_deref(), Some("sae"));
+        assert_eq!(settings.get_str("802-11-wireless-security", "psk").as_deref(), Some("correct horse"));
+        assert!(settings.describe(WIFI_KEYS).contains("hidden"));
+        assert!(settings.is_created());
+
+        // Switching to an open network drops the security setting entirely.
+        let open = wifi(Some(WifiSecurity::Open));
+        edit_wifi(&open, &mut settings).unwrap();
+        assert!(settings.get("802-11-wireless-security", "key-mgmt").is_none());
+    }
+
+    #[test]
+    fn test_enterprise_settings() {
+        let action = wifi(Some(WifiSecurity::Enterprise(peap())));
+        let mut settings = new_wifi("Office");
+        edit_wifi(&action, &mut settings).unwrap();
+
+        assert_eq!(settings.get_str("802-11-wireless-security", "key-mgmt").as_deref(), Some("wpa-eap"));
+        assert_eq!(settings.get_str("802-1x", "phase2-auth").as_deref(), Some("mschapv2"));
+        assert_eq!(settings.get_str("802-1x", "password").as_deref(), Some("hunter22"));
+        assert!(settings.get("802-1x", "ca-cert").is_some());
+        assert!(settings.get("802-1x", "client-cert").is_none());
+    }
+
+    #[test]
+    fn test_validate_security() {
+        assert!(validate(&wifi(None)).is_ok());
+        assert!(validate(&wifi(Some(WifiSecurity::Wpa2Psk { psk: Secret::Plain("short".to_string()) }))).is_err());
+        // Encrypted passphrases are only checked once decrypted.
+        let sealed = wifi(Some(WifiSecurity::Wpa2Psk { psk: Secret::Encrypted("c2VhbGVk".to_string()) }));
+        assert!(validate(&sealed).is_ok());
+        assert!(check_decrypted(&sealed).is_err());
+
+        let tls = EnterpriseAuth { eap: EapMethod::Tls, client_cert: Some(PathBuf::from("client.pem")), ..peap() };
+        assert!(validate(&wifi(Some(WifiSecurity::Enterprise(tls)))).is_err());
+        let no_password = EnterpriseAuth { password: None, ..peap() };
+        assert!(validate(&wifi(Some(WifiSecurity::Enterprise(no_password)))).is_err());
+    }
+}
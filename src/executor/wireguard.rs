@@ -0,0 +1,291 @@
+// Network Manager - WireGuard Tunnels
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! WireGuard tunnels, created as NetworkManager `wireguard` connections.
+//!
+//! The private key reaches this module decrypted; it is only ever written
+//! to NetworkManager, which keeps it in its root-only connection store.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use super::ActionOutcome;
+use crate::models::actions::Action;
+use crate::models::plan::PlannedChange;
+use crate::models::validation::{
+    validate_cidr, validate_endpoint, validate_interface_name, validate_wireguard_key, Network,
+};
+use crate::models::{Error, NetworkAction, Result};
+use crate::nm::{ConnectionSettings, NmClient, PeerData, SavedConnection};
+
+/// Settings of a tunnel connection an action changes (secrets excluded).
+const TUNNEL_KEYS: &[(&str, &str)] = &[
+    ("wireguard", "listen-port"),
+    ("wireguard", "peers"),
+    ("ipv4", "address-data"),
+    ("ipv6", "address-data"),
+];
+
+/// Build an action failure for a WireGuard action.
+fn failed(action: &NetworkAction, reason: impl Into<String>) -> Error {
+    Error::action_failed(action.name(), reason)
+}
+
+/// Validate a WireGuard action, returning its tunnel name and addresses.
+fn validate(action: &NetworkAction) -> Result<(&str, Vec<Network>)> {
+    let NetworkAction::WireGuard { name, addresses, peers, .. } = action else {
+        return Err(failed(action, "Not a WireGuard action"));
+    };
+    validate_interface_name(name)?;
+    if addresses.is_empty() {
+        return Err(failed(action, "A WireGuard tunnel needs at least one address"));
+    }
+    let addresses = addresses.iter().map(|a| validate_cidr(a)).collect::<Result<Vec<_>>>()?;
+    if peers.is_empty() {
+        return Err(failed(action, "A WireGuard tunnel needs at least one peer"));
+    }
+    for peer in peers {
+        validate_wireguard_key(&peer.public_key)?;
+        if let Some(endpoint) = &peer.endpoint {
+            validate_endpoint(endpoint)?;
+        }
+        for allowed in &peer.allowed_ips {
+            validate_cidr(allowed)?;
+        }
+    }
+    Ok((name, addresses))
+}
+
+/// The decrypted private key of a WireGuard action.
+fn private_key(action: &NetworkAction) -> Result<String> {
+    let NetworkAction::WireGuard { private_key, .. } = action else {
+        return Err(failed(action, "Not a WireGuard action"));
+    };
+    let key = private_key
+        .plaintext()
+        .ok_or_else(|| failed(action, "The private key is encrypted and could not be decrypted"))?;
+    validate_wireguard_key(key)
+}
+
+/// Apply an action's tunnel settings, except the private key.
+fn edit_tunnel(action: &NetworkAction, addresses: &[Network], settings: &mut ConnectionSettings) {
+    let NetworkAction::WireGuard { listen_port, peers, .. } = action else { return };
+
+    match listen_port {
+        Some(port) => settings.set("wireguard", "listen-port", u32::from(*port)),
+        None => settings.remove("wireguard", "listen-port"),
+    }
+    let peers: Vec<PeerData> = peers
+        .iter()
+        .map(|peer| PeerData {
+            public_key: peer.public_key.trim().to_string(),
+            endpoint: peer.endpoint.clone(),
+            allowed_ips: peer.allowed_ips.clone(),
+            persistent_keepalive: peer.persistent_keepalive.map(u32::from),
+        })
+        .collect();
+    settings.set_wireguard_peers(&peers);
+
+    let v4: Vec<_> = addresses
+        .iter()
+        .filter_map(|(ip, prefix)| match ip {
+            IpAddr::V4(a) => Some((*a, *prefix)),
+            IpAddr::V6(_) => None,
+        })
+        .collect();
+    let v6: Vec<_> = addresses
+        .iter()
+        .filter_map(|(ip, prefix)| match ip {
+            IpAddr::V6(a) => Some((*a, *prefix)),
+            IpAddr::V4(_) => None,
+        })
+        .collect();
+    settings.set("ipv4", "method", if v4.is_empty() { "disabled" } else { "manual" });
+    settings.set_ipv4_addresses(&v4);
+    settings.set("ipv6", "method", if v6.is_empty() { "disabled" } else { "manual" });
+    settings.set_ipv6_addresses(&v6);
+}
+
+/// Settings for a new tunnel connection.
+fn new_tunnel(name: &str) -> ConnectionSettings {
+    let mut settings = ConnectionSettings::new();
+    settings.set("connection", "id", name);
+    settings.set("connection", "uuid", uuid::Uuid::new_v4().to_string());
+    settings.set("connection", "type", "wireguard");
+    settings.set("connection", "interface-name", name);
+    settings.mark_created();
+    settings
+}
+
+/// Create or update a WireGuard tunnel and bring it up.
+pub(super) fn apply(nm: &NmClient, action: &NetworkAction) -> Result<ActionOutcome> {
+    let (name, addresses) = validate(action)?;
+    let key = private_key(action)?;
+
+    match nm.virtual_connection("wireguard", name)? {
+        Some(conn) if !conn.settings.is_created() => Err(failed(
+            action,
+            format!("{} is already configured outside of profiles", name),
+        )),
+        Some(mut conn) => {
+            edit_tunnel(action, &addresses, &mut conn.settings);
+            conn.settings.set("wireguard", "private-key", key);
+            nm.update_connection(&conn)?;
+            nm.activate(&conn, None)?;
+            Ok(ActionOutcome::Applied)
+        }
+        None => {
+            let mut settings = new_tunnel(name);
+            edit_tunnel(action, &addresses, &mut settings);
+            settings.set("wireguard", "private-key", key);
+            let conn = nm.load_connection(nm.add_connection(&settings)?)?;
+            if let Err(e) = nm.activate(&conn, None) {
+                // Do not leave a tunnel behind that never came up.
+                if let Err(delete_err) = nm.delete_connection(&conn) {
+                    tracing::warn!("Failed to remove WireGuard connection {}: {}", name, delete_err);
+                }
+                return Err(e);
+            }
+            Ok(ActionOutcome::Applied)
+        }
+    }
+}
+
+/// A WireGuard tunnel as it was before an action created or changed it.
+#[derive(Debug, Clone)]
+pub struct WireGuardSnapshot {
+    /// Tunnel interface name.
+    name: String,
+    /// Tunnel connection with its secrets, or `None` if it did not exist.
+    previous: Option<SavedConnection>,
+    /// Whether the tunnel was active.
+    active: bool,
+}
+
+impl fmt::Display for WireGuardSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.previous {
+            None => write!(f, "{} absent", self.name),
+            Some(conn) => {
+                let state = if self.active { "active" } else { "inactive" };
+                write!(f, "{} {}\n{}", self.name, state, conn.settings.describe(TUNNEL_KEYS))
+            }
+        }
+    }
+}
+
+/// Capture the tunnel an action is about to change.
+pub(super) fn capture(nm: &NmClient, action: &NetworkAction) -> Result<WireGuardSnapshot> {
+    let (name, _) = validate(action)?;
+    let mut previous = nm.virtual_connection("wireguard", name)?;
+    let active = match &mut previous {
+        Some(conn) => {
+            nm.load_secrets(conn, "wireguard")?;
+            nm.active_connection_of(conn)?.is_some()
+        }
+        None => false,
+    };
+    Ok(WireGuardSnapshot { name: name.to_string(), previous, active })
+}
+
+/// Restore a tunnel to its captured state.
+pub(super) fn restore(nm: &NmClient, snapshot: &WireGuardSnapshot) -> Result<ActionOutcome> {
+    let Some(current) = nm.virtual_connection("wireguard", &snapshot.name)? else {
+        return Ok(ActionOutcome::Skipped(format!("{} no longer exists", snapshot.name)));
+    };
+    let Some(before) = &snapshot.previous else {
+        nm.delete_connection(&current)?;
+        return Ok(ActionOutcome::Applied);
+    };
+
+    nm.update_connection(before)?;
+    match (snapshot.active, nm.active_connection_of(before)?) {
+        (true, _) => {
+            nm.activate(before, None)?;
+        }
+        (false, Some(active)) => nm.deactivate(&active)?,
+        (false, None) => {}
+    }
+    Ok(ActionOutcome::Applied)
+}
+
+/// Describe what a WireGuard action would change.
+pub(super) fn plan(nm: &NmClient, action: &NetworkAction) -> Result<PlannedChange> {
+    let wrapped = Action::Network(action.clone());
+    let (name, addresses) = validate(action)?;
+    let current = nm.virtual_connection("wireguard", name)?;
+
+    let mut desired = current.as_ref().map(|c| c.settings.clone()).unwrap_or_default();
+    edit_tunnel(action, &addresses, &mut desired);
+    let desired = format!("{} active\n{}", name, desired.describe(TUNNEL_KEYS));
+
+    let Some(current) = current else {
+        return Ok(PlannedChange::compare_with_secrets(wrapped, Some(format!("{} absent", name)), desired));
+    };
+    let state = if nm.active_connection_of(&current)?.is_some() { "active" } else { "inactive" };
+    let current = format!("{} {}\n{}", name, state, current.settings.describe(TUNNEL_KEYS));
+    Ok(PlannedChange::compare_with_secrets(wrapped, Some(current), desired))
+}
+
+/// Remove a tunnel created by this application.
+pub(super) fn release(nm: &NmClient, action: &NetworkAction) -> Result<ActionOutcome> {
+    let (name, _) = validate(action)?;
+    match nm.virtual_connection("wireguard", name)? {
+        Some(conn) if conn.settings.is_created() => {
+            nm.delete_connection(&conn)?;
+            Ok(ActionOutcome::Applied)
+        }
+        Some(_) => Ok(ActionOutcome::Skipped(format!("{} was not created by a profile", name))),
+        None => Ok(ActionOutcome::Skipped(format!("{} no longer exists", name))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Secret, WireGuardPeer};
+
+    fn tunnel(addresses: &[&str], private_key: Secret) -> NetworkAction {
+        NetworkAction::WireGuard {
+            name: "wg0".to_string(),
+            addresses: addresses.iter().map(|a| a.to_string()).collect(),
+            private_key,
+            listen_port: Some(51820),
+            peers: vec![WireGuardPeer {
+                public_key: "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=".to_string(),
+                endpoint: Some("vpn.example.com:51820".to_string()),
+                allowed_ips: vec!["10.0.0.0/24".to_string()],
+                persistent_keepalive: Some(25),
+            }],
+        }
+    }
+
+    fn key() -> Secret {
+        Secret::Plain("yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=".to_string())
+    }
+
+    #[test]
+    fn test_tunnel_settings() {
+        let action = tunnel(&["10.0.0.2/24"], key());
+        let (name, addresses) = validate(&action).unwrap();
+        let mut settings = new_tunnel(name);
+        edit_tunnel(&action, &addresses, &mut settings);
+
+        assert_eq!(settings.connection_type().as_deref(), Some("wireguard"));
+        assert_eq!(settings.get_u32("wireguard", "listen-port"), Some(51820));
+        assert_eq!(settings.get_str("ipv4", "method").as_deref(), Some("manual"));
+        assert_eq!(settings.get_str("ipv6", "method").as_deref(), Some("disabled"));
+        assert!(settings.describe(TUNNEL_KEYS).contains("vpn.example.com:51820"));
+        assert!(settings.get("wireguard", "private-key").is_none());
+        assert!(settings.is_created());
+    }
+
+    #[test]
+    fn test_private_key_must_be_decrypted() {
+        assert!(private_key(&tunnel(&["10.0.0.2/24"], key())).is_ok());
+        assert!(private_key(&tunnel(&["10.0.0.2/24"], Secret::Encrypted("c2VhbGVk".to_string()))).is_err());
+        assert!(validate(&tunnel(&[], key())).is_err());
+        assert!(validate(&tunnel(&["10.0.0.2"], key())).is_err());
+    }
+}
@@ -0,0 +1,23 @@
+// Network Manager - Shared Library
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! # Network Manager
+//!
+//! GTK-independent code shared by the GUI application (`network-manager`)
+//! and the privileged system daemon (`cd-network-managerd`).
+//!
+//! Nothing in this crate may depend on GTK or libadwaita: the daemon runs
+//! as a system service without a display.
+
+pub mod dbus_client;
+pub mod executor;
+pub mod models;
+pub mod nm;
+pub mod network_utils;
+pub mod scheduler;
+pub mod services;
+pub mod storage;
+
+#[cfg(test)]
+mod test_support;
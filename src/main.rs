@@ -16,16 +16,15 @@ use std::env;
 
 mod application;
 mod autostart;
-mod dbus_client;
-mod models;
-mod network_utils;
-mod scheduler;
-mod services;
-mod storage;
 mod tray;
 mod ui;
 mod version_check;
 
+// Shared, GTK-independent modules live in the library crate so the daemon
+// can use them too; re-export them so `crate::models` etc. keep resolving.
+#[allow(unused_imports)]
+use network_manager::{dbus_client, executor, models, network_utils, nm, scheduler, services, storage};
+
 use application::Application;
 
 /// Application ID for GNOME/Freedesktop.
@@ -6,13 +6,17 @@
 //!
 //! These actions run external scripts or programs as part of profile activation.
 //! **Security note**: Script execution requires explicit user consent and logging.
+//! Consent is recorded as the SHA-256 digest of the approved file, which is
+//! checked again before every run (see `services::integrity`).
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use super::ActionScope;
+
 /// Script execution mode.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ScriptMode {
     /// Wait for script to complete.
@@ -27,8 +31,42 @@ pub enum ScriptMode {
     },
 }
 
+/// What a script may access in the Landlock sandbox mode.
+///
+/// System directories (`/usr`, `/etc`, ...), the script itself and its
+/// working directory are always accessible; everything else must be listed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptSandbox {
+    /// Additional paths the script may read and execute.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read_only_paths: Vec<PathBuf>,
+    /// Paths the script may write to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub writable_paths: Vec<PathBuf>,
+    /// Whether the script may open network sockets.
+    #[serde(default = "default_allow_network")]
+    pub allow_network: bool,
+}
+
+impl Default for ScriptSandbox {
+    fn default() -> Self {
+        Self { read_only_paths: Vec::new(), writable_paths: Vec::new(), allow_network: default_allow_network() }
+    }
+}
+
+impl ScriptSandbox {
+    /// Whether nothing differs from the defaults.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_allow_network() -> bool {
+    true
+}
+
 /// Program execution mode.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ProgramMode {
     /// Run in foreground, wait for exit.
@@ -41,7 +79,7 @@ pub enum ProgramMode {
 }
 
 /// Automation actions.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "action", rename_all = "snake_case")]
 pub enum AutomationAction {
     /// Run a script before profile actions.
@@ -63,6 +101,12 @@ pub enum AutomationAction {
         /// Continue on script failure.
         #[serde(default)]
         continue_on_error: bool,
+        /// Access granted in the Landlock sandbox mode.
+        #[serde(default, skip_serializing_if = "ScriptSandbox::is_default")]
+        sandbox: ScriptSandbox,
+        /// SHA-256 digest of the script the user approved (unset until then).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        approved_sha256: Option<String>,
     },
 
     /// Run a script after profile actions.
@@ -84,6 +128,12 @@ pub enum AutomationAction {
         /// Continue on script failure.
         #[serde(default)]
         continue_on_error: bool,
+        /// Access granted in the Landlock sandbox mode.
+        #[serde(default, skip_serializing_if = "ScriptSandbox::is_default")]
+        sandbox: ScriptSandbox,
+        /// SHA-256 digest of the script the user approved (unset until then).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        approved_sha256: Option<String>,
     },
 
     /// Run an external program.
@@ -102,6 +152,12 @@ pub enum AutomationAction {
         /// Working directory.
         #[serde(skip_serializing_if = "Option::is_none")]
         working_dir: Option<PathBuf>,
+        /// Only run the program while it matches an approved digest.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        pin_digest: bool,
+        /// SHA-256 digest of the program the user approved.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        approved_sha256: Option<String>,
     },
 
     /// Kill a running program (by name or PID file).
@@ -134,7 +190,7 @@ pub enum AutomationAction {
 }
 
 /// Kill signal type.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum KillSignal {
     #[default]
@@ -145,7 +201,7 @@ pub enum KillSignal {
 }
 
 /// Wait condition for automation.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum WaitCondition {
     /// Wait for network connectivity.
@@ -158,6 +214,18 @@ pub enum WaitCondition {
     Duration { seconds: u32 },
 }
 
+impl WaitCondition {
+    /// Get a human-readable description.
+    pub fn description(&self) -> String {
+        match self {
+            Self::NetworkUp => "network connectivity".to_string(),
+            Self::HostReachable { host } => format!("{} to be reachable", host),
+            Self::FileExists { path } => format!("{} to exist", path.display()),
+            Self::Duration { seconds } => format!("{}s", seconds),
+        }
+    }
+}
+
 impl AutomationAction {
     /// Get a short name for the action.
     pub fn name(&self) -> String {
@@ -187,7 +255,7 @@ impl AutomationAction {
                 format!("Kill: {}", program_name)
             }
             Self::WaitFor { condition, timeout_seconds } => {
-                format!("Wait for {:?} ({}s timeout)", condition, timeout_seconds)
+                format!("Wait for {} ({}s timeout)", condition.description(), timeout_seconds)
             }
             Self::Notification { title, .. } => {
                 format!("Notify: {}", title)
@@ -213,7 +281,7 @@ impl AutomationAction {
             Self::PreScript { .. } | Self::PostScript { .. } => true,
             // Kill may require privilege depending on target
             Self::KillProgram { .. } => true,
-            // Run program, wait, and notifications don't require privilege
+            // Programs run as the user, waits and notifications need nothing
             Self::RunProgram { .. } | Self::WaitFor { .. } | Self::Notification { .. } => false,
         }
     }
@@ -222,4 +290,60 @@ impl AutomationAction {
     pub fn is_script(&self) -> bool {
         matches!(self, Self::PreScript { .. } | Self::PostScript { .. })
     }
+
+    /// Where the action takes effect: programs, notifications and stopping
+    /// programs act on the user's session, everything else runs from the daemon.
+    pub fn scope(&self) -> ActionScope {
+        match self {
+            Self::RunProgram { .. } | Self::KillProgram { .. } | Self::Notification { .. } => ActionScope::Session,
+            _ => ActionScope::System,
+        }
+    }
+
+    /// Check if this action executes external code (a script or a program).
+    pub fn runs_code(&self) -> bool {
+        matches!(
+            self,
+            Self::PreScript { .. } | Self::PostScript { .. } | Self::RunProgram { .. }
+        )
+    }
+
+    /// The script or program whose digest must be approved before it runs.
+    ///
+    /// Scripts always need approval; programs only with `pin_digest`.
+    pub fn pinned_target(&self) -> Option<&str> {
+        match self {
+            Self::PreScript { path, .. } | Self::PostScript { path, .. } => path.to_str(),
+            Self::RunProgram { program, pin_digest: true, .. } => Some(program),
+            _ => None,
+        }
+    }
+
+    /// Get the approved digest of the script or program.
+    pub fn approved_sha256(&self) -> Option<&str> {
+        match self {
+            Self::PreScript { approved_sha256, .. }
+            | Self::PostScript { approved_sha256, .. }
+            | Self::RunProgram { approved_sha256, .. } => approved_sha256.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Set (or clear) the approved digest of the script or program.
+    pub fn set_approved_sha256(&mut self, digest: Option<String>) {
+        if let Self::PreScript { approved_sha256, .. }
+        | Self::PostScript { approved_sha256, .. }
+        | Self::RunProgram { approved_sha256, .. } = self
+        {
+            *approved_sha256 = digest;
+        }
+    }
+
+    /// Check if a failure of this action should not abort the profile.
+    pub fn continue_on_error(&self) -> bool {
+        match self {
+            Self::PreScript { continue_on_error, .. } | Self::PostScript { continue_on_error, .. } => *continue_on_error,
+            _ => false,
+        }
+    }
 }
@@ -18,12 +18,57 @@ mod automation;
 pub use network::*;
 pub use system::*;
 pub use system::{ProxyConfig, ProxyMode, HostsEntry};
-pub use automation::{AutomationAction, ScriptMode, ProgramMode};
+pub use automation::{AutomationAction, ScriptMode, ScriptSandbox, ProgramMode, KillSignal, WaitCondition};
+
+use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+/// Phase of profile activation an action runs in, in execution order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionPhase {
+    /// Pre-scripts, before anything is changed.
+    Pre,
+    /// Network actions.
+    Network,
+    /// System actions.
+    System,
+    /// Post-scripts, once the profile is in place.
+    Post,
+    /// Other automation (programs, notifications, ...).
+    Automation,
+}
+
+impl ExecutionPhase {
+    /// Name exported to scripts.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pre => "pre",
+            Self::Network => "network",
+            Self::System => "system",
+            Self::Post => "post",
+            Self::Automation => "automation",
+        }
+    }
+}
+
+/// Where an action takes effect when a profile is applied through the daemon.
+///
+/// The daemon runs as root without access to home directories or session
+/// buses, so anything belonging to the user's session is applied by the
+/// application itself once the daemon has applied the rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionScope {
+    /// System-wide settings, applied by the daemon.
+    System,
+    /// The user's files, session bus and processes, applied in the session.
+    Session,
+}
+
 /// Unified action enum covering all action types.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Action {
     /// Network configuration action.
@@ -53,6 +98,17 @@ impl Action {
         }
     }
 
+    /// Get the phase this action runs in.
+    pub fn phase(&self) -> ExecutionPhase {
+        match self {
+            Action::Network(_) => ExecutionPhase::Network,
+            Action::System(_) => ExecutionPhase::System,
+            Action::Automation(AutomationAction::PreScript { .. }) => ExecutionPhase::Pre,
+            Action::Automation(AutomationAction::PostScript { .. }) => ExecutionPhase::Post,
+            Action::Automation(_) => ExecutionPhase::Automation,
+        }
+    }
+
     /// Get the action category name.
     pub fn category(&self) -> &'static str {
         match self {
@@ -79,4 +135,142 @@ impl Action {
             Action::Automation(a) => a.requires_privilege(),
         }
     }
+
+    /// The part of this action that takes effect in `scope`, if any.
+    pub fn scoped(&self, scope: ActionScope) -> Option<Action> {
+        match self {
+            Action::Network(_) => (scope == ActionScope::System).then(|| self.clone()),
+            Action::System(a) => a.scoped(scope).map(Action::System),
+            Action::Automation(a) => (a.scope() == scope).then(|| self.clone()),
+        }
+    }
+
+    /// Interface the action creates, for actions that create virtual interfaces.
+    pub fn created_interface(&self) -> Option<String> {
+        match self {
+            Action::Network(a) => a.created_interface(),
+            _ => None,
+        }
+    }
+
+    /// The part of this action a following profile does not take over.
+    ///
+    /// `next` holds the actions of the profile replacing this one. Returns
+    /// `None` if `next` declares the same action or creates the same
+    /// interface; hosts entries and environment variables `next` declares
+    /// as well are left out.
+    pub fn unclaimed(&self, next: &[Action]) -> Option<Action> {
+        if next.contains(self) {
+            return None;
+        }
+        if let Some(interface) = self.created_interface() {
+            if next.iter().any(|a| a.created_interface().as_ref() == Some(&interface)) {
+                return None;
+            }
+        }
+        match self {
+            Action::System(SystemAction::HostsEntries { entries, replace_managed }) => {
+                let claimed: Vec<&HostsEntry> = next
+                    .iter()
+                    .flat_map(|a| match a {
+                        Action::System(SystemAction::HostsEntries { entries, .. }) => entries.as_slice(),
+                        _ => &[],
+                    })
+                    .collect();
+                let entries: Vec<HostsEntry> = entries.iter().filter(|e| !claimed.contains(e)).cloned().collect();
+                let replace_managed = *replace_managed;
+                (!entries.is_empty()).then_some(Action::System(SystemAction::HostsEntries { entries, replace_managed }))
+            }
+            Action::System(SystemAction::EnvironmentVariables { variables }) => {
+                let claimed = |key: &String| {
+                    next.iter().any(|a| match a {
+                        Action::System(SystemAction::EnvironmentVariables { variables }) => variables.contains_key(key),
+                        _ => false,
+                    })
+                };
+                let variables: HashMap<String, String> =
+                    variables.iter().filter(|(k, _)| !claimed(k)).map(|(k, v)| (k.clone(), v.clone())).collect();
+                (!variables.is_empty()).then_some(Action::System(SystemAction::EnvironmentVariables { variables }))
+            }
+            _ => Some(self.clone()),
+        }
+    }
+
+    /// Check if a failure of this action should not abort the profile.
+    ///
+    /// Only scripts can opt out; any other failure rolls the profile back.
+    pub fn continue_on_error(&self) -> bool {
+        match self {
+            Action::Automation(a) => a.continue_on_error(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::script_action;
+
+    fn hosts(names: &[&str]) -> Action {
+        let entries = names
+            .iter()
+            .map(|name| HostsEntry { ip: "10.0.0.5".parse().unwrap(), hostnames: vec![name.to_string()], comment: None })
+            .collect();
+        Action::System(SystemAction::HostsEntries { entries, replace_managed: false })
+    }
+
+    #[test]
+    fn test_scoped() {
+        let proxy = Action::System(SystemAction::ProxyConfig(ProxyConfig {
+            backends: vec![ProxyBackend::Gnome, ProxyBackend::Apt, ProxyBackend::Git],
+            ..Default::default()
+        }));
+        let backends = |action: Option<Action>| match action {
+            Some(Action::System(SystemAction::ProxyConfig(config))) => config.backends,
+            _ => Vec::new(),
+        };
+        assert_eq!(backends(proxy.scoped(ActionScope::System)), vec![ProxyBackend::Apt]);
+        assert_eq!(backends(proxy.scoped(ActionScope::Session)), vec![ProxyBackend::Gnome, ProxyBackend::Git]);
+
+        let hosts = hosts(&["nas"]);
+        assert_eq!(hosts.scoped(ActionScope::System), Some(hosts.clone()));
+        assert_eq!(hosts.scoped(ActionScope::Session), None);
+
+        let env = Action::System(SystemAction::EnvironmentVariables {
+            variables: [("EDITOR".to_string(), "vim".to_string())].into(),
+        });
+        assert_eq!(env.scoped(ActionScope::System), None);
+        assert_eq!(env.scoped(ActionScope::Session), Some(env.clone()));
+
+        let notify = Action::Automation(AutomationAction::Notification {
+            title: "Office".to_string(),
+            body: String::new(),
+            icon: None,
+        });
+        assert_eq!(notify.scoped(ActionScope::System), None);
+        assert_eq!(notify.scoped(ActionScope::Session), Some(notify.clone()));
+
+        let program = Action::Automation(script_action("/usr/bin/firefox", ExecutionPhase::Automation));
+        assert_eq!(program.scoped(ActionScope::System), None);
+        assert_eq!(program.scoped(ActionScope::Session), Some(program.clone()));
+    }
+
+    #[test]
+    fn test_unclaimed() {
+        let bridge = |port| {
+            Action::Network(NetworkAction::Bridge { name: "br0".to_string(), ports: vec![port], stp: true, forward_delay: None })
+        };
+        assert_eq!(bridge("eth0".to_string()).unclaimed(&[bridge("eth1".to_string())]), None);
+        assert_eq!(hosts(&["nas"]).unclaimed(&[hosts(&["nas"])]), None);
+        assert_eq!(hosts(&["nas", "printer"]).unclaimed(&[hosts(&["nas"])]), Some(hosts(&["printer"])));
+        assert_eq!(hosts(&["nas"]).unclaimed(&[]), Some(hosts(&["nas"])));
+
+        let env = |keys: &[&str]| {
+            let variables = keys.iter().map(|k| (k.to_string(), "1".to_string())).collect();
+            Action::System(SystemAction::EnvironmentVariables { variables })
+        };
+        assert_eq!(env(&["EDITOR", "PAGER"]).unclaimed(&[env(&["PAGER"])]), Some(env(&["EDITOR"])));
+        assert_eq!(env(&["PAGER"]).unclaimed(&[env(&["PAGER", "EDITOR"])]), None);
+    }
 }
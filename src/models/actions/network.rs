@@ -7,10 +7,12 @@
 //! These actions are applied via NetworkManager D-Bus API.
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::PathBuf;
 
 /// IPv4 configuration method.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Ipv4Method {
     /// Obtain address via DHCP.
@@ -25,7 +27,7 @@ pub enum Ipv4Method {
 }
 
 /// IPv6 configuration method.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Ipv6Method {
     /// Obtain address via SLAAC/DHCPv6.
@@ -42,7 +44,7 @@ pub enum Ipv6Method {
 }
 
 /// IPv4 address with prefix length.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ipv4Address {
     /// IP address.
     pub address: Ipv4Addr,
@@ -51,7 +53,7 @@ pub struct Ipv4Address {
 }
 
 /// IPv6 address with prefix length.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ipv6Address {
     /// IP address.
     pub address: Ipv6Addr,
@@ -60,7 +62,7 @@ pub struct Ipv6Address {
 }
 
 /// Static route definition.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StaticRoute {
     /// Destination network.
     pub destination: String,
@@ -71,10 +73,241 @@ pub struct StaticRoute {
     /// Metric (optional).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metric: Option<u32>,
+    /// Routing table (main table if unset).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<u32>,
+}
+
+/// Policy routing rule selecting a routing table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoutingRule {
+    /// Source network in CIDR notation (e.g., "10.20.0.0/16").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// Destination network in CIDR notation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// Firewall mark to match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fwmark: Option<u32>,
+    /// Mask applied to the firewall mark (all bits if unset).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fwmask: Option<u32>,
+    /// Routing table to look up for matching traffic.
+    pub table: u32,
+    /// Rule priority (lower values are evaluated first).
+    pub priority: u32,
+}
+
+/// Bonding mode (kernel/NetworkManager names).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum BondMode {
+    /// Round-robin transmission.
+    BalanceRr,
+    /// One active slave, the others on standby.
+    #[default]
+    ActiveBackup,
+    /// Transmit by hash of source and destination.
+    BalanceXor,
+    /// Transmit on all slaves.
+    Broadcast,
+    /// IEEE 802.3ad dynamic link aggregation (LACP).
+    #[serde(rename = "802.3ad")]
+    Lacp,
+    /// Adaptive transmit load balancing.
+    BalanceTlb,
+    /// Adaptive load balancing.
+    BalanceAlb,
+}
+
+impl BondMode {
+    /// Mode name as used in the `bond.options` setting.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BalanceRr => "balance-rr",
+            Self::ActiveBackup => "active-backup",
+            Self::BalanceXor => "balance-xor",
+            Self::Broadcast => "broadcast",
+            Self::Lacp => "802.3ad",
+            Self::BalanceTlb => "balance-tlb",
+            Self::BalanceAlb => "balance-alb",
+        }
+    }
+}
+
+/// A credential stored in a profile.
+///
+/// Secrets are encrypted whenever a profile is written to disk and only
+/// decrypted right before the profile is applied.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Secret {
+    /// Plaintext value.
+    Plain(String),
+    /// Base64 ciphertext produced by `ProfileEncryption`.
+    Encrypted(String),
+}
+
+impl Secret {
+    /// The plaintext value, if the secret is not encrypted.
+    pub fn plaintext(&self) -> Option<&str> {
+        match self {
+            Self::Plain(value) => Some(value),
+            Self::Encrypted(_) => None,
+        }
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Plain(_) => write!(f, "Secret::Plain([REDACTED])"),
+            Self::Encrypted(_) => write!(f, "Secret::Encrypted(..)"),
+        }
+    }
+}
+
+/// Wi-Fi security settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum WifiSecurity {
+    /// No encryption.
+    Open,
+    /// WPA2-Personal (pre-shared key).
+    Wpa2Psk {
+        /// Passphrase.
+        psk: Secret,
+    },
+    /// WPA3-Personal (SAE).
+    Wpa3Psk {
+        /// Passphrase.
+        psk: Secret,
+    },
+    /// WPA2/WPA3-Enterprise (802.1X).
+    Enterprise(EnterpriseAuth),
+}
+
+impl WifiSecurity {
+    /// Short label for display.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Open => "open".to_string(),
+            Self::Wpa2Psk { .. } => "WPA2".to_string(),
+            Self::Wpa3Psk { .. } => "WPA3".to_string(),
+            Self::Enterprise(auth) => format!("802.1X {}", auth.eap.as_str().to_uppercase()),
+        }
+    }
+}
+
+/// 802.1X EAP method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EapMethod {
+    /// Protected EAP (password inside a TLS tunnel).
+    Peap,
+    /// Tunneled TLS (password inside a TLS tunnel).
+    Ttls,
+    /// EAP-TLS (client certificate).
+    Tls,
+}
+
+impl EapMethod {
+    /// Method name as used in the `802-1x.eap` setting.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Peap => "peap",
+            Self::Ttls => "ttls",
+            Self::Tls => "tls",
+        }
+    }
+}
+
+/// 802.1X credentials.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnterpriseAuth {
+    /// EAP method.
+    pub eap: EapMethod,
+    /// User identity.
+    pub identity: String,
+    /// Outer identity sent before the tunnel is established (PEAP/TTLS).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anonymous_identity: Option<String>,
+    /// Password (PEAP/TTLS).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<Secret>,
+    /// Inner authentication method (PEAP/TTLS, "mschapv2" if unset).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase2_auth: Option<String>,
+    /// CA certificate used to verify the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<PathBuf>,
+    /// Client certificate (TLS).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<PathBuf>,
+    /// Client private key (TLS).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<PathBuf>,
+    /// Password of the client private key (TLS).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private_key_password: Option<Secret>,
+}
+
+/// Wi-Fi frequency band.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WifiBand {
+    /// 2.4 GHz (channels 1-14).
+    #[serde(rename = "2.4ghz")]
+    Ghz2_4,
+    /// 5 GHz (channels 36-177).
+    #[serde(rename = "5ghz")]
+    Ghz5,
+}
+
+impl WifiBand {
+    /// Band as used in the `802-11-wireless.band` setting.
+    pub fn as_nm(&self) -> &'static str {
+        match self {
+            Self::Ghz2_4 => "bg",
+            Self::Ghz5 => "a",
+        }
+    }
+
+    /// Whether a channel number belongs to the band.
+    pub fn has_channel(&self, channel: u16) -> bool {
+        match self {
+            Self::Ghz2_4 => (1..=14).contains(&channel),
+            Self::Ghz5 => (36..=177).contains(&channel),
+        }
+    }
+}
+
+impl fmt::Display for WifiBand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ghz2_4 => write!(f, "2.4 GHz"),
+            Self::Ghz5 => write!(f, "5 GHz"),
+        }
+    }
+}
+
+/// WireGuard peer definition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WireGuardPeer {
+    /// Peer public key (base64).
+    pub public_key: String,
+    /// Peer address as "host:port" (optional for peers that connect to us).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Networks routed to the peer, in CIDR notation.
+    pub allowed_ips: Vec<String>,
+    /// Keepalive interval in seconds (disabled if unset).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persistent_keepalive: Option<u16>,
 }
 
 /// Network interface enable/disable action.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InterfaceState {
     /// Interface name (e.g., "eth0", "wlan0").
     pub interface: String,
@@ -85,7 +318,7 @@ pub struct InterfaceState {
 /// Network configuration actions.
 ///
 /// All actions are applied via NetworkManager D-Bus API.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "action", rename_all = "snake_case")]
 pub enum NetworkAction {
     /// Configure IPv4 settings.
@@ -136,7 +369,7 @@ pub enum NetworkAction {
         domains: Vec<String>,
     },
 
-    /// Add static routes.
+    /// Set static routes (replacing the connection's existing routes).
     StaticRoutes {
         /// Target interface.
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -145,6 +378,15 @@ pub enum NetworkAction {
         routes: Vec<StaticRoute>,
     },
 
+    /// Set policy routing rules (replacing the connection's existing rules).
+    RoutingRules {
+        /// Target interface.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        interface: Option<String>,
+        /// Rules to add.
+        rules: Vec<RoutingRule>,
+    },
+
     /// Enable or disable a network interface.
     InterfaceEnable(InterfaceState),
 
@@ -164,13 +406,25 @@ pub enum NetworkAction {
         mac_address: String,
     },
 
-    /// Activate a Wi-Fi connection by SSID.
+    /// Connect to a Wi-Fi network.
+    ///
+    /// Without security settings a saved connection for the SSID is
+    /// activated. With them, the connection is created or updated first.
     WifiConnect {
         /// SSID to connect to.
         ssid: String,
         /// Specific interface (optional).
         #[serde(skip_serializing_if = "Option::is_none")]
         interface: Option<String>,
+        /// Security settings (use the saved connection if unset).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        security: Option<WifiSecurity>,
+        /// The network does not broadcast its SSID.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        hidden: bool,
+        /// Only connect to this access point (format: "AA:BB:CC:DD:EE:FF").
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bssid: Option<String>,
     },
 
     /// Activate a VPN connection.
@@ -195,6 +449,83 @@ pub enum NetworkAction {
         #[serde(skip_serializing_if = "Option::is_none")]
         name: Option<String>,
     },
+
+    /// Create a bond of several interfaces.
+    Bond {
+        /// Bond interface name (e.g., "bond0").
+        name: String,
+        /// Bonding mode.
+        #[serde(default)]
+        mode: BondMode,
+        /// Link monitoring interval in milliseconds (0 disables it).
+        #[serde(default = "default_miimon")]
+        miimon: u32,
+        /// Interfaces to enslave.
+        slaves: Vec<String>,
+    },
+
+    /// Create a bridge.
+    Bridge {
+        /// Bridge interface name (e.g., "br0").
+        name: String,
+        /// Interfaces to attach as bridge ports.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        ports: Vec<String>,
+        /// Enable the Spanning Tree Protocol.
+        #[serde(default = "default_stp")]
+        stp: bool,
+        /// STP forward delay in seconds (2-30, NetworkManager default if unset).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        forward_delay: Option<u32>,
+    },
+
+    /// Create a WireGuard tunnel.
+    #[serde(rename = "wireguard")]
+    WireGuard {
+        /// Tunnel interface name (e.g., "wg0").
+        name: String,
+        /// Tunnel addresses in CIDR notation.
+        addresses: Vec<String>,
+        /// Interface private key (base64).
+        private_key: Secret,
+        /// UDP listen port (chosen by the kernel if unset).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        listen_port: Option<u16>,
+        /// Tunnel peers.
+        peers: Vec<WireGuardPeer>,
+    },
+
+    /// Turn a Wi-Fi adapter into a WPA2 access point.
+    ///
+    /// Clients get addresses from NetworkManager's shared IPv4 method
+    /// (DHCP and NAT through the host's other connections).
+    Hotspot {
+        /// Wi-Fi interface to run the access point on.
+        interface: String,
+        /// Network name.
+        ssid: String,
+        /// Frequency band (chosen by NetworkManager if unset).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        band: Option<WifiBand>,
+        /// Channel within the band (requires a band).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        channel: Option<u16>,
+        /// WPA2 passphrase.
+        passphrase: Secret,
+        /// Host address and prefix of the shared network (10.42.0.1/24 if unset).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        address: Option<Ipv4Address>,
+    },
+}
+
+/// Default bond link monitoring interval (ms).
+fn default_miimon() -> u32 {
+    100
+}
+
+/// STP is enabled by default, as in NetworkManager.
+fn default_stp() -> bool {
+    true
 }
 
 impl NetworkAction {
@@ -206,6 +537,7 @@ impl NetworkAction {
             Self::DnsServers { .. } => "DNS Servers".to_string(),
             Self::DnsSearchDomains { .. } => "DNS Search Domains".to_string(),
             Self::StaticRoutes { .. } => "Static Routes".to_string(),
+            Self::RoutingRules { .. } => "Routing Rules".to_string(),
             Self::InterfaceEnable(state) => format!("{} Interface", if state.enabled { "Enable" } else { "Disable" }),
             Self::SetMtu { .. } => "Set MTU".to_string(),
             Self::SetMacAddress { .. } => "Set MAC Address".to_string(),
@@ -213,6 +545,10 @@ impl NetworkAction {
             Self::VpnConnect { connection_name } => format!("Connect VPN: {}", connection_name),
             Self::VpnDisconnect { connection_name } => format!("Disconnect VPN: {}", connection_name),
             Self::VlanConfig { vlan_id, .. } => format!("VLAN {}", vlan_id),
+            Self::Bond { name, .. } => format!("Bond: {}", name),
+            Self::Bridge { name, .. } => format!("Bridge: {}", name),
+            Self::WireGuard { name, .. } => format!("WireGuard: {}", name),
+            Self::Hotspot { ssid, .. } => format!("Hotspot: {}", ssid),
         }
     }
 
@@ -242,6 +578,9 @@ impl NetworkAction {
             Self::StaticRoutes { routes, .. } => {
                 format!("Static routes: {} entries", routes.len())
             }
+            Self::RoutingRules { rules, .. } => {
+                format!("Routing rules: {} entries", rules.len())
+            }
             Self::InterfaceEnable(state) => {
                 format!(
                     "{} interface {}",
@@ -255,6 +594,9 @@ impl NetworkAction {
             Self::SetMacAddress { interface, mac_address } => {
                 format!("MAC {} on {}", mac_address, interface)
             }
+            Self::WifiConnect { ssid, security: Some(security), .. } => {
+                format!("Connect to Wi-Fi: {} ({})", ssid, security.label())
+            }
             Self::WifiConnect { ssid, .. } => {
                 format!("Connect to Wi-Fi: {}", ssid)
             }
@@ -267,6 +609,24 @@ impl NetworkAction {
             Self::VlanConfig { parent_interface, vlan_id, .. } => {
                 format!("VLAN {} on {}", vlan_id, parent_interface)
             }
+            Self::Bond { name, mode, slaves, .. } => {
+                format!("Bond {} ({}) of {}", name, mode.as_str(), slaves.join(", "))
+            }
+            Self::Bridge { name, ports, .. } if ports.is_empty() => {
+                format!("Bridge {}", name)
+            }
+            Self::Bridge { name, ports, .. } => {
+                format!("Bridge {} with {}", name, ports.join(", "))
+            }
+            Self::WireGuard { name, peers, .. } => {
+                format!("WireGuard tunnel {} with {} peer(s)", name, peers.len())
+            }
+            Self::Hotspot { interface, ssid, band: Some(band), .. } => {
+                format!("Hotspot {} on {} ({})", ssid, interface, band)
+            }
+            Self::Hotspot { interface, ssid, .. } => {
+                format!("Hotspot {} on {}", ssid, interface)
+            }
         }
     }
 
@@ -275,13 +635,50 @@ impl NetworkAction {
         match self {
             Self::Ipv4Config { .. } | Self::Ipv6Config { .. } => "network-wired-symbolic",
             Self::DnsServers { .. } | Self::DnsSearchDomains { .. } => "network-server-symbolic",
-            Self::StaticRoutes { .. } => "route-symbolic",
+            Self::StaticRoutes { .. } | Self::RoutingRules { .. } => "route-symbolic",
             Self::InterfaceEnable(_) => "network-wired-symbolic",
             Self::SetMtu { .. } => "preferences-system-network-symbolic",
             Self::SetMacAddress { .. } => "network-wired-symbolic",
             Self::WifiConnect { .. } => "network-wireless-symbolic",
             Self::VpnConnect { .. } | Self::VpnDisconnect { .. } => "network-vpn-symbolic",
-            Self::VlanConfig { .. } => "network-wired-symbolic",
+            Self::VlanConfig { .. } | Self::Bond { .. } | Self::Bridge { .. } => "network-wired-symbolic",
+            Self::WireGuard { .. } => "network-vpn-symbolic",
+            Self::Hotspot { .. } => "network-wireless-hotspot-symbolic",
+        }
+    }
+
+    /// Interface the action creates, for actions that create virtual interfaces.
+    ///
+    /// A hotspot takes its adapter over, so it counts as creating it.
+    pub fn created_interface(&self) -> Option<String> {
+        match self {
+            Self::VlanConfig { parent_interface, vlan_id, name } => {
+                Some(name.clone().unwrap_or_else(|| format!("{}.{}", parent_interface, vlan_id)))
+            }
+            Self::Bond { name, .. } | Self::Bridge { name, .. } | Self::WireGuard { name, .. } => Some(name.clone()),
+            Self::Hotspot { interface, .. } => Some(interface.clone()),
+            _ => None,
+        }
+    }
+
+    /// Whether the action sets any non-empty secret.
+    pub fn has_secrets(&self) -> bool {
+        self.clone().secrets_mut().iter().any(|s| s.plaintext() != Some(""))
+    }
+
+    /// The secrets the action carries.
+    pub fn secrets_mut(&mut self) -> Vec<&mut Secret> {
+        match self {
+            Self::WireGuard { private_key, .. } => vec![private_key],
+            Self::Hotspot { passphrase, .. } => vec![passphrase],
+            Self::WifiConnect { security: Some(security), .. } => match security {
+                WifiSecurity::Open => Vec::new(),
+                WifiSecurity::Wpa2Psk { psk } | WifiSecurity::Wpa3Psk { psk } => vec![psk],
+                WifiSecurity::Enterprise(auth) => {
+                    auth.password.iter_mut().chain(auth.private_key_password.iter_mut()).collect()
+                }
+            },
+            _ => Vec::new(),
         }
     }
 }
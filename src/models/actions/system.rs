@@ -10,8 +10,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::IpAddr;
 
+use super::ActionScope;
+
 /// Hosts file entry.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HostsEntry {
     /// IP address.
     pub ip: IpAddr,
@@ -23,7 +25,7 @@ pub struct HostsEntry {
 }
 
 /// Proxy configuration type.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ProxyMode {
     /// No proxy.
@@ -36,7 +38,7 @@ pub enum ProxyMode {
 }
 
 /// Proxy configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProxyConfig {
     /// Proxy mode.
     pub mode: ProxyMode,
@@ -58,6 +60,9 @@ pub struct ProxyConfig {
     /// PAC URL (for auto mode).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pac_url: Option<String>,
+    /// Where the configuration is written.
+    #[serde(default = "default_proxy_backends")]
+    pub backends: Vec<ProxyBackend>,
 }
 
 impl Default for ProxyConfig {
@@ -70,22 +75,138 @@ impl Default for ProxyConfig {
             socks_proxy: None,
             no_proxy: Vec::new(),
             pac_url: None,
+            backends: default_proxy_backends(),
         }
     }
 }
 
+/// Backends of proxy configurations saved before backends were selectable.
+fn default_proxy_backends() -> Vec<ProxyBackend> {
+    vec![ProxyBackend::Gnome]
+}
+
+/// A place a proxy configuration is written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyBackend {
+    /// GNOME system proxy (`org.gnome.system.proxy` gsettings).
+    Gnome,
+    /// KDE proxy settings (`kioslaverc`).
+    Kde,
+    /// Proxy variables for the systemd user session (`environment.d`).
+    EnvironmentD,
+    /// APT (`/etc/apt/apt.conf.d`).
+    Apt,
+    /// DNF (`/etc/dnf/dnf.conf`).
+    Dnf,
+    /// Git (`http.proxy` in the global config).
+    Git,
+    /// npm (user config).
+    Npm,
+}
+
+impl ProxyBackend {
+    /// All backends, in the order they are applied.
+    pub const ALL: [Self; 7] = [Self::Gnome, Self::Kde, Self::EnvironmentD, Self::Apt, Self::Dnf, Self::Git, Self::Npm];
+
+    /// Where the backend's settings live: system files for APT and DNF,
+    /// the user's settings and home directory for the others.
+    pub fn scope(&self) -> ActionScope {
+        match self {
+            Self::Apt | Self::Dnf => ActionScope::System,
+            Self::Gnome | Self::Kde | Self::EnvironmentD | Self::Git | Self::Npm => ActionScope::Session,
+        }
+    }
+
+    /// Human-readable name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Gnome => "GNOME",
+            Self::Kde => "KDE",
+            Self::EnvironmentD => "Session Environment",
+            Self::Apt => "APT",
+            Self::Dnf => "DNF",
+            Self::Git => "Git",
+            Self::Npm => "npm",
+        }
+    }
+}
+
+/// Transport protocol of a firewall port rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortProtocol {
+    /// TCP.
+    Tcp,
+    /// UDP.
+    Udp,
+    /// SCTP.
+    Sctp,
+}
+
+impl PortProtocol {
+    /// Protocol name as used by firewalld and nftables.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tcp => "tcp",
+            Self::Udp => "udp",
+            Self::Sctp => "sctp",
+        }
+    }
+}
+
+/// A port or port range to open.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallPort {
+    /// First port.
+    pub port: u16,
+    /// Last port of a range.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_port: Option<u16>,
+    /// Protocol.
+    pub protocol: PortProtocol,
+}
+
+impl FirewallPort {
+    /// Port or range as firewalld writes it ("8080" or "60000-61000").
+    pub fn range(&self) -> String {
+        match self.end_port {
+            Some(end) => format!("{}-{}", self.port, end),
+            None => self.port.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for FirewallPort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.range(), self.protocol.as_str())
+    }
+}
+
 /// Firewall zone/profile (firewalld).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Ports, services and rich rules are opened in the default zone. Without
+/// firewalld, an equivalent nftables table is installed instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FirewallConfig {
     /// Default zone name.
     pub default_zone: String,
     /// Interface-to-zone mappings.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub interface_zones: HashMap<String, String>,
+    /// Ports to open.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub open_ports: Vec<FirewallPort>,
+    /// Services to allow (firewalld service names, e.g. "ssh").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub services: Vec<String>,
+    /// Rich rules in firewalld syntax (firewalld only).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rich_rules: Vec<String>,
 }
 
 /// System configuration actions.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "action", rename_all = "snake_case")]
 pub enum SystemAction {
     /// Set system hostname.
@@ -155,7 +276,8 @@ impl SystemAction {
                 format!("/etc/hosts: {} entries", entries.len())
             }
             Self::ProxyConfig(config) => {
-                format!("Proxy: {:?}", config.mode)
+                let backends: Vec<_> = config.backends.iter().map(ProxyBackend::display_name).collect();
+                format!("Proxy: {:?} ({})", config.mode, backends.join(", "))
             }
             Self::FirewallConfig(config) => {
                 format!("Firewall zone: {}", config.default_zone)
@@ -185,6 +307,22 @@ impl SystemAction {
         }
     }
 
+    /// The part of this action that takes effect in `scope`, if any.
+    ///
+    /// Environment variables belong to the session; a proxy configuration
+    /// is split by the scope of its backends.
+    pub fn scoped(&self, scope: ActionScope) -> Option<Self> {
+        match self {
+            Self::EnvironmentVariables { .. } => (scope == ActionScope::Session).then(|| self.clone()),
+            Self::ProxyConfig(config) => {
+                let backends: Vec<ProxyBackend> =
+                    config.backends.iter().copied().filter(|b| b.scope() == scope).collect();
+                (!backends.is_empty()).then(|| Self::ProxyConfig(ProxyConfig { backends, ..config.clone() }))
+            }
+            _ => (scope == ActionScope::System).then(|| self.clone()),
+        }
+    }
+
     /// Check if this action requires elevated privileges.
     pub fn requires_privilege(&self) -> bool {
         match self {
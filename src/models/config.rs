@@ -40,14 +40,28 @@ pub enum SandboxMode {
     Bubblewrap,
     /// Use firejail for sandboxing.
     Firejail,
+    /// Run scripts as hardened transient systemd services.
+    Systemd,
+    /// Confine scripts with Landlock and seccomp from the forked process.
+    Landlock,
 }
 
 impl SandboxMode {
+    /// Every mode, in the order offered in settings.
+    pub const ALL: [Self; 5] = [Self::None, Self::Bubblewrap, Self::Firejail, Self::Systemd, Self::Landlock];
+
+    /// Parse a mode from its [`as_str`](Self::as_str) name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.as_str() == name)
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::None => "none",
             Self::Bubblewrap => "bubblewrap",
             Self::Firejail => "firejail",
+            Self::Systemd => "systemd",
+            Self::Landlock => "landlock",
         }
     }
     
@@ -56,10 +70,56 @@ impl SandboxMode {
             Self::None => "No Sandboxing",
             Self::Bubblewrap => "Bubblewrap",
             Self::Firejail => "Firejail",
+            Self::Systemd => "systemd Transient Unit",
+            Self::Landlock => "Landlock and seccomp",
         }
     }
 }
 
+/// Limits for scripts run in [`SandboxMode::Systemd`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemdSandboxConfig {
+    /// Addresses or CIDR networks scripts may talk to (empty allows all).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ip_address_allow: Vec<String>,
+
+    /// Memory limit in MiB (0 for no limit).
+    #[serde(default = "default_sandbox_memory_max_mb")]
+    pub memory_max_mb: u64,
+
+    /// CPU time as a percentage of one CPU (0 for no limit).
+    #[serde(default = "default_sandbox_cpu_quota_percent")]
+    pub cpu_quota_percent: u32,
+
+    /// Longest a script may run, in seconds, unless its own timeout is
+    /// set (0 for no limit).
+    #[serde(default = "default_sandbox_runtime_max_secs")]
+    pub runtime_max_secs: u32,
+}
+
+impl Default for SystemdSandboxConfig {
+    fn default() -> Self {
+        Self {
+            ip_address_allow: Vec::new(),
+            memory_max_mb: default_sandbox_memory_max_mb(),
+            cpu_quota_percent: default_sandbox_cpu_quota_percent(),
+            runtime_max_secs: default_sandbox_runtime_max_secs(),
+        }
+    }
+}
+
+fn default_sandbox_memory_max_mb() -> u64 {
+    256
+}
+
+fn default_sandbox_cpu_quota_percent() -> u32 {
+    50
+}
+
+fn default_sandbox_runtime_max_secs() -> u32 {
+    300
+}
+
 /// Connection watchdog configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WatchdogConfig {
@@ -269,6 +329,10 @@ pub struct AppConfig {
     /// Script sandboxing mode.
     #[serde(default)]
     pub sandbox_mode: SandboxMode,
+
+    /// Limits for the systemd sandbox mode.
+    #[serde(default)]
+    pub systemd_sandbox: SystemdSandboxConfig,
     
     /// Encrypt sensitive profile data.
     #[serde(default)]
@@ -338,6 +402,7 @@ impl Default for AppConfig {
             watchdog: WatchdogConfig::default(),
             schedules: Vec::new(),
             sandbox_mode: SandboxMode::None,
+            systemd_sandbox: SystemdSandboxConfig::default(),
             encrypt_profiles: false,
             encryption_key: None,
             scheduling_enabled: false,
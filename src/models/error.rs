@@ -99,6 +99,9 @@ pub enum Error {
     #[error("Failed to parse configuration: {0}")]
     ConfigParseFailed(String),
 
+    #[error("Profile secrets unavailable: {0}")]
+    SecretsFailed(String),
+
     // ========================================
     // Script Errors
     // ========================================
@@ -111,6 +114,9 @@ pub enum Error {
     #[error("Script not executable: {0}")]
     ScriptNotExecutable(String),
 
+    #[error("Script not trusted: {script} - {reason}")]
+    ScriptNotTrusted { script: String, reason: String },
+
     // ========================================
     // System Errors
     // ========================================
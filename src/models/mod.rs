@@ -12,6 +12,7 @@
 //! - **Adapter**: Per-adapter network configuration
 //! - **Rules**: Auto-switch condition rules (rule engine)
 //! - **Execution**: Result types for profile application
+//! - **Plan**: Dry-run plans of what applying a profile would change
 //! - **Error**: Shared error types
 //!
 //! ## Design Principles
@@ -25,6 +26,7 @@ pub mod actions;
 pub mod adapter;
 pub mod config;
 pub mod error;
+pub mod plan;
 pub mod profile;
 pub mod result;
 pub mod rules;
@@ -33,16 +35,19 @@ pub mod templates;
 pub mod validation;
 
 // Re-export main types for convenience
-pub use actions::{NetworkAction, SystemAction, AutomationAction};
-pub use actions::{Ipv4Method, Ipv4Address, InterfaceState};
-pub use actions::{ProxyConfig, ProxyMode};
-pub use actions::{ScriptMode, ProgramMode};
+pub use actions::{ActionScope, NetworkAction, SystemAction, AutomationAction};
+pub use actions::{Ipv4Method, Ipv4Address, Ipv6Method, Ipv6Address, InterfaceState};
+pub use actions::{BondMode, RoutingRule, Secret, StaticRoute, WireGuardPeer};
+pub use actions::{EapMethod, EnterpriseAuth, WifiBand, WifiSecurity};
+pub use actions::{FirewallConfig, FirewallPort, PortProtocol, ProxyBackend, ProxyConfig, ProxyMode};
+pub use actions::{ScriptMode, ScriptSandbox, ProgramMode};
 pub use adapter::{AdapterType, AdapterInfo};
 // Adapter config types available via adapter:: when needed
 #[allow(unused_imports)]
-pub use config::{AppConfig, ThemePreference, SandboxMode, WatchdogConfig, WatchdogAction, ScheduleEntry, HotkeyEntry};
+pub use config::{AppConfig, ThemePreference, SandboxMode, SystemdSandboxConfig, WatchdogConfig, WatchdogAction, ScheduleEntry, HotkeyEntry};
 pub use error::{Error, Result};
-pub use profile::{Profile, ProfileGroup};
+pub use profile::{CheckpointSettings, Profile, ProfileGroup};
+pub use plan::ExecutionPlan;
 pub use result::ExecutionResult;
 #[allow(unused_imports)]
 pub use templates::ProfileTemplate;
@@ -0,0 +1,186 @@
+// Network Manager - Execution Plans
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Dry-run plans describing what applying a profile would change.
+
+use serde::{Deserialize, Serialize};
+
+use super::actions::Action;
+
+/// How applying an action would affect the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The action would change the system.
+    Change,
+    /// The system already matches the action.
+    NoOp,
+    /// The action cannot be applied (not implemented or not applicable).
+    Unsupported,
+}
+
+impl ChangeKind {
+    /// Short label for display.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Change => "Change",
+            Self::NoOp => "No change",
+            Self::Unsupported => "Unsupported",
+        }
+    }
+}
+
+/// Planned effect of a single action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedChange {
+    /// The action being planned.
+    pub action: Action,
+    /// Whether it would change anything.
+    pub kind: ChangeKind,
+    /// Current value read from the system (if it could be read).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    /// Value the profile would set.
+    pub desired: String,
+    /// Why the action is unsupported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl PlannedChange {
+    /// Create a planned change.
+    pub fn new(action: Action, kind: ChangeKind, current: Option<String>, desired: impl Into<String>) -> Self {
+        Self {
+            action,
+            kind,
+            current,
+            desired: desired.into(),
+            note: None,
+        }
+    }
+
+    /// Plan an action by comparing its current and desired values.
+    pub fn compare(action: Action, current: Option<String>, desired: impl Into<String>) -> Self {
+        let desired = desired.into();
+        let kind = if current.as_deref() == Some(desired.as_str()) {
+            ChangeKind::NoOp
+        } else {
+            ChangeKind::Change
+        };
+        Self::new(action, kind, current, desired)
+    }
+
+    /// Plan an action whose secrets the compared values leave out.
+    ///
+    /// Secrets are never read back from the system, so an action that sets
+    /// any is always a change, and the desired value says so.
+    pub fn compare_with_secrets(action: Action, current: Option<String>, desired: impl Into<String>) -> Self {
+        let mut desired = desired.into();
+        if matches!(&action, Action::Network(a) if a.has_secrets()) {
+            desired.push_str("\nsecrets replaced");
+        }
+        Self::compare(action, current, desired)
+    }
+
+    /// Plan an action that cannot be applied.
+    pub fn unsupported(action: Action, note: impl Into<String>) -> Self {
+        let desired = action.description();
+        Self {
+            action,
+            kind: ChangeKind::Unsupported,
+            current: None,
+            desired,
+            note: Some(note.into()),
+        }
+    }
+}
+
+/// Dry-run plan for a whole profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionPlan {
+    /// Profile ID being planned.
+    pub profile_id: String,
+    /// Profile name.
+    pub profile_name: String,
+    /// Planned effect of each action, in execution order.
+    pub changes: Vec<PlannedChange>,
+}
+
+impl ExecutionPlan {
+    /// Create an empty plan.
+    pub fn new(profile_id: impl Into<String>, profile_name: impl Into<String>) -> Self {
+        Self {
+            profile_id: profile_id.into(),
+            profile_name: profile_name.into(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// Count actions of a given kind.
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes.iter().filter(|c| c.kind == kind).count()
+    }
+
+    /// Check if applying the profile would change anything.
+    pub fn has_changes(&self) -> bool {
+        self.count(ChangeKind::Change) > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NetworkAction, Secret, SystemAction};
+
+    fn timezone(tz: &str) -> Action {
+        Action::System(SystemAction::SetTimezone { timezone: tz.to_string() })
+    }
+
+    #[test]
+    fn test_compare() {
+        let same = PlannedChange::compare(timezone("UTC"), Some("UTC".to_string()), "UTC");
+        assert_eq!(same.kind, ChangeKind::NoOp);
+
+        let different = PlannedChange::compare(timezone("UTC"), Some("Europe/Athens".to_string()), "UTC");
+        assert_eq!(different.kind, ChangeKind::Change);
+
+        let unknown = PlannedChange::compare(timezone("UTC"), None, "UTC");
+        assert_eq!(unknown.kind, ChangeKind::Change);
+    }
+
+    #[test]
+    fn test_compare_with_secrets() {
+        let tunnel = |key: &str| {
+            Action::Network(NetworkAction::WireGuard {
+                name: "wg0".to_string(),
+                addresses: vec!["10.0.0.2/24".to_string()],
+                private_key: Secret::Plain(key.to_string()),
+                listen_port: None,
+                peers: Vec::new(),
+            })
+        };
+        let current = Some("wg0 active".to_string());
+
+        let keyed = PlannedChange::compare_with_secrets(tunnel("key"), current.clone(), "wg0 active");
+        assert_eq!(keyed.kind, ChangeKind::Change);
+        assert_eq!(keyed.desired, "wg0 active\nsecrets replaced");
+
+        let keyless = PlannedChange::compare_with_secrets(tunnel(""), current, "wg0 active");
+        assert_eq!(keyless.kind, ChangeKind::NoOp);
+    }
+
+    #[test]
+    fn test_plan_counts() {
+        let mut plan = ExecutionPlan::new("id", "Office");
+        plan.changes.push(PlannedChange::compare(timezone("UTC"), Some("UTC".to_string()), "UTC"));
+        assert!(!plan.has_changes());
+
+        plan.changes.push(PlannedChange::unsupported(timezone("Mars/Olympus"), "Unknown timezone"));
+        plan.changes.push(PlannedChange::compare(timezone("UTC"), None, "UTC"));
+        assert!(plan.has_changes());
+        assert_eq!(plan.count(ChangeKind::NoOp), 1);
+        assert_eq!(plan.count(ChangeKind::Unsupported), 1);
+        assert_eq!(plan.changes[1].desired, "Timezone: Mars/Olympus");
+    }
+}
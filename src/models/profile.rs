@@ -16,7 +16,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::actions::{Action, AutomationAction, NetworkAction, SystemAction};
+use super::actions::{Action, ActionScope, AutomationAction, ExecutionPhase, NetworkAction, Secret, SystemAction};
 use super::rules::RuleSet;
 use super::schema::SchemaVersion;
 
@@ -114,6 +114,38 @@ impl ProfileMetadata {
     }
 }
 
+/// Confirmation required before a profile's network changes are kept.
+///
+/// Network actions run inside a NetworkManager checkpoint that restores the
+/// previous network state unless the changes are confirmed in time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointSettings {
+    /// Seconds to wait for confirmation before reverting.
+    #[serde(default = "CheckpointSettings::default_timeout")]
+    pub timeout_secs: u32,
+    /// Host whose reachability confirms the changes automatically.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probe_host: Option<String>,
+}
+
+impl CheckpointSettings {
+    /// Default confirmation timeout.
+    pub const DEFAULT_TIMEOUT_SECS: u32 = 30;
+
+    fn default_timeout() -> u32 {
+        Self::DEFAULT_TIMEOUT_SECS
+    }
+}
+
+impl Default for CheckpointSettings {
+    fn default() -> Self {
+        Self {
+            timeout_secs: Self::DEFAULT_TIMEOUT_SECS,
+            probe_host: None,
+        }
+    }
+}
+
 /// A complete network/system profile.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
@@ -140,6 +172,10 @@ pub struct Profile {
     #[serde(default)]
     pub approved_for_users: bool,
 
+    /// Require confirmation of network changes (disabled if `None`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<CheckpointSettings>,
+
     /// Current status (not persisted, set at runtime).
     #[serde(skip)]
     pub status: ProfileStatus,
@@ -155,6 +191,7 @@ impl Profile {
             automation_actions: Vec::new(),
             auto_switch_rules: None,
             approved_for_users: false,
+            checkpoint: None,
             status: ProfileStatus::Inactive,
         }
     }
@@ -169,19 +206,64 @@ impl Profile {
         &self.metadata.name
     }
 
-    /// Get all actions as a unified iterator.
+    /// Get all actions as a unified iterator, in execution order.
+    ///
+    /// Actions run phase by phase (see [`ExecutionPhase`]): pre-scripts,
+    /// network, system, post-scripts, then other automation. Within a phase
+    /// they keep their order in the profile.
     pub fn all_actions(&self) -> impl Iterator<Item = Action> + '_ {
-        self.network_actions
-            .iter()
-            .cloned()
-            .map(Action::Network)
+        let automation = |phase: ExecutionPhase| {
+            self.automation_actions
+                .iter()
+                .cloned()
+                .map(Action::Automation)
+                .filter(move |a| a.phase() == phase)
+        };
+        automation(ExecutionPhase::Pre)
+            .chain(self.network_actions.iter().cloned().map(Action::Network))
             .chain(self.system_actions.iter().cloned().map(Action::System))
-            .chain(
-                self.automation_actions
-                    .iter()
-                    .cloned()
-                    .map(Action::Automation),
-            )
+            .chain(automation(ExecutionPhase::Post))
+            .chain(automation(ExecutionPhase::Automation))
+    }
+
+    /// A copy of the profile with only the actions (or parts of them) that
+    /// take effect in `scope`.
+    pub fn scoped(&self, scope: ActionScope) -> Profile {
+        let mut profile = self.clone();
+        if scope != ActionScope::System {
+            profile.network_actions.clear();
+        }
+        profile.system_actions = self.system_actions.iter().filter_map(|a| a.scoped(scope)).collect();
+        profile.automation_actions.retain(|a| a.scope() == scope);
+        profile
+    }
+
+    /// Forget all script and program approvals, e.g. for an imported profile.
+    pub fn revoke_approvals(&mut self) {
+        for action in &mut self.automation_actions {
+            action.set_approved_sha256(None);
+        }
+    }
+
+    /// Keep approvals from `previous` actions that run the same file.
+    ///
+    /// Used when actions are rebuilt after editing; the digest is still
+    /// checked before every run.
+    pub fn carry_over_approvals(&mut self, previous: &[AutomationAction]) {
+        for action in &mut self.automation_actions {
+            let Some(target) = action.pinned_target() else { continue };
+            let digest = previous
+                .iter()
+                .find(|p| p.pinned_target() == Some(target))
+                .and_then(|p| p.approved_sha256())
+                .map(str::to_string);
+            action.set_approved_sha256(digest);
+        }
+    }
+
+    /// Secrets carried by the profile's actions.
+    pub fn secrets_mut(&mut self) -> impl Iterator<Item = &mut Secret> + '_ {
+        self.network_actions.iter_mut().flat_map(NetworkAction::secrets_mut)
     }
 
     /// Count total number of actions.
@@ -262,6 +344,22 @@ mod tests {
         assert_eq!(profile.description(), Some("Configuration for office network"));
     }
     
+    #[test]
+    fn test_checkpoint_settings_serialization() {
+        let mut profile = Profile::new("Remote Server");
+        assert!(!profile.to_toml().unwrap().contains("checkpoint"));
+
+        profile.checkpoint = Some(CheckpointSettings {
+            timeout_secs: 60,
+            probe_host: Some("10.0.0.1".to_string()),
+        });
+        let restored = Profile::from_toml(&profile.to_toml().unwrap()).unwrap();
+        assert_eq!(restored.checkpoint, profile.checkpoint);
+
+        let minimal: CheckpointSettings = toml::from_str("").unwrap();
+        assert_eq!(minimal, CheckpointSettings::default());
+    }
+
     #[test]
     fn test_profile_clone_preserves_data() {
         let profile = Profile::new("Original");
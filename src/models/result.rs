@@ -71,6 +71,9 @@ pub struct ActionResult {
     /// Prior state captured for rollback (if reversible).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prior_state: Option<String>,
+    /// Bounded output of a script (if it printed anything).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -85,6 +88,7 @@ impl ActionResult {
             duration_ms,
             started_at: Utc::now(),
             prior_state: None,
+            output: None,
         }
     }
 
@@ -98,6 +102,7 @@ impl ActionResult {
             duration_ms: 0,
             started_at: Utc::now(),
             prior_state: None,
+            output: None,
         }
     }
 
@@ -111,6 +116,7 @@ impl ActionResult {
             duration_ms,
             started_at: Utc::now(),
             prior_state: None,
+            output: None,
         }
     }
 
@@ -124,10 +130,23 @@ impl ActionResult {
             duration_ms: 0,
             started_at: Utc::now(),
             prior_state: None,
+            output: None,
         }
     }
 }
 
+/// A NetworkManager checkpoint awaiting confirmation.
+///
+/// NetworkManager reverts the network changes at `expires_at` unless the
+/// checkpoint is confirmed first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingCheckpoint {
+    /// Checkpoint object path.
+    pub path: String,
+    /// When the changes will be reverted.
+    pub expires_at: DateTime<Utc>,
+}
+
 /// Overall result of profile execution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionResult {
@@ -150,6 +169,12 @@ pub struct ExecutionResult {
     /// User who initiated the execution (if available).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub initiated_by: Option<String>,
+    /// Rollback steps, in the order they ran (empty if nothing was reverted).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rollback: Vec<ActionResult>,
+    /// Network checkpoint the caller must confirm to keep the changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<PendingCheckpoint>,
 }
 
 #[allow(dead_code)]
@@ -167,6 +192,8 @@ impl ExecutionResult {
             started_at: now,
             completed_at: now,
             initiated_by: None,
+            rollback: Vec::new(),
+            checkpoint: None,
         }
     }
 
@@ -175,6 +202,24 @@ impl ExecutionResult {
         self.actions.push(result);
     }
 
+    /// Add a rollback step result.
+    pub fn add_rollback(&mut self, result: ActionResult) {
+        self.rollback.push(result);
+    }
+
+    /// Append the results of applying another part of the same profile
+    /// (such as its session actions) and recalculate the overall status.
+    pub fn merge(&mut self, other: ExecutionResult) {
+        self.actions.extend(other.actions);
+        self.rollback.extend(other.rollback);
+        self.finalize();
+    }
+
+    /// Check if already-applied actions were reverted.
+    pub fn is_rolled_back(&self) -> bool {
+        !self.rollback.is_empty()
+    }
+
     /// Finalize the result, calculating overall status.
     pub fn finalize(&mut self) {
         self.completed_at = Utc::now();
@@ -188,6 +233,13 @@ impl ExecutionResult {
             self.status = StepStatus::Error;
             let error_count = self.actions.iter().filter(|a| a.status.is_error()).count();
             self.message = format!("{} action(s) failed", error_count);
+            if self.is_rolled_back() {
+                if self.rollback.iter().any(|r| r.status.is_error()) {
+                    self.message.push_str("; rollback incomplete");
+                } else {
+                    self.message.push_str("; changes rolled back");
+                }
+            }
         } else if has_warnings {
             self.status = StepStatus::Warning;
             self.message = "Completed with warnings".to_string();
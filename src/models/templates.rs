@@ -7,8 +7,10 @@
 //! Templates provide a quick way to create profiles with sensible defaults
 //! for typical use cases like home networks, office environments, or public WiFi.
 
-use super::actions::{NetworkAction, Ipv4Method, AutomationAction, SystemAction};
-use super::actions::{ProxyConfig, ProxyMode, HostsEntry};
+use rand::distributions::{Alphanumeric, DistString};
+
+use super::actions::{NetworkAction, Ipv4Method, AutomationAction, SystemAction, Secret};
+use super::actions::{ProxyBackend, ProxyConfig, ProxyMode, HostsEntry};
 use super::profile::Profile;
 
 /// Available profile templates.
@@ -25,6 +27,8 @@ pub enum ProfileTemplate {
     VpnOnly,
     /// Development environment
     Development,
+    /// Wi-Fi hotspot for tethering devices
+    Hotspot,
     /// Minimal/blank template
     Blank,
 }
@@ -39,6 +43,7 @@ impl ProfileTemplate {
             Self::PublicWifi,
             Self::VpnOnly,
             Self::Development,
+            Self::Hotspot,
             Self::Blank,
         ]
     }
@@ -51,6 +56,7 @@ impl ProfileTemplate {
             Self::PublicWifi => "Public WiFi (Secure)",
             Self::VpnOnly => "VPN Only",
             Self::Development => "Development",
+            Self::Hotspot => "Wi-Fi Hotspot",
             Self::Blank => "Blank Profile",
         }
     }
@@ -63,6 +69,7 @@ impl ProfileTemplate {
             Self::PublicWifi => "Secure configuration for untrusted public networks",
             Self::VpnOnly => "Route all traffic through VPN connection",
             Self::Development => "Local development with custom hosts and DNS",
+            Self::Hotspot => "Share this machine's connection with nearby devices over Wi-Fi",
             Self::Blank => "Start with an empty profile",
         }
     }
@@ -75,6 +82,7 @@ impl ProfileTemplate {
             Self::PublicWifi => "network-wireless-symbolic",
             Self::VpnOnly => "network-vpn-symbolic",
             Self::Development => "utilities-terminal-symbolic",
+            Self::Hotspot => "network-wireless-hotspot-symbolic",
             Self::Blank => "document-new-symbolic",
         }
     }
@@ -141,6 +149,7 @@ impl ProfileTemplate {
                     socks_proxy: None,
                     no_proxy: vec!["localhost".to_string(), "127.0.0.1".to_string(), ".company.com".to_string()],
                     pac_url: None,
+                    backends: vec![ProxyBackend::Gnome, ProxyBackend::EnvironmentD],
                 }));
             }
             
@@ -228,6 +237,27 @@ impl ProfileTemplate {
                 });
             }
             
+            Self::Hotspot => {
+                profile.metadata.icon = Some("network-wireless-hotspot-symbolic".to_string());
+                profile.metadata.group = Some(super::profile::ProfileGroup::new("Travel"));
+                
+                // Access point (placeholder interface - the first Wi-Fi adapter
+                // is chosen when creating the profile); the passphrase is
+                // random so no two hotspots share a default.
+                let mut ssid = format!("{} Hotspot", name);
+                while ssid.len() > 32 {
+                    ssid.pop();
+                }
+                profile.network_actions.push(NetworkAction::Hotspot {
+                    interface: "wlan0".to_string(),
+                    ssid,
+                    band: None,
+                    channel: None,
+                    passphrase: Secret::Plain(Alphanumeric.sample_string(&mut rand::thread_rng(), 16)),
+                    address: None,
+                });
+            }
+            
             Self::Blank => {
                 // Empty profile - no pre-configured actions
                 profile.metadata.icon = Some("document-new-symbolic".to_string());
@@ -259,6 +289,16 @@ mod tests {
         assert!(profile.network_actions.iter().any(|a| matches!(a, NetworkAction::Ipv4Config { method: Ipv4Method::Auto, .. })));
     }
     
+    #[test]
+    fn test_hotspot_template() {
+        let profile = ProfileTemplate::Hotspot.create_profile("Field");
+        let Some(NetworkAction::Hotspot { ssid, passphrase, .. }) = profile.network_actions.first() else {
+            panic!("hotspot template without a hotspot action");
+        };
+        assert_eq!(ssid, "Field Hotspot");
+        assert_eq!(passphrase.plaintext().map(str::len), Some(16));
+    }
+    
     #[test]
     fn test_blank_template() {
         let profile = ProfileTemplate::Blank.create_profile("Empty");
@@ -10,6 +10,7 @@
 use std::fs;
 use std::path::Path;
 use crate::models::{AdapterInfo, AdapterType};
+use crate::nm::NmClient;
 
 /// Detect all network adapters on the system.
 ///
@@ -85,6 +86,11 @@ pub fn detect_network_adapters() -> Vec<AdapterInfo> {
     adapters
 }
 
+/// Find a detected adapter by interface name.
+pub fn find_adapter(name: &str) -> Option<AdapterInfo> {
+    detect_network_adapters().into_iter().find(|a| a.name == name)
+}
+
 /// Determine the type of network adapter.
 fn determine_adapter_type(path: &Path, name: &str) -> AdapterType {
     // Check for wireless by looking for wireless directory
@@ -173,55 +179,32 @@ fn natural_sort_key(s: &str) -> (String, u32) {
 }
 
 /// Detect available WiFi networks (SSIDs).
-/// 
-/// Uses nmcli to scan for available networks.
+///
+/// Uses the access points NetworkManager has seen on all Wi-Fi devices.
 #[allow(dead_code)]
 pub fn detect_wifi_networks() -> Vec<String> {
-    let output = std::process::Command::new("nmcli")
-        .args(["-t", "-f", "SSID", "device", "wifi", "list"])
-        .output();
-
-    match output {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            stdout
-                .lines()
-                .filter(|line| !line.is_empty())
-                .map(|s| s.to_string())
-                .collect::<std::collections::HashSet<_>>()
-                .into_iter()
-                .collect()
-        }
-        _ => Vec::new(),
-    }
+    let mut ssids = NmClient::connect()
+        .and_then(|nm| nm.visible_ssids())
+        .unwrap_or_default();
+    ssids.sort();
+    ssids
 }
 
 /// Detect configured VPN connections.
 ///
-/// Uses nmcli to list VPN connections.
+/// Returns the names of saved VPN and WireGuard connections.
 #[allow(dead_code)]
 pub fn detect_vpn_connections() -> Vec<String> {
-    let output = std::process::Command::new("nmcli")
-        .args(["-t", "-f", "NAME,TYPE", "connection", "show"])
-        .output();
-
-    match output {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            stdout
-                .lines()
-                .filter_map(|line| {
-                    let parts: Vec<&str> = line.split(':').collect();
-                    if parts.len() >= 2 && parts[1].contains("vpn") {
-                        Some(parts[0].to_string())
-                    } else {
-                        None
-                    }
-                })
+    NmClient::connect()
+        .and_then(|nm| nm.connections())
+        .map(|connections| {
+            connections
+                .into_iter()
+                .filter(|c| matches!(c.settings.connection_type().as_deref(), Some("vpn" | "wireguard")))
+                .map(|c| c.id())
                 .collect()
-        }
-        _ => Vec::new(),
-    }
+        })
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -0,0 +1,598 @@
+// Network Manager - NetworkManager D-Bus Client
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Blocking client for the `org.freedesktop.NetworkManager` D-Bus API.
+//!
+//! Replaces parsing of `nmcli -t` output, which breaks on names containing
+//! `:` and depends on the nmcli binary and its locale. All calls are
+//! blocking; async callers should use `spawn_blocking`.
+
+mod proxies;
+mod settings;
+
+use std::time::{Duration, Instant};
+use zbus::blocking::Connection;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath};
+
+use crate::models::{Error, Result};
+
+pub use proxies::SettingsDict;
+pub use settings::{ConnectionSettings, PeerData, RouteData, RuleData};
+
+use proxies::{
+    AccessPointProxy, ActiveConnectionProxy, DeviceProxy, DnsManagerProxy,
+    NetworkManagerProxy, SettingsConnectionProxy, SettingsProxy,
+    WirelessDeviceProxy,
+};
+
+/// `NM_STATE_CONNECTED_LOCAL`: the lowest "connected" global state.
+const NM_STATE_CONNECTED_LOCAL: u32 = 50;
+
+/// `NM_STATE_CONNECTED_GLOBAL`: full Internet connectivity.
+const NM_STATE_CONNECTED_GLOBAL: u32 = 70;
+
+/// `NM_ACTIVE_CONNECTION_STATE_ACTIVATED`.
+const ACTIVE_STATE_ACTIVATED: u32 = 2;
+
+/// `NM_ACTIVE_CONNECTION_STATE_DEACTIVATED`.
+const ACTIVE_STATE_DEACTIVATED: u32 = 4;
+
+/// `NM_CHECKPOINT_CREATE_FLAG_DESTROY_ALL`: replace older checkpoints.
+const CHECKPOINT_DESTROY_ALL: u32 = 0x01;
+
+/// `NM_CHECKPOINT_CREATE_FLAG_DELETE_NEW_CONNECTIONS`.
+const CHECKPOINT_DELETE_NEW_CONNECTIONS: u32 = 0x02;
+
+/// `NM_CHECKPOINT_CREATE_FLAG_DISCONNECT_NEW_DEVICES`.
+const CHECKPOINT_DISCONNECT_NEW_DEVICES: u32 = 0x04;
+
+/// Object path prefix of NetworkManager checkpoints.
+pub const CHECKPOINT_PATH_PREFIX: &str = "/org/freedesktop/NetworkManager/Checkpoint/";
+
+/// D-Bus error NetworkManager returns for an interface it has no device for.
+const UNKNOWN_DEVICE_ERROR: &str = "org.freedesktop.NetworkManager.UnknownDevice";
+
+/// How long to wait for a connection to finish activating.
+const ACTIVATION_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// NetworkManager device types we distinguish (`NMDeviceType`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    Ethernet,
+    Wifi,
+    Bond,
+    Vlan,
+    Bridge,
+    WireGuard,
+    Loopback,
+    Other(u32),
+}
+
+impl From<u32> for DeviceType {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Ethernet,
+            2 => Self::Wifi,
+            10 => Self::Bond,
+            11 => Self::Vlan,
+            13 => Self::Bridge,
+            29 => Self::WireGuard,
+            32 => Self::Loopback,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// A network device known to NetworkManager.
+#[derive(Debug, Clone)]
+pub struct Device {
+    /// D-Bus object path.
+    pub path: OwnedObjectPath,
+    /// Interface name (e.g. "enp3s0").
+    pub interface: String,
+    /// Device type.
+    pub device_type: DeviceType,
+    /// Whether NetworkManager manages the device.
+    pub managed: bool,
+    /// Active connection on the device, if any.
+    pub active_connection: Option<OwnedObjectPath>,
+}
+
+/// A saved connection profile.
+#[derive(Debug, Clone)]
+pub struct SavedConnection {
+    /// D-Bus object path.
+    pub path: OwnedObjectPath,
+    /// Connection settings.
+    pub settings: ConnectionSettings,
+}
+
+impl SavedConnection {
+    /// Connection ID (name), or an empty string.
+    pub fn id(&self) -> String {
+        self.settings.id().unwrap_or_default()
+    }
+}
+
+/// A currently active connection.
+#[derive(Debug, Clone)]
+pub struct ActiveConnection {
+    /// D-Bus object path.
+    pub path: OwnedObjectPath,
+    /// Connection ID (name).
+    pub id: String,
+    /// Connection UUID.
+    pub uuid: String,
+    /// Connection type (e.g. "802-3-ethernet", "vpn", "wireguard").
+    pub connection_type: String,
+    /// Path of the saved connection.
+    pub connection: OwnedObjectPath,
+    /// Interface names of the devices it is active on.
+    pub interfaces: Vec<String>,
+    /// Whether this is a VPN connection.
+    pub vpn: bool,
+}
+
+/// Blocking NetworkManager client.
+pub struct NmClient {
+    conn: Connection,
+}
+
+/// Map a zbus error to a NetworkManager error, keeping NM's own message.
+fn nm_error(err: zbus::Error) -> Error {
+    match err {
+        zbus::Error::MethodError(name, Some(detail), _) => {
+            Error::NetworkManagerDbus(format!("{} ({})", detail, name.as_str()))
+        }
+        other => Error::NetworkManagerDbus(other.to_string()),
+    }
+}
+
+/// Validate a checkpoint object path.
+fn checkpoint_path(checkpoint: &str) -> Result<ObjectPath<'_>> {
+    if !checkpoint.starts_with(CHECKPOINT_PATH_PREFIX) {
+        return Err(Error::NetworkManagerDbus(format!("Not a checkpoint: {}", checkpoint)));
+    }
+    ObjectPath::try_from(checkpoint).map_err(|e| Error::NetworkManagerDbus(e.to_string()))
+}
+
+/// Treat NetworkManager's "/" placeholder path as `None`.
+fn non_root(path: OwnedObjectPath) -> Option<OwnedObjectPath> {
+    (path.as_str() != "/").then_some(path)
+}
+
+impl NmClient {
+    /// Connect to NetworkManager on the system bus.
+    pub fn connect() -> Result<Self> {
+        let conn = Connection::system().map_err(|e| Error::DbusConnectionFailed(e.to_string()))?;
+        Ok(Self { conn })
+    }
+
+    fn manager(&self) -> Result<NetworkManagerProxy<'_>> {
+        NetworkManagerProxy::builder(&self.conn)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .map_err(nm_error)
+    }
+
+    fn device_proxy(&self, path: &ObjectPath<'_>) -> Result<DeviceProxy<'_>> {
+        DeviceProxy::builder(&self.conn)
+            .path(path.to_owned())
+            .map_err(nm_error)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .map_err(nm_error)
+    }
+
+    fn active_proxy(&self, path: &ObjectPath<'_>) -> Result<ActiveConnectionProxy<'_>> {
+        ActiveConnectionProxy::builder(&self.conn)
+            .path(path.to_owned())
+            .map_err(nm_error)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .map_err(nm_error)
+    }
+
+    fn settings_proxy(&self, path: &ObjectPath<'_>) -> Result<SettingsConnectionProxy<'_>> {
+        SettingsConnectionProxy::builder(&self.conn)
+            .path(path.to_owned())
+            .map_err(nm_error)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .map_err(nm_error)
+    }
+
+    /// Whether NetworkManager reports any connectivity (local or better).
+    pub fn is_connected(&self) -> Result<bool> {
+        Ok(self.manager()?.state().map_err(nm_error)? >= NM_STATE_CONNECTED_LOCAL)
+    }
+
+    /// Whether NetworkManager reports full Internet connectivity.
+    pub fn is_online(&self) -> Result<bool> {
+        Ok(self.manager()?.state().map_err(nm_error)? >= NM_STATE_CONNECTED_GLOBAL)
+    }
+
+    /// Turn networking on or off, as `nmcli networking on|off` does.
+    pub fn set_networking(&self, enabled: bool) -> Result<()> {
+        self.manager()?.enable(enabled).map_err(nm_error)
+    }
+
+    /// DNS servers NetworkManager has configured, in priority order and
+    /// without duplicates.
+    pub fn dns_servers(&self) -> Result<Vec<String>> {
+        let dns = DnsManagerProxy::builder(&self.conn)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .map_err(nm_error)?;
+        let mut servers = Vec::new();
+        for entry in dns.configuration().map_err(nm_error)? {
+            let nameservers = entry
+                .get("nameservers")
+                .and_then(|v| v.try_clone().ok())
+                .and_then(|v| Vec::<String>::try_from(v).ok())
+                .unwrap_or_default();
+            for server in nameservers {
+                if !servers.contains(&server) {
+                    servers.push(server);
+                }
+            }
+        }
+        Ok(servers)
+    }
+
+    /// Load a device by object path.
+    fn load_device(&self, path: OwnedObjectPath) -> Result<Device> {
+        let proxy = self.device_proxy(&path)?;
+        Ok(Device {
+            interface: proxy.interface().map_err(nm_error)?,
+            device_type: proxy.device_type().map_err(nm_error)?.into(),
+            managed: proxy.managed().map_err(nm_error)?,
+            active_connection: non_root(proxy.active_connection().map_err(nm_error)?),
+            path,
+        })
+    }
+
+    /// List all devices.
+    pub fn devices(&self) -> Result<Vec<Device>> {
+        self.manager()?
+            .get_devices()
+            .map_err(nm_error)?
+            .into_iter()
+            .map(|path| self.load_device(path))
+            .collect()
+    }
+
+    /// Find a device by interface name.
+    pub fn device_by_interface(&self, interface: &str) -> Result<Device> {
+        let path = self.manager()?.get_device_by_ip_iface(interface).map_err(nm_error)?;
+        self.load_device(path)
+    }
+
+    /// Find a device by interface name, or `None` if there is no such device.
+    pub fn find_device(&self, interface: &str) -> Result<Option<Device>> {
+        match self.manager()?.get_device_by_ip_iface(interface) {
+            Ok(path) => self.load_device(path).map(Some),
+            Err(zbus::Error::MethodError(name, _, _)) if name.as_str() == UNKNOWN_DEVICE_ERROR => Ok(None),
+            Err(e) => Err(nm_error(e)),
+        }
+    }
+
+    /// The device carrying the primary (default-route) connection.
+    pub fn primary_device(&self) -> Result<Option<Device>> {
+        let Some(primary) = non_root(self.manager()?.primary_connection().map_err(nm_error)?) else {
+            return Ok(None);
+        };
+        let devices = self.active_proxy(&primary)?.devices().map_err(nm_error)?;
+        devices.into_iter().next().map(|path| self.load_device(path)).transpose()
+    }
+
+    /// List all saved connections.
+    pub fn connections(&self) -> Result<Vec<SavedConnection>> {
+        let settings = SettingsProxy::new(&self.conn).map_err(nm_error)?;
+        settings
+            .list_connections()
+            .map_err(nm_error)?
+            .into_iter()
+            .map(|path| self.load_connection(path))
+            .collect()
+    }
+
+    /// Load a saved connection by object path.
+    pub fn load_connection(&self, path: OwnedObjectPath) -> Result<SavedConnection> {
+        let settings = self.settings_proxy(&path)?.get_settings().map_err(nm_error)?;
+        Ok(SavedConnection { path, settings: settings.into() })
+    }
+
+    /// Load the secrets of one setting (e.g. `wireguard`) into a connection.
+    ///
+    /// `GetSettings` never returns secrets; they are needed to restore a
+    /// connection exactly as it was.
+    pub fn load_secrets(&self, connection: &mut SavedConnection, setting: &str) -> Result<()> {
+        let secrets = self.settings_proxy(&connection.path)?.get_secrets(setting).map_err(nm_error)?;
+        connection.settings.merge_secrets(secrets);
+        Ok(())
+    }
+
+    /// Find a saved connection by ID (name).
+    pub fn connection_by_id(&self, id: &str) -> Result<Option<SavedConnection>> {
+        Ok(self.connections()?.into_iter().find(|c| c.id() == id))
+    }
+
+    /// Find the saved connection of a virtual interface (`bond`, `bridge`, ...).
+    pub fn virtual_connection(&self, connection_type: &str, interface: &str) -> Result<Option<SavedConnection>> {
+        Ok(self.connections()?.into_iter().find(|c| {
+            c.settings.connection_type().as_deref() == Some(connection_type)
+                && c.settings.interface_name().as_deref() == Some(interface)
+        }))
+    }
+
+    /// Find the connection attaching an interface to a bond or bridge.
+    ///
+    /// `connection.master` may name the master interface or its UUID.
+    pub fn port_connection(&self, master: &SavedConnection, interface: &str) -> Result<Option<SavedConnection>> {
+        let names = [master.settings.interface_name(), master.settings.uuid()];
+        Ok(self.connections()?.into_iter().find(|c| {
+            let port_master = c.settings.get_str("connection", "master");
+            port_master.is_some() && names.contains(&port_master)
+                && c.settings.interface_name().as_deref() == Some(interface)
+        }))
+    }
+
+    /// Find the saved VLAN connection for a parent interface and VLAN ID.
+    pub fn vlan_connection(&self, parent: &str, vlan_id: u16) -> Result<Option<SavedConnection>> {
+        Ok(self.connections()?.into_iter().find(|c| {
+            c.settings.connection_type().as_deref() == Some("vlan")
+                && c.settings.get_str("vlan", "parent").as_deref() == Some(parent)
+                && c.settings.get_u32("vlan", "id") == Some(u32::from(vlan_id))
+        }))
+    }
+
+    /// Find the saved Wi-Fi connection for an SSID.
+    ///
+    /// Falls back to a connection named after the SSID.
+    pub fn wifi_connection(&self, ssid: &str) -> Result<Option<SavedConnection>> {
+        let connections = self.connections()?;
+        let by_ssid = connections
+            .iter()
+            .position(|c| c.settings.ssid().as_deref() == Some(ssid.as_bytes()));
+        let index = by_ssid.or_else(|| connections.iter().position(|c| c.id() == ssid));
+        Ok(index.map(|i| connections[i].clone()))
+    }
+
+    /// Find the connection to configure for an interface.
+    ///
+    /// Prefers the connection active on the device, then a saved connection
+    /// bound to the interface by name. Returns `None` for unmanaged devices.
+    pub fn connection_for_interface(&self, interface: &str) -> Result<Option<SavedConnection>> {
+        let device = match self.device_by_interface(interface) {
+            Ok(device) => device,
+            Err(_) => return Ok(None),
+        };
+        if !device.managed {
+            return Ok(None);
+        }
+        if let Some(active) = &device.active_connection {
+            let saved = self.active_proxy(active)?.connection().map_err(nm_error)?;
+            return self.load_connection(saved).map(Some);
+        }
+        Ok(self
+            .connections()?
+            .into_iter()
+            .find(|c| c.settings.interface_name().as_deref() == Some(interface)))
+    }
+
+    /// Replace a saved connection's settings (persisted to disk).
+    ///
+    /// Secrets not included in `settings` are kept by NetworkManager.
+    pub fn update_connection(&self, connection: &SavedConnection) -> Result<()> {
+        self.settings_proxy(&connection.path)?
+            .update(connection.settings.as_dict())
+            .map_err(nm_error)
+    }
+
+    /// Add and persist a new connection.
+    pub fn add_connection(&self, settings: &ConnectionSettings) -> Result<OwnedObjectPath> {
+        SettingsProxy::new(&self.conn)
+            .map_err(nm_error)?
+            .add_connection(settings.as_dict())
+            .map_err(nm_error)
+    }
+
+    /// Delete a saved connection.
+    pub fn delete_connection(&self, connection: &SavedConnection) -> Result<()> {
+        self.settings_proxy(&connection.path)?.delete().map_err(nm_error)
+    }
+
+    /// Activate a saved connection and wait until it is up.
+    ///
+    /// With no device, NetworkManager picks one (required for VPNs).
+    pub fn activate(&self, connection: &SavedConnection, device: Option<&Device>) -> Result<ActiveConnection> {
+        let root = ObjectPath::from_static_str_unchecked("/");
+        let device_path = device.map(|d| d.path.as_ref()).unwrap_or_else(|| root.clone());
+        let active = self
+            .manager()?
+            .activate_connection(&connection.path, &device_path, &root)
+            .map_err(nm_error)?;
+        self.wait_activated(&connection.id(), active)
+    }
+
+    /// Connect a device using the best available saved connection.
+    pub fn connect_device(&self, device: &Device) -> Result<ActiveConnection> {
+        let root = ObjectPath::from_static_str_unchecked("/");
+        let active = self
+            .manager()?
+            .activate_connection(&root, &device.path, &root)
+            .map_err(nm_error)?;
+        self.wait_activated(&device.interface, active)
+    }
+
+    /// Poll an active connection until it is activated or fails.
+    fn wait_activated(&self, name: &str, path: OwnedObjectPath) -> Result<ActiveConnection> {
+        let deadline = Instant::now() + ACTIVATION_TIMEOUT;
+        loop {
+            // The object disappears when activation fails.
+            let state = self
+                .active_proxy(&path)?
+                .state()
+                .unwrap_or(ACTIVE_STATE_DEACTIVATED);
+            match state {
+                ACTIVE_STATE_ACTIVATED => return self.load_active(path),
+                ACTIVE_STATE_DEACTIVATED => {
+                    return Err(Error::NetworkManagerDbus(format!("Activation of '{}' failed", name)))
+                }
+                _ if Instant::now() >= deadline => {
+                    return Err(Error::ActionTimeout(format!("Activation of '{}'", name)))
+                }
+                _ => std::thread::sleep(Duration::from_millis(250)),
+            }
+        }
+    }
+
+    /// Load an active connection by object path.
+    fn load_active(&self, path: OwnedObjectPath) -> Result<ActiveConnection> {
+        let proxy = self.active_proxy(&path)?;
+        let interfaces = proxy
+            .devices()
+            .map_err(nm_error)?
+            .into_iter()
+            .filter_map(|d| self.device_proxy(&d).ok()?.interface().ok())
+            .collect();
+        Ok(ActiveConnection {
+            id: proxy.id().map_err(nm_error)?,
+            uuid: proxy.uuid().map_err(nm_error)?,
+            connection_type: proxy.connection_type().map_err(nm_error)?,
+            connection: proxy.connection().map_err(nm_error)?,
+            vpn: proxy.vpn().map_err(nm_error)?,
+            interfaces,
+            path,
+        })
+    }
+
+    /// List active connections.
+    pub fn active_connections(&self) -> Result<Vec<ActiveConnection>> {
+        self.manager()?
+            .active_connections()
+            .map_err(nm_error)?
+            .into_iter()
+            .map(|path| self.load_active(path))
+            .collect()
+    }
+
+    /// Find the active connection of a saved connection, if it is active.
+    pub fn active_connection_of(&self, connection: &SavedConnection) -> Result<Option<ActiveConnection>> {
+        Ok(self
+            .active_connections()?
+            .into_iter()
+            .find(|a| a.connection == connection.path))
+    }
+
+    /// Deactivate an active connection.
+    pub fn deactivate(&self, active: &ActiveConnection) -> Result<()> {
+        self.manager()?.deactivate_connection(&active.path).map_err(nm_error)
+    }
+
+    /// Disconnect a device and prevent it from auto-activating.
+    pub fn disconnect_device(&self, device: &Device) -> Result<()> {
+        self.device_proxy(&device.path)?.disconnect().map_err(nm_error)
+    }
+
+    /// Reapply the saved settings of a device's active connection.
+    pub fn reapply(&self, device: &Device) -> Result<()> {
+        self.device_proxy(&device.path)?
+            .reapply(Default::default(), 0, 0)
+            .map_err(nm_error)
+    }
+
+    /// Snapshot all devices into a checkpoint.
+    ///
+    /// NetworkManager rolls back to the checkpoint by itself after
+    /// `timeout_secs` unless it is destroyed first. Connections and devices
+    /// created after the checkpoint are removed on rollback.
+    pub fn create_checkpoint(&self, timeout_secs: u32) -> Result<OwnedObjectPath> {
+        let flags = CHECKPOINT_DESTROY_ALL | CHECKPOINT_DELETE_NEW_CONNECTIONS | CHECKPOINT_DISCONNECT_NEW_DEVICES;
+        self.manager()?
+            .checkpoint_create(&[], timeout_secs, flags)
+            .map_err(nm_error)
+    }
+
+    /// Restart a checkpoint's rollback countdown at `timeout_secs` from now.
+    pub fn adjust_checkpoint_timeout(&self, checkpoint: &str, timeout_secs: u32) -> Result<()> {
+        self.manager()?
+            .checkpoint_adjust_rollback_timeout(&checkpoint_path(checkpoint)?, timeout_secs)
+            .map_err(nm_error)
+    }
+
+    /// Keep the current state and discard a checkpoint.
+    pub fn destroy_checkpoint(&self, checkpoint: &str) -> Result<()> {
+        self.manager()?
+            .checkpoint_destroy(&checkpoint_path(checkpoint)?)
+            .map_err(nm_error)
+    }
+
+    /// Roll back to a checkpoint now.
+    pub fn rollback_checkpoint(&self, checkpoint: &str) -> Result<()> {
+        let results = self
+            .manager()?
+            .checkpoint_rollback(&checkpoint_path(checkpoint)?)
+            .map_err(nm_error)?;
+        // NM_ROLLBACK_RESULT_OK is 0.
+        let failed: Vec<_> = results.iter().filter(|(_, r)| **r != 0).map(|(d, _)| d.as_str()).collect();
+        if !failed.is_empty() {
+            return Err(Error::NetworkManagerDbus(format!("Rollback failed for {}", failed.join(", "))));
+        }
+        Ok(())
+    }
+
+    /// SSID of the access point the first connected Wi-Fi device uses.
+    pub fn current_ssid(&self) -> Result<Option<String>> {
+        for device in self.devices()? {
+            if device.device_type != DeviceType::Wifi || device.active_connection.is_none() {
+                continue;
+            }
+            let wireless = WirelessDeviceProxy::builder(&self.conn)
+                .path(device.path.clone())
+                .map_err(nm_error)?
+                .cache_properties(CacheProperties::No)
+                .build()
+                .map_err(nm_error)?;
+            let Some(ap) = non_root(wireless.active_access_point().map_err(nm_error)?) else {
+                continue;
+            };
+            let ssid = self.access_point_ssid(ap)?;
+            if !ssid.is_empty() {
+                return Ok(Some(ssid));
+            }
+        }
+        Ok(None)
+    }
+
+    /// SSIDs visible to all Wi-Fi devices (deduplicated, unsorted).
+    pub fn visible_ssids(&self) -> Result<Vec<String>> {
+        let mut ssids = std::collections::HashSet::new();
+        for device in self.devices()?.into_iter().filter(|d| d.device_type == DeviceType::Wifi) {
+            let wireless = WirelessDeviceProxy::builder(&self.conn)
+                .path(device.path)
+                .map_err(nm_error)?
+                .build()
+                .map_err(nm_error)?;
+            for ap in wireless.get_all_access_points().map_err(nm_error)? {
+                let ssid = self.access_point_ssid(ap)?;
+                if !ssid.is_empty() {
+                    ssids.insert(ssid);
+                }
+            }
+        }
+        Ok(ssids.into_iter().collect())
+    }
+
+    fn access_point_ssid(&self, path: OwnedObjectPath) -> Result<String> {
+        let ap = AccessPointProxy::builder(&self.conn)
+            .path(path)
+            .map_err(nm_error)?
+            .build()
+            .map_err(nm_error)?;
+        Ok(String::from_utf8_lossy(&ap.ssid().map_err(nm_error)?).into_owned())
+    }
+}
@@ -0,0 +1,495 @@
+// Network Manager - NetworkManager Connection Settings
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Typed access to NetworkManager connection settings (`a{sa{sv}}`).
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use zbus::zvariant::{OwnedValue, Str, Value};
+
+use super::proxies::SettingsDict;
+
+/// `AF_INET`, the routing rule family for IPv4.
+const AF_INET: i32 = 2;
+
+/// `AF_INET6`, the routing rule family for IPv6.
+const AF_INET6: i32 = 10;
+
+/// `user.data` key marking connections created by this application.
+const CREATED_BY_KEY: &str = "com.chrisdaggas.network-manager.created-by";
+
+/// A static route (one `route-data` entry).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteData {
+    /// Destination network address.
+    pub dest: IpAddr,
+    /// Destination prefix length.
+    pub prefix: u8,
+    /// Gateway, if the route is not on-link.
+    pub next_hop: Option<IpAddr>,
+    /// Route metric.
+    pub metric: Option<u32>,
+    /// Routing table.
+    pub table: Option<u32>,
+}
+
+/// A policy routing rule (one `routing-rules` entry).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleData {
+    /// Rule priority.
+    pub priority: u32,
+    /// Source network.
+    pub from: Option<(IpAddr, u8)>,
+    /// Destination network.
+    pub to: Option<(IpAddr, u8)>,
+    /// Firewall mark and mask.
+    pub fwmark: Option<(u32, u32)>,
+    /// Routing table to look up.
+    pub table: u32,
+}
+
+/// A WireGuard peer (one `wireguard.peers` entry).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerData {
+    /// Peer public key (base64).
+    pub public_key: String,
+    /// Peer endpoint ("host:port").
+    pub endpoint: Option<String>,
+    /// Networks routed to the peer, in CIDR notation.
+    pub allowed_ips: Vec<String>,
+    /// Keepalive interval in seconds.
+    pub persistent_keepalive: Option<u32>,
+}
+
+/// Connection settings, keyed by setting name (`ipv4`, `802-3-ethernet`, ...).
+#[derive(Debug, Default)]
+pub struct ConnectionSettings(SettingsDict);
+
+impl ConnectionSettings {
+    /// Create empty settings (for a new connection).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Borrow the raw settings dictionary.
+    pub fn as_dict(&self) -> &SettingsDict {
+        &self.0
+    }
+
+    /// Connection ID (`connection.id`).
+    pub fn id(&self) -> Option<String> {
+        self.get_str("connection", "id")
+    }
+
+    /// Connection UUID (`connection.uuid`).
+    pub fn uuid(&self) -> Option<String> {
+        self.get_str("connection", "uuid")
+    }
+
+    /// Connection type (`connection.type`).
+    pub fn connection_type(&self) -> Option<String> {
+        self.get_str("connection", "type")
+    }
+
+    /// Interface the connection is bound to (`connection.interface-name`).
+    pub fn interface_name(&self) -> Option<String> {
+        self.get_str("connection", "interface-name")
+    }
+
+    /// Wi-Fi SSID (`802-11-wireless.ssid`).
+    pub fn ssid(&self) -> Option<Vec<u8>> {
+        self.get("802-11-wireless", "ssid")
+            .and_then(|v| v.try_clone().ok())
+            .and_then(|v| Vec::<u8>::try_from(v).ok())
+    }
+
+    /// Mark the connection as created by this application.
+    pub fn mark_created(&mut self) {
+        let data = HashMap::from([(CREATED_BY_KEY.to_string(), "network-manager".to_string())]);
+        self.set("user", "data", data);
+    }
+
+    /// Whether the connection was created by this application.
+    pub fn is_created(&self) -> bool {
+        self.get("user", "data")
+            .and_then(|v| v.try_clone().ok())
+            .and_then(|v| HashMap::<String, String>::try_from(v).ok())
+            .is_some_and(|data| data.contains_key(CREATED_BY_KEY))
+    }
+
+    /// Get a raw value.
+    pub fn get(&self, setting: &str, key: &str) -> Option<&OwnedValue> {
+        self.0.get(setting)?.get(key)
+    }
+
+    /// Get a string value.
+    pub fn get_str(&self, setting: &str, key: &str) -> Option<String> {
+        self.get(setting, key)?.downcast_ref::<&str>().ok().map(str::to_string)
+    }
+
+    /// Get a `u32` value.
+    pub fn get_u32(&self, setting: &str, key: &str) -> Option<u32> {
+        self.get(setting, key)?.downcast_ref::<u32>().ok()
+    }
+
+    /// Set a value, creating the setting if needed.
+    pub fn set<'a>(&mut self, setting: &str, key: &str, value: impl Into<Value<'a>>) {
+        // Conversion only fails for file descriptors, which settings never hold.
+        if let Ok(value) = OwnedValue::try_from(value.into()) {
+            self.0
+                .entry(setting.to_string())
+                .or_default()
+                .insert(key.to_string(), value);
+        }
+    }
+
+    /// Remove a value.
+    pub fn remove(&mut self, setting: &str, key: &str) {
+        if let Some(values) = self.0.get_mut(setting) {
+            values.remove(key);
+        }
+    }
+
+    /// Remove a whole setting (e.g. `802-1x`).
+    pub fn remove_setting(&mut self, setting: &str) {
+        self.0.remove(setting);
+    }
+
+    /// Set a certificate or key property (`802-1x.ca-cert`, ...) to a file.
+    ///
+    /// NetworkManager takes paths as a NUL-terminated `file://` URI in a
+    /// byte array.
+    pub fn set_cert_path(&mut self, setting: &str, key: &str, path: &Path) {
+        let mut uri = format!("file://{}", path.display()).into_bytes();
+        uri.push(0);
+        self.set(setting, key, uri);
+    }
+
+    /// Set static IPv4 addresses (`ipv4.address-data`).
+    pub fn set_ipv4_addresses(&mut self, addresses: &[(Ipv4Addr, u8)]) {
+        let data: Vec<_> = addresses
+            .iter()
+            .map(|(address, prefix)| address_data(address.to_string(), *prefix))
+            .collect();
+        // The deprecated `addresses` property takes precedence if present.
+        self.remove("ipv4", "addresses");
+        self.set("ipv4", "address-data", data);
+    }
+
+    /// Set static IPv6 addresses (`ipv6.address-data`).
+    pub fn set_ipv6_addresses(&mut self, addresses: &[(Ipv6Addr, u8)]) {
+        let data: Vec<_> = addresses
+            .iter()
+            .map(|(address, prefix)| address_data(address.to_string(), *prefix))
+            .collect();
+        self.remove("ipv6", "addresses");
+        self.set("ipv6", "address-data", data);
+    }
+
+    /// Set IPv4 DNS servers (`ipv4.dns`, network byte order).
+    pub fn set_ipv4_dns(&mut self, servers: &[Ipv4Addr]) {
+        let servers: Vec<u32> = servers.iter().map(|s| u32::from_ne_bytes(s.octets())).collect();
+        self.set("ipv4", "dns", servers);
+    }
+
+    /// Set IPv6 DNS servers (`ipv6.dns`).
+    pub fn set_ipv6_dns(&mut self, servers: &[Ipv6Addr]) {
+        let servers: Vec<Vec<u8>> = servers.iter().map(|s| s.octets().to_vec()).collect();
+        self.set("ipv6", "dns", servers);
+    }
+
+    /// Set static routes (`<setting>.route-data`) for `ipv4` or `ipv6`.
+    pub fn set_routes(&mut self, setting: &str, routes: &[RouteData]) {
+        // The deprecated `routes` property takes precedence if present.
+        self.remove(setting, "routes");
+        if routes.is_empty() {
+            self.remove(setting, "route-data");
+            return;
+        }
+        let data: Vec<_> = routes
+            .iter()
+            .map(|route| {
+                let mut entry = HashMap::new();
+                entry.insert("dest".to_string(), string_value(route.dest.to_string()));
+                entry.insert("prefix".to_string(), OwnedValue::from(u32::from(route.prefix)));
+                if let Some(next_hop) = route.next_hop {
+                    entry.insert("next-hop".to_string(), string_value(next_hop.to_string()));
+                }
+                if let Some(metric) = route.metric {
+                    entry.insert("metric".to_string(), OwnedValue::from(metric));
+                }
+                if let Some(table) = route.table {
+                    entry.insert("table".to_string(), OwnedValue::from(table));
+                }
+                entry
+            })
+            .collect();
+        self.set(setting, "route-data", data);
+    }
+
+    /// Set policy routing rules (`<setting>.routing-rules`) for `ipv4` or `ipv6`.
+    pub fn set_routing_rules(&mut self, setting: &str, rules: &[RuleData]) {
+        if rules.is_empty() {
+            self.remove(setting, "routing-rules");
+            return;
+        }
+        let family = if setting == "ipv6" { AF_INET6 } else { AF_INET };
+        let data: Vec<_> = rules
+            .iter()
+            .map(|rule| {
+                let mut entry = HashMap::new();
+                entry.insert("family".to_string(), OwnedValue::from(family));
+                entry.insert("priority".to_string(), OwnedValue::from(rule.priority));
+                entry.insert("table".to_string(), OwnedValue::from(rule.table));
+                if let Some((address, len)) = rule.from {
+                    entry.insert("from".to_string(), string_value(address.to_string()));
+                    entry.insert("from-len".to_string(), OwnedValue::from(len));
+                }
+                if let Some((address, len)) = rule.to {
+                    entry.insert("to".to_string(), string_value(address.to_string()));
+                    entry.insert("to-len".to_string(), OwnedValue::from(len));
+                }
+                if let Some((mark, mask)) = rule.fwmark {
+                    entry.insert("fwmark".to_string(), OwnedValue::from(mark));
+                    entry.insert("fwmask".to_string(), OwnedValue::from(mask));
+                }
+                entry
+            })
+            .collect();
+        self.set(setting, "routing-rules", data);
+    }
+
+    /// Set WireGuard peers (`wireguard.peers`).
+    pub fn set_wireguard_peers(&mut self, peers: &[PeerData]) {
+        let data: Vec<_> = peers
+            .iter()
+            .map(|peer| {
+                let mut entry = HashMap::new();
+                entry.insert("public-key".to_string(), string_value(peer.public_key.clone()));
+                if let Some(endpoint) = &peer.endpoint {
+                    entry.insert("endpoint".to_string(), string_value(endpoint.clone()));
+                }
+                let allowed_ips = Value::from(peer.allowed_ips.clone());
+                if let Ok(allowed_ips) = OwnedValue::try_from(allowed_ips) {
+                    entry.insert("allowed-ips".to_string(), allowed_ips);
+                }
+                if let Some(keepalive) = peer.persistent_keepalive {
+                    entry.insert("persistent-keepalive".to_string(), OwnedValue::from(keepalive));
+                }
+                entry
+            })
+            .collect();
+        self.set("wireguard", "peers", data);
+    }
+
+    /// Add secrets returned by `GetSecrets` to the settings.
+    ///
+    /// Values already present are kept, so secret-only copies of composite
+    /// values (such as WireGuard peers) do not replace the full ones.
+    pub fn merge_secrets(&mut self, secrets: SettingsDict) {
+        for (setting, values) in secrets {
+            let target = self.0.entry(setting).or_default();
+            for (key, value) in values {
+                target.entry(key).or_insert(value);
+            }
+        }
+    }
+
+    /// Check whether selected values are the same in both settings.
+    ///
+    /// A missing value and an empty string or array are treated as equal,
+    /// as NetworkManager does.
+    pub fn same_values(&self, other: &Self, keys: &[(&str, &str)]) -> bool {
+        keys.iter()
+            .all(|(setting, key)| self.normalized(setting, key) == other.normalized(setting, key))
+    }
+
+    /// A value rendered for comparison, with empty values as `None`.
+    fn normalized(&self, setting: &str, key: &str) -> Option<String> {
+        let value = self.get(setting, key)?;
+        match &**value {
+            Value::Str(s) if s.is_empty() => None,
+            Value::Array(a) if a.is_empty() => None,
+            v => Some(v.to_string()),
+        }
+    }
+
+    /// Render selected values as `setting.key=value` lines.
+    pub fn describe(&self, keys: &[(&str, &str)]) -> String {
+        keys.iter()
+            .map(|(setting, key)| {
+                let value = self.get(setting, key).map(|v| v.to_string()).unwrap_or_default();
+                format!("{}.{}={}", setting, key, value)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl From<SettingsDict> for ConnectionSettings {
+    fn from(dict: SettingsDict) -> Self {
+        Self(dict)
+    }
+}
+
+impl Clone for ConnectionSettings {
+    fn clone(&self) -> Self {
+        let dict = self
+            .as_dict()
+            .iter()
+            .map(|(setting, values)| {
+                let values = values
+                    .iter()
+                    .filter_map(|(k, v)| Some((k.clone(), v.try_clone().ok()?)))
+                    .collect();
+                (setting.clone(), values)
+            })
+            .collect();
+        Self(dict)
+    }
+}
+
+/// Build one `address-data` entry.
+fn address_data(address: String, prefix: u8) -> HashMap<String, OwnedValue> {
+    let mut entry = HashMap::new();
+    entry.insert("address".to_string(), string_value(address));
+    entry.insert("prefix".to_string(), OwnedValue::from(u32::from(prefix)));
+    entry
+}
+
+/// Wrap a string as a D-Bus value.
+fn string_value(s: String) -> OwnedValue {
+    OwnedValue::from(Str::from(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_get() {
+        let mut settings = ConnectionSettings::new();
+        settings.set("connection", "id", "Office LAN");
+        settings.set("802-3-ethernet", "mtu", 9000u32);
+
+        assert_eq!(settings.id().as_deref(), Some("Office LAN"));
+        assert_eq!(settings.get_u32("802-3-ethernet", "mtu"), Some(9000));
+        assert_eq!(settings.get_str("connection", "uuid"), None);
+
+        settings.remove("802-3-ethernet", "mtu");
+        assert_eq!(settings.get_u32("802-3-ethernet", "mtu"), None);
+    }
+
+    #[test]
+    fn test_ipv4_addresses_replace_deprecated_property() {
+        let mut settings = ConnectionSettings::new();
+        settings.set("ipv4", "addresses", vec![vec![0u32, 24, 0]]);
+        settings.set_ipv4_addresses(&[(Ipv4Addr::new(192, 168, 1, 10), 24)]);
+
+        assert!(settings.get("ipv4", "addresses").is_none());
+        let described = settings.describe(&[("ipv4", "address-data")]);
+        assert!(described.contains("192.168.1.10"));
+    }
+
+    #[test]
+    fn test_ipv4_dns_network_byte_order() {
+        let mut settings = ConnectionSettings::new();
+        settings.set_ipv4_dns(&[Ipv4Addr::new(1, 2, 3, 4)]);
+
+        let dns = Vec::<u32>::try_from(settings.get("ipv4", "dns").unwrap().try_clone().unwrap()).unwrap();
+        assert_eq!(dns[0].to_ne_bytes(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_same_values_treats_empty_as_missing() {
+        let mut current = ConnectionSettings::new();
+        current.set("ipv4", "method", "auto");
+        let mut desired = current.clone();
+        desired.set_ipv4_addresses(&[]);
+        desired.set("ipv4", "dns-search", Vec::<String>::new());
+
+        let keys = [("ipv4", "method"), ("ipv4", "address-data"), ("ipv4", "dns-search")];
+        assert!(current.same_values(&desired, &keys));
+
+        desired.set("ipv4", "method", "manual");
+        assert!(!current.same_values(&desired, &keys));
+    }
+
+    #[test]
+    fn test_routes_and_rules() {
+        let mut settings = ConnectionSettings::new();
+        settings.set("ipv4", "routes", vec![vec![0u32, 24, 0, 0]]);
+        settings.set_routes("ipv4", &[RouteData {
+            dest: "10.20.0.0".parse().unwrap(),
+            prefix: 16,
+            next_hop: Some("192.168.1.1".parse().unwrap()),
+            metric: Some(50),
+            table: None,
+        }]);
+        assert!(settings.get("ipv4", "routes").is_none());
+        let routes = settings.describe(&[("ipv4", "route-data")]);
+        assert!(routes.contains("10.20.0.0") && routes.contains("192.168.1.1"));
+
+        settings.set_routing_rules("ipv6", &[RuleData {
+            priority: 1000,
+            from: Some(("fd00:20::".parse().unwrap(), 64)),
+            to: None,
+            fwmark: None,
+            table: 100,
+        }]);
+        let rules = settings.describe(&[("ipv6", "routing-rules")]);
+        assert!(rules.contains("fd00:20::"));
+
+        settings.set_routes("ipv4", &[]);
+        assert!(settings.get("ipv4", "route-data").is_none());
+    }
+
+    #[test]
+    fn test_wireguard_peers_and_secrets() {
+        let mut settings = ConnectionSettings::new();
+        settings.set_wireguard_peers(&[PeerData {
+            public_key: "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=".to_string(),
+            endpoint: Some("vpn.example.com:51820".to_string()),
+            allowed_ips: vec!["0.0.0.0/0".to_string()],
+            persistent_keepalive: Some(25),
+        }]);
+        let peers = settings.describe(&[("wireguard", "peers")]);
+        assert!(peers.contains("vpn.example.com:51820") && peers.contains("0.0.0.0/0"));
+
+        let mut secrets = ConnectionSettings::new();
+        secrets.set("wireguard", "private-key", "secret");
+        secrets.set("wireguard", "peers", Vec::<HashMap<String, OwnedValue>>::new());
+        settings.merge_secrets(secrets.0);
+        assert_eq!(settings.get_str("wireguard", "private-key").as_deref(), Some("secret"));
+        assert_eq!(settings.describe(&[("wireguard", "peers")]), peers);
+    }
+
+    #[test]
+    fn test_cert_path_and_remove_setting() {
+        let mut settings = ConnectionSettings::new();
+        settings.set_cert_path("802-1x", "ca-cert", Path::new("/etc/ssl/ca.pem"));
+        let value = settings.get("802-1x", "ca-cert").and_then(|v| v.try_clone().ok());
+        let bytes = value.and_then(|v| Vec::<u8>::try_from(v).ok()).unwrap();
+        assert_eq!(bytes, b"file:///etc/ssl/ca.pem\0");
+
+        settings.remove_setting("802-1x");
+        assert!(settings.get("802-1x", "ca-cert").is_none());
+    }
+
+    #[test]
+    fn test_created_marker() {
+        let mut settings = ConnectionSettings::new();
+        assert!(!settings.is_created());
+        settings.mark_created();
+        assert!(settings.is_created());
+    }
+
+    #[test]
+    fn test_ssid_bytes() {
+        let mut settings = ConnectionSettings::new();
+        settings.set("802-11-wireless", "ssid", b"cafe:guest".to_vec());
+        assert_eq!(settings.ssid().as_deref(), Some(&b"cafe:guest"[..]));
+    }
+}
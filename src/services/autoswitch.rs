@@ -9,6 +9,7 @@
 
 use crate::models::rules::{Condition, InterfaceStateMatch, RuleOperator, RuleSet};
 use crate::models::Profile;
+use crate::nm::NmClient;
 use chrono::{Datelike, Local, Timelike};
 use std::collections::HashMap;
 use std::process::Command;
@@ -173,20 +174,13 @@ impl AutoSwitchService {
 
     /// Get current Wi-Fi SSID.
     fn get_current_ssid() -> Option<String> {
-        // Try nmcli first
-        let output = Command::new("nmcli")
-            .args(["-t", "-f", "active,ssid", "dev", "wifi"])
-            .output()
-            .ok()?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        for line in stdout.lines() {
-            if line.starts_with("yes:") {
-                return Some(line.trim_start_matches("yes:").to_string());
+        match NmClient::connect().and_then(|nm| nm.current_ssid()) {
+            Ok(ssid) => ssid,
+            Err(e) => {
+                debug!("Failed to query current SSID: {}", e);
+                None
             }
         }
-        
-        None
     }
 
     /// Get gateway MAC address.
@@ -294,13 +288,8 @@ impl AutoSwitchService {
     /// Check if any network is available.
     fn check_network_available(&self) -> bool {
         // Check if connected to any network
-        Command::new("nmcli")
-            .args(["general", "status"])
-            .output()
-            .map(|out| {
-                let stdout = String::from_utf8_lossy(&out.stdout);
-                stdout.contains("connected")
-            })
+        NmClient::connect()
+            .and_then(|nm| nm.is_connected())
             .unwrap_or(false)
     }
 
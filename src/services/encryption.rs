@@ -6,6 +6,10 @@
 //!
 //! Uses AES-256-GCM for authenticated encryption of sensitive profile data.
 //! Key derivation uses Argon2id with a random salt (stored alongside ciphertext).
+//!
+//! Secrets inside profiles (such as WireGuard private keys) are encrypted
+//! with a random machine-local key kept in [`SECRETS_KEY_FILE`], so they are
+//! protected even when profile encryption is turned off.
 
 use aes_gcm::{
     aead::{Aead, KeyInit},
@@ -14,14 +18,22 @@ use aes_gcm::{
 use argon2::Argon2;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use rand::RngCore;
-use tracing::{debug, error};
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use tracing::{debug, error, info};
 use zeroize::Zeroize;
 
+use crate::models::{Error, Profile, Secret};
+
 /// Length of the random salt used for key derivation.
 const SALT_LEN: usize = 16;
 /// Length of the AES-256-GCM nonce.
 const NONCE_LEN: usize = 12;
 
+/// File (in the config or state directory) holding the key for profile secrets.
+pub const SECRETS_KEY_FILE: &str = "secrets.key";
+
 /// Profile encryption service.
 #[allow(dead_code)]
 pub struct ProfileEncryption {
@@ -102,6 +114,80 @@ impl ProfileEncryption {
         // `with_key` / `set_key` API keeps backward compatibility.
     }
 
+    /// Load the secrets key stored at `path`, generating it on first use.
+    ///
+    /// The key file is created with 0600 permissions and holds 32 random
+    /// bytes in base64.
+    pub fn from_key_file(path: &Path) -> Result<Self, EncryptionError> {
+        let mut passphrase = match fs::read_to_string(path) {
+            Ok(content) => content.trim().to_string(),
+            Err(e) if e.kind() == ErrorKind::NotFound => Self::create_key_file(path)?,
+            Err(e) => return Err(EncryptionError::InvalidData(format!("Cannot read {:?}: {}", path, e))),
+        };
+        if passphrase.is_empty() {
+            return Err(EncryptionError::InvalidData(format!("Key file {:?} is empty", path)));
+        }
+        let service = Self::with_key(&passphrase);
+        passphrase.zeroize();
+        Ok(service)
+    }
+
+    /// Write a new random key file, returning its content.
+    fn create_key_file(path: &Path) -> Result<String, EncryptionError> {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let mut bytes = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut bytes);
+        let key = BASE64.encode(bytes);
+        bytes.zeroize();
+
+        let written = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)
+            .and_then(|mut file| file.write_all(key.as_bytes()).and_then(|_| file.sync_all()));
+        match written {
+            Ok(()) => {
+                info!("Created secrets key {:?}", path);
+                Ok(key)
+            }
+            // Another process created it first.
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => fs::read_to_string(path)
+                .map(|content| content.trim().to_string())
+                .map_err(|e| EncryptionError::InvalidData(format!("Cannot read {:?}: {}", path, e))),
+            Err(e) => Err(EncryptionError::EncryptionFailed(format!("Cannot create {:?}: {}", path, e))),
+        }
+    }
+
+    /// Encrypt a secret in place (no-op if it is already encrypted).
+    pub fn seal(&self, secret: &mut Secret) -> Result<(), EncryptionError> {
+        if let Secret::Plain(plaintext) = secret {
+            let sealed = self.encrypt(plaintext)?;
+            plaintext.zeroize();
+            *secret = Secret::Encrypted(sealed);
+        }
+        Ok(())
+    }
+
+    /// Decrypt a secret in place (no-op if it is not encrypted).
+    pub fn reveal(&self, secret: &mut Secret) -> Result<(), EncryptionError> {
+        if let Secret::Encrypted(ciphertext) = secret {
+            *secret = Secret::Plain(self.decrypt(ciphertext)?);
+        }
+        Ok(())
+    }
+
+    /// Encrypt every secret of a profile.
+    pub fn seal_secrets(&self, profile: &mut Profile) -> Result<(), EncryptionError> {
+        profile.secrets_mut().try_for_each(|secret| self.seal(secret))
+    }
+
+    /// Decrypt every secret of a profile.
+    pub fn reveal_secrets(&self, profile: &mut Profile) -> Result<(), EncryptionError> {
+        profile.secrets_mut().try_for_each(|secret| self.reveal(secret))
+    }
+
     /// Clear the encryption key (zeroed via Zeroize on drop).
     pub fn clear_key(&mut self) {
         self.key_bytes = None;
@@ -256,6 +342,12 @@ impl std::fmt::Display for EncryptionError {
 
 impl std::error::Error for EncryptionError {}
 
+impl From<EncryptionError> for Error {
+    fn from(err: EncryptionError) -> Self {
+        Error::SecretsFailed(err.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,6 +374,37 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_profile_secrets_round_trip() {
+        use crate::models::{NetworkAction, WireGuardPeer};
+
+        let dir = std::env::temp_dir().join(format!("nm-secrets-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("temp dir");
+        let key_file = dir.join(SECRETS_KEY_FILE);
+
+        let mut profile = Profile::new("Tunnel");
+        profile.network_actions.push(NetworkAction::WireGuard {
+            name: "wg0".to_string(),
+            addresses: vec!["10.0.0.2/24".to_string()],
+            private_key: Secret::Plain("private".to_string()),
+            listen_port: None,
+            peers: Vec::<WireGuardPeer>::new(),
+        });
+
+        let enc = ProfileEncryption::from_key_file(&key_file).expect("key file");
+        enc.seal_secrets(&mut profile).expect("seal");
+        let json = serde_json::to_string(&profile).expect("serialize");
+        assert!(!json.contains("private\""));
+        assert!(profile.secrets_mut().all(|s| s.plaintext().is_none()));
+
+        // The key file is reused, not regenerated.
+        let enc = ProfileEncryption::from_key_file(&key_file).expect("key file");
+        enc.reveal_secrets(&mut profile).expect("reveal");
+        assert_eq!(profile.secrets_mut().next().and_then(|s| s.plaintext()), Some("private"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_no_key() {
         let enc = ProfileEncryption::new();
@@ -0,0 +1,339 @@
+// Network Manager - Script Integrity
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Script integrity pinning.
+//!
+//! A script runs only after the user approved it, which stores the SHA-256
+//! digest of the file in its action. Before every run the file is checked
+//! for owners and writers other than root and the running user, then read
+//! once into a private copy while it is hashed. Only that copy runs, so a
+//! script that changed since approval (or could be changed by someone
+//! else) is refused, and one replaced after the check is never executed.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::models::{AutomationAction, Error, Profile, Result};
+
+/// Group- and world-writable permission bits.
+const GROUP_WRITE: u32 = 0o020;
+const OTHER_WRITE: u32 = 0o002;
+const STICKY: u32 = 0o1000;
+
+/// Where the daemon keeps verified copies (its systemd `RuntimeDirectory`).
+const DAEMON_COPIES_DIR: &str = "/run/cd-network-manager/verified";
+
+/// Find the file a script or program action runs.
+///
+/// Program names without a `/` are looked up in `PATH`.
+pub fn resolve(target: &str) -> Option<PathBuf> {
+    if target.contains('/') {
+        return Some(PathBuf::from(target));
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).map(|dir| dir.join(target)).find(|candidate| candidate.is_file())
+}
+
+/// SHA-256 digest of a file, as lowercase hex.
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    hash_copy(&mut File::open(path)?, &mut io::sink())
+}
+
+/// Hash everything read from `source`, writing it to `copy` as well.
+fn hash_copy(source: &mut impl Read, copy: &mut impl Write) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = source.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        copy.write_all(&buffer[..read])?;
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Who besides root and the running user can modify a file, if anyone.
+pub fn foreign_writers(path: &Path) -> io::Result<Option<String>> {
+    // SAFETY: geteuid() has no preconditions.
+    untrusted(path, unsafe { libc::geteuid() })
+}
+
+/// Why a file could be modified by someone other than root or `euid`.
+///
+/// The file and every directory above it must be owned by root or `euid`
+/// (so only root when running as root). A group- or world-writable
+/// directory lets others swap the file, unless it is sticky (like `/tmp`).
+/// Write access for the root group is accepted.
+fn untrusted(path: &Path, euid: u32) -> io::Result<Option<String>> {
+    let path = fs::canonicalize(path)?;
+    for (i, entry) in path.ancestors().enumerate() {
+        let meta = fs::metadata(entry)?;
+        let mode = meta.permissions().mode();
+        let is_dir = i > 0;
+        let what = if is_dir { "directory" } else { "file" };
+        if meta.uid() != 0 && meta.uid() != euid {
+            return Ok(Some(format!("{} {} is owned by uid {}", what, entry.display(), meta.uid())));
+        }
+        if is_dir && mode & STICKY != 0 {
+            continue;
+        }
+        if mode & OTHER_WRITE != 0 {
+            return Ok(Some(format!("{} {} is writable by all users", what, entry.display())));
+        }
+        if mode & GROUP_WRITE != 0 && meta.gid() != 0 {
+            return Ok(Some(format!("{} {} is writable by group {}", what, entry.display(), meta.gid())));
+        }
+    }
+    Ok(None)
+}
+
+/// Directory for verified copies: the daemon's runtime directory as root,
+/// otherwise the user's runtime directory.
+fn copies_dir() -> PathBuf {
+    // SAFETY: geteuid() has no preconditions.
+    if unsafe { libc::geteuid() } == 0 {
+        PathBuf::from(DAEMON_COPIES_DIR)
+    } else {
+        dirs::runtime_dir().unwrap_or_else(std::env::temp_dir).join("cd-network-manager").join("verified")
+    }
+}
+
+/// Check a file against its approved digest and return the private copy
+/// to run instead of it.
+pub fn verify(path: &Path, approved_sha256: Option<&str>) -> Result<PathBuf> {
+    verify_into(path, approved_sha256, &copies_dir())
+}
+
+/// [`verify`], keeping the copy below `copies`.
+///
+/// The file is opened once; the digest is taken from the same read that
+/// fills the copy, so the copy holds exactly the approved bytes.
+fn verify_into(path: &Path, approved_sha256: Option<&str>, copies: &Path) -> Result<PathBuf> {
+    let refuse = |reason: String| Error::ScriptNotTrusted { script: path.display().to_string(), reason };
+    let Some(approved) = approved_sha256 else {
+        return Err(refuse("not approved yet; review it in the application".to_string()));
+    };
+    if !path.exists() {
+        return Err(Error::ScriptNotFound(path.display().to_string()));
+    }
+    if let Some(writers) = foreign_writers(path).map_err(|e| refuse(e.to_string()))? {
+        return Err(refuse(writers));
+    }
+
+    let checked = fs::metadata(path).map_err(|e| refuse(e.to_string()))?;
+    let mut source = File::open(path).map_err(|e| refuse(e.to_string()))?;
+    let opened = source.metadata().map_err(|e| refuse(e.to_string()))?;
+    if (opened.dev(), opened.ino()) != (checked.dev(), checked.ino()) {
+        return Err(refuse("replaced while it was being checked".to_string()));
+    }
+
+    // One directory per digest keeps the file name (and so `$0`) intact.
+    let name = path.file_name().ok_or_else(|| refuse("not a file".to_string()))?;
+    let dir = copies.join(approved.to_ascii_lowercase());
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o711)
+        .create(&dir)
+        .map_err(|e| refuse(format!("cannot create {}: {}", dir.display(), e)))?;
+    if let Some(writers) = foreign_writers(&dir).map_err(|e| refuse(e.to_string()))? {
+        return Err(refuse(writers));
+    }
+
+    // Written under a temporary name and renamed, so a copy that is still
+    // running is never modified.
+    let staging = dir.join(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o755)
+        .open(&staging)
+        .and_then(|mut copy| hash_copy(&mut source, &mut copy));
+    let digest = match written {
+        Ok(digest) => digest,
+        Err(e) => {
+            let _ = fs::remove_file(&staging);
+            return Err(refuse(e.to_string()));
+        }
+    };
+    if !digest.eq_ignore_ascii_case(approved) {
+        let _ = fs::remove_file(&staging);
+        return Err(refuse("changed since it was approved".to_string()));
+    }
+    let copy = dir.join(name);
+    fs::rename(&staging, &copy).map_err(|e| {
+        let _ = fs::remove_file(&staging);
+        refuse(e.to_string())
+    })?;
+    Ok(copy)
+}
+
+/// A file shown for approval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileInfo {
+    /// Resolved path.
+    pub path: PathBuf,
+    /// Owner's user name (or UID if unknown).
+    pub owner: String,
+    /// Permission bits.
+    pub mode: u32,
+    /// SHA-256 digest.
+    pub sha256: String,
+    /// Why the file cannot be trusted regardless of approval, if so.
+    pub problem: Option<String>,
+}
+
+impl FileInfo {
+    /// Read the details of a file.
+    pub fn inspect(path: &Path) -> io::Result<Self> {
+        let meta = fs::metadata(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            owner: user_name(meta.uid()).unwrap_or_else(|| meta.uid().to_string()),
+            mode: meta.permissions().mode() & 0o7777,
+            sha256: sha256_file(path)?,
+            problem: foreign_writers(path)?,
+        })
+    }
+
+    /// Permissions in `ls -l` style, such as `rwxr-xr-x`.
+    pub fn permissions(&self) -> String {
+        let mut text = String::new();
+        for shift in [6, 3, 0] {
+            let bits = (self.mode >> shift) & 0o7;
+            text.push(if bits & 4 != 0 { 'r' } else { '-' });
+            text.push(if bits & 2 != 0 { 'w' } else { '-' });
+            text.push(if bits & 1 != 0 { 'x' } else { '-' });
+        }
+        text
+    }
+}
+
+/// Name of the user with `uid`, from `/etc/passwd`.
+fn user_name(uid: u32) -> Option<String> {
+    let passwd = fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        (fields.nth(1)?.parse::<u32>().ok()? == uid).then(|| name.to_string())
+    })
+}
+
+/// An action of a profile that needs the user's approval before it runs.
+#[derive(Debug, Clone)]
+pub struct PendingApproval {
+    /// Index into the profile's automation actions.
+    pub index: usize,
+    /// What the action runs as written in the profile.
+    pub target: String,
+    /// The file's details, or why they could not be read.
+    pub file: std::result::Result<FileInfo, String>,
+}
+
+/// Actions of a profile whose file is unapproved or changed since approval.
+pub fn pending_approvals(profile: &Profile) -> Vec<PendingApproval> {
+    profile
+        .automation_actions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, action)| {
+            let target = action.pinned_target()?;
+            let file = match resolve(target) {
+                Some(path) => FileInfo::inspect(&path).map_err(|e| format!("{}: {}", path.display(), e)),
+                None => Err(format!("{} not found in PATH", target)),
+            };
+            let approved = action.approved_sha256();
+            let current = matches!(&file, Ok(info) if info.problem.is_none() && Some(info.sha256.as_str()) == approved);
+            (!current).then(|| PendingApproval { index, target: target.to_string(), file })
+        })
+        .collect()
+}
+
+/// Record the user's approval of pending actions that can be trusted.
+///
+/// Returns how many actions were approved.
+pub fn approve(actions: &mut [AutomationAction], pending: &[PendingApproval]) -> usize {
+    let mut approved = 0;
+    for item in pending {
+        if let (Ok(info @ FileInfo { problem: None, .. }), Some(action)) = (&item.file, actions.get_mut(item.index)) {
+            action.set_approved_sha256(Some(info.sha256.clone()));
+            approved += 1;
+        }
+    }
+    approved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::actions::ExecutionPhase;
+    use crate::test_support::{script_action, temp_script};
+
+    #[test]
+    fn test_verify_pinned_script() {
+        let path = temp_script("echo hi", 0o755);
+        let copies = path.parent().unwrap().join("copies");
+        let digest = sha256_file(&path).unwrap();
+        assert_eq!(digest.len(), 64);
+
+        let copy = verify_into(&path, Some(&digest), &copies).unwrap();
+        assert!(copy.starts_with(&copies));
+        assert_eq!(copy.file_name(), path.file_name());
+        assert_eq!(sha256_file(&copy).unwrap(), digest);
+        assert!(matches!(verify_into(&path, None, &copies), Err(Error::ScriptNotTrusted { .. })));
+
+        fs::write(&path, "#!/bin/sh\necho changed\n").unwrap();
+        assert!(matches!(verify_into(&path, Some(&digest), &copies), Err(Error::ScriptNotTrusted { .. })));
+        // The approved copy is left as it was.
+        assert_eq!(sha256_file(&copy).unwrap(), digest);
+
+        let digest = sha256_file(&path).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o757)).unwrap();
+        let err = verify_into(&path, Some(&digest), &copies).unwrap_err();
+        assert!(err.to_string().contains("writable by all users"));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_untrusted_owner() {
+        let path = temp_script("echo hi", 0o755);
+        // SAFETY: geteuid() has no preconditions.
+        let euid = unsafe { libc::geteuid() };
+        if euid == 0 {
+            std::os::unix::fs::chown(&path, Some(65534), Some(65534)).unwrap();
+        }
+        let owner = if euid == 0 { 65534 } else { euid };
+
+        // As root, only root-owned scripts are trusted.
+        let reason = untrusted(&path, 0).unwrap().unwrap();
+        assert!(reason.contains(&format!("is owned by uid {}", owner)), "{}", reason);
+        assert_eq!(untrusted(&path, owner).unwrap(), None);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_pending_approvals() {
+        let path = temp_script("echo hi", 0o700);
+        let mut profile = Profile::new("Office");
+        profile.automation_actions.push(script_action(&path, ExecutionPhase::Post));
+
+        let pending = pending_approvals(&profile);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].file.as_ref().unwrap().permissions(), "rwx------");
+        assert_eq!(approve(&mut profile.automation_actions, &pending), 1);
+        assert!(pending_approvals(&profile).is_empty());
+
+        profile.revoke_approvals();
+        assert_eq!(pending_approvals(&profile).len(), 1);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
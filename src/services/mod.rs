@@ -9,10 +9,12 @@
 //! - Watchdog: Monitors connectivity and takes action on failure
 //! - Sandbox: Provides script execution isolation
 //! - Encryption: Profile data encryption/decryption
+//! - Integrity: Script approval and digest pinning
 
 pub mod watchdog;
 pub mod sandbox;
 pub mod encryption;
+pub mod integrity;
 pub mod autoswitch;
 
 pub use watchdog::WatchdogService;
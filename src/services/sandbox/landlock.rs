@@ -0,0 +1,397 @@
+// Network Manager - Landlock Script Sandbox
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Scripts confined with Landlock and seccomp.
+//!
+//! The script is forked as in the other modes, and between fork and exec
+//! the child restricts itself: a Landlock ruleset limits the file system to
+//! read-only system directories, the script, its working directory and the
+//! paths listed for the script, and a seccomp filter denies system calls a
+//! script has no use for (mounting, tracing, loading modules, creating
+//! namespaces, ...) as well as IP sockets when network access is off. Both
+//! are inherited by everything the script starts and cannot be lifted.
+//!
+//! The ruleset and filter are built in the parent; the child only makes the
+//! system calls that apply them, as nothing else is safe after fork.
+
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::Command;
+
+use tracing::{debug, error, info};
+
+use super::{SandboxError, ScriptCommand};
+
+/// `landlock_create_ruleset` flag returning the supported ABI version.
+const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+/// Rule type for a file or directory hierarchy.
+const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+
+/// File system access rights (`LANDLOCK_ACCESS_FS_*`).
+const ACCESS_FS_EXECUTE: u64 = 1 << 0;
+const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+/// Every right of ABI 1, up to and including `MAKE_SYM`.
+const ACCESS_FS_ABI_1: u64 = (1 << 13) - 1;
+const ACCESS_FS_REFER: u64 = 1 << 13;
+const ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+const ACCESS_FS_IOCTL_DEV: u64 = 1 << 15;
+
+/// Rights that make sense on a regular file rather than a directory.
+const FILE_ACCESS: u64 =
+    ACCESS_FS_EXECUTE | ACCESS_FS_WRITE_FILE | ACCESS_FS_READ_FILE | ACCESS_FS_TRUNCATE | ACCESS_FS_IOCTL_DEV;
+/// Read and execute.
+const READ_ACCESS: u64 = ACCESS_FS_EXECUTE | ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+
+/// System paths every script may read, if present.
+const SYSTEM_PATHS: &[&str] = &[
+    "/usr",
+    "/bin",
+    "/sbin",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/etc/alternatives",
+    "/etc/ld.so.cache",
+    "/etc/localtime",
+    "/etc/nsswitch.conf",
+    "/etc/resolv.conf",
+    "/etc/hosts",
+    "/etc/passwd",
+    "/etc/group",
+    "/etc/ssl",
+    "/etc/pki",
+];
+
+/// Devices every script may read and write.
+const DEVICE_PATHS: &[&str] = &["/dev/null", "/dev/zero", "/dev/random", "/dev/urandom"];
+
+/// System calls denied with `EPERM`.
+const DENIED_SYSCALLS: &[libc::c_long] = &[
+    libc::SYS_ptrace,
+    libc::SYS_process_vm_readv,
+    libc::SYS_process_vm_writev,
+    libc::SYS_mount,
+    libc::SYS_umount2,
+    libc::SYS_pivot_root,
+    libc::SYS_chroot,
+    libc::SYS_unshare,
+    libc::SYS_setns,
+    libc::SYS_init_module,
+    libc::SYS_finit_module,
+    libc::SYS_delete_module,
+    libc::SYS_kexec_load,
+    libc::SYS_kexec_file_load,
+    libc::SYS_reboot,
+    libc::SYS_swapon,
+    libc::SYS_swapoff,
+    libc::SYS_acct,
+    libc::SYS_bpf,
+    libc::SYS_perf_event_open,
+    libc::SYS_userfaultfd,
+    libc::SYS_io_uring_setup,
+    libc::SYS_keyctl,
+    libc::SYS_add_key,
+    libc::SYS_request_key,
+    libc::SYS_open_by_handle_at,
+    libc::SYS_settimeofday,
+    libc::SYS_clock_settime,
+    libc::SYS_sethostname,
+    libc::SYS_setdomainname,
+];
+
+/// `clone` flags that create namespaces.
+const CLONE_NAMESPACES: libc::c_int = libc::CLONE_NEWNS
+    | libc::CLONE_NEWUTS
+    | libc::CLONE_NEWIPC
+    | libc::CLONE_NEWUSER
+    | libc::CLONE_NEWPID
+    | libc::CLONE_NEWNET
+    | libc::CLONE_NEWCGROUP;
+
+/// Socket families denied when a script has no network access.
+const NETWORK_FAMILIES: &[libc::c_int] = &[libc::AF_INET, libc::AF_INET6, libc::AF_PACKET];
+
+/// Audit architecture the seccomp filter is written for.
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: Option<u32> = Some(0xc000_003e);
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: Option<u32> = Some(0xc000_00b7);
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const AUDIT_ARCH: Option<u32> = None;
+
+/// Offsets into `struct seccomp_data`.
+const SECCOMP_DATA_NR: u32 = 0;
+const SECCOMP_DATA_ARCH: u32 = 4;
+/// Low 32 bits of the first argument (both supported targets are little-endian).
+const SECCOMP_DATA_ARG0: u32 = 16;
+
+#[repr(C)]
+struct RulesetAttr {
+    handled_access_fs: u64,
+}
+
+#[repr(C, packed)]
+struct PathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: libc::c_int,
+}
+
+/// The Landlock ABI version of the running kernel, if Landlock is enabled.
+pub fn abi_version() -> Option<u32> {
+    // SAFETY: a null attribute with the version flag only queries the ABI.
+    let abi = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            std::ptr::null::<RulesetAttr>(),
+            0usize,
+            LANDLOCK_CREATE_RULESET_VERSION,
+        )
+    };
+    (abi > 0).then_some(abi as u32)
+}
+
+/// Whether Landlock and a seccomp filter for this architecture can be used.
+pub fn is_available() -> bool {
+    // SAFETY: PR_GET_SECCOMP takes no pointers.
+    let seccomp = unsafe { libc::prctl(libc::PR_GET_SECCOMP, 0, 0, 0, 0) } >= 0;
+    abi_version().is_some() && AUDIT_ARCH.is_some() && seccomp
+}
+
+/// File system rights the kernel's ABI knows about; all of them are
+/// restricted.
+fn handled_access(abi: u32) -> u64 {
+    let mut access = ACCESS_FS_ABI_1;
+    if abi >= 2 {
+        access |= ACCESS_FS_REFER;
+    }
+    if abi >= 3 {
+        access |= ACCESS_FS_TRUNCATE;
+    }
+    if abi >= 5 {
+        access |= ACCESS_FS_IOCTL_DEV;
+    }
+    access
+}
+
+/// A Landlock ruleset allowing `rules`, each a path and its access rights.
+///
+/// Missing paths are an error unless `optional` is set for them.
+fn ruleset(abi: u32, rules: &[(&Path, u64, bool)]) -> Result<OwnedFd, SandboxError> {
+    let failed = |what: &str, e: io::Error| SandboxError::ExecutionFailed(format!("Landlock {}: {}", what, e));
+    let handled = handled_access(abi);
+    let attr = RulesetAttr { handled_access_fs: handled };
+    // SAFETY: attr is a valid ruleset attribute of the given size.
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            &attr as *const RulesetAttr,
+            std::mem::size_of::<RulesetAttr>(),
+            0u32,
+        )
+    };
+    if fd < 0 {
+        return Err(failed("ruleset", io::Error::last_os_error()));
+    }
+    // SAFETY: the kernel returned a new descriptor that nothing else owns.
+    let ruleset = unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) };
+
+    for (path, access, optional) in rules {
+        let file = match OpenOptions::new().read(true).custom_flags(libc::O_PATH | libc::O_CLOEXEC).open(path) {
+            Ok(file) => file,
+            Err(e) if *optional && e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(failed(&path.display().to_string(), e)),
+        };
+        let is_dir = fs::metadata(path).map(|m| m.is_dir()).unwrap_or(false);
+        let allowed = access & handled & if is_dir { u64::MAX } else { FILE_ACCESS };
+        let rule = PathBeneathAttr { allowed_access: allowed, parent_fd: file.as_raw_fd() };
+        // SAFETY: both descriptors are open and rule is a valid path-beneath attribute.
+        let added = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_add_rule,
+                ruleset.as_raw_fd(),
+                LANDLOCK_RULE_PATH_BENEATH,
+                &rule as *const PathBeneathAttr,
+                0u32,
+            )
+        };
+        if added != 0 {
+            return Err(failed(&path.display().to_string(), io::Error::last_os_error()));
+        }
+        debug!("Landlock: {} allowed {:#x}", path.display(), allowed);
+    }
+    Ok(ruleset)
+}
+
+fn statement(code: u32, k: u32) -> libc::sock_filter {
+    libc::sock_filter { code: code as u16, jt: 0, jf: 0, k }
+}
+
+fn jump(code: u32, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+    libc::sock_filter { code: code as u16, jt, jf, k }
+}
+
+/// Return `errno` from a system call.
+fn deny(errno: libc::c_int) -> libc::sock_filter {
+    statement(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_ERRNO | (errno as u32 & libc::SECCOMP_RET_DATA))
+}
+
+/// The seccomp program for a script.
+fn seccomp_filter(arch: u32, allow_network: bool) -> Vec<libc::sock_filter> {
+    let load = |offset| statement(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, offset);
+    let jeq = |k, jt, jf| jump(libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K, k, jt, jf);
+
+    // System call numbers differ per architecture, so anything else dies.
+    let mut filter = vec![
+        load(SECCOMP_DATA_ARCH),
+        jeq(arch, 1, 0),
+        statement(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_KILL_PROCESS),
+        load(SECCOMP_DATA_NR),
+    ];
+    if cfg!(target_arch = "x86_64") {
+        // x32 system calls share the x86-64 audit architecture.
+        filter.push(jump(libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K, 0x4000_0000, 0, 1));
+        filter.push(deny(libc::ENOSYS));
+    }
+    for nr in DENIED_SYSCALLS {
+        filter.push(jeq(*nr as u32, 0, 1));
+        filter.push(deny(libc::EPERM));
+    }
+
+    // clone3 hides its flags behind a pointer; C libraries fall back to clone.
+    filter.push(jeq(libc::SYS_clone3 as u32, 0, 1));
+    filter.push(deny(libc::ENOSYS));
+    filter.extend([
+        jeq(libc::SYS_clone as u32, 0, 3),
+        load(SECCOMP_DATA_ARG0),
+        jump(libc::BPF_JMP | libc::BPF_JSET | libc::BPF_K, CLONE_NAMESPACES as u32, 0, 1),
+        deny(libc::EPERM),
+        load(SECCOMP_DATA_NR),
+    ]);
+
+    if !allow_network {
+        let families = NETWORK_FAMILIES.len() as u8;
+        filter.push(jeq(libc::SYS_socket as u32, 0, families + 2));
+        filter.push(load(SECCOMP_DATA_ARG0));
+        for (i, family) in NETWORK_FAMILIES.iter().enumerate() {
+            let remaining = families - 1 - i as u8;
+            // The last family falls through to the allow below when it differs.
+            let jf = if remaining == 0 { 1 } else { 0 };
+            filter.push(jeq(*family as u32, remaining, jf));
+        }
+        filter.push(deny(libc::EACCES));
+    }
+    filter.push(statement(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_ALLOW));
+    filter
+}
+
+/// Command running the script under Landlock and seccomp.
+/// Returns an error if the kernel lacks either — never falls back silently.
+pub(super) fn command(script: &ScriptCommand) -> Result<Command, SandboxError> {
+    let (Some(abi), Some(arch), true) = (abi_version(), AUDIT_ARCH, is_available()) else {
+        error!("Landlock or seccomp is not available but sandbox mode is set to Landlock");
+        return Err(SandboxError::SandboxNotAvailable(
+            "Landlock is not enabled in this kernel (Linux 5.13 or newer with lsm=landlock is required). \
+             Choose another sandbox mode or disable sandboxing."
+                .to_string(),
+        ));
+    };
+
+    info!("Executing script with Landlock ABI {} and seccomp: {}", abi, script.path.display());
+
+    let mut rules: Vec<(&Path, u64, bool)> = Vec::new();
+    rules.extend(SYSTEM_PATHS.iter().map(|p| (Path::new(*p), READ_ACCESS, true)));
+    rules.extend(DEVICE_PATHS.iter().map(|p| (Path::new(*p), FILE_ACCESS, true)));
+    rules.push((script.path, READ_ACCESS, false));
+    if let Some(dir) = script.working_dir {
+        rules.push((dir, u64::MAX, false));
+    }
+    rules.extend(script.sandbox.read_only_paths.iter().map(|p| (p.as_path(), READ_ACCESS, false)));
+    rules.extend(script.sandbox.writable_paths.iter().map(|p| (p.as_path(), u64::MAX, false)));
+    let ruleset = ruleset(abi, &rules)?;
+    let filter = seccomp_filter(arch, script.sandbox.allow_network);
+
+    let mut cmd = Command::new(script.path);
+    // SAFETY: the closure runs between fork and exec and only makes system
+    // calls on data prepared above; it neither allocates nor locks.
+    unsafe {
+        cmd.pre_exec(move || {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if libc::syscall(libc::SYS_landlock_restrict_self, ruleset.as_raw_fd(), 0u32) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let program = libc::sock_fprog { len: filter.len() as u16, filter: filter.as_ptr() as *mut _ };
+            if libc::syscall(libc::SYS_seccomp, libc::SECCOMP_SET_MODE_FILTER, 0u32, &program as *const _) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    Ok(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SandboxMode, ScriptMode, ScriptSandbox};
+    use crate::services::SandboxRunner;
+    use crate::test_support::temp_script;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_seccomp_filter() {
+        let arch = 0xc000_003e;
+        let open = seccomp_filter(arch, true);
+        let closed = seccomp_filter(arch, false);
+        assert_eq!(open[1].k, arch);
+        assert_eq!(open.last().unwrap().k, libc::SECCOMP_RET_ALLOW);
+        assert_eq!(closed.len(), open.len() + NETWORK_FAMILIES.len() + 3);
+
+        // Every jump stays inside the program.
+        for (i, insn) in closed.iter().enumerate() {
+            if u32::from(insn.code) & 0x07 == libc::BPF_JMP {
+                assert!(i + 1 + usize::from(insn.jt.max(insn.jf)) < closed.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_landlock_confines_script() {
+        let path = temp_script("echo ok > allowed.txt\necho no > \"$OTHER/denied.txt\" || true", 0o755);
+        let dir = path.parent().unwrap().to_path_buf();
+        let other = std::env::temp_dir().join(format!("nm-landlock-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&other).unwrap();
+
+        let env = HashMap::from([("OTHER".to_string(), other.display().to_string())]);
+        let sandbox = ScriptSandbox::default();
+        let script = ScriptCommand {
+            path: &path,
+            args: &[],
+            env: &env,
+            working_dir: Some(&dir),
+            mode: &ScriptMode::Wait,
+            sandbox: &sandbox,
+            label: "test",
+        };
+        let runner = SandboxRunner::new(SandboxMode::Landlock);
+        let result = runner.execute(&script);
+
+        if runner.is_available() {
+            assert!(result.unwrap().status.unwrap().success());
+            assert!(dir.join("allowed.txt").exists());
+            assert!(!other.join("denied.txt").exists());
+        } else {
+            assert!(matches!(result, Err(SandboxError::SandboxNotAvailable(_))));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&other).unwrap();
+    }
+}
@@ -0,0 +1,465 @@
+// Network Manager - Script Sandboxing
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Script execution with sandboxing support.
+//!
+//! Provides isolated script execution using bubblewrap, firejail, a
+//! transient systemd service or Landlock and seccomp.
+//! When the user configures sandboxing, the required tool **must** be
+//! present — falling back to unsandboxed execution is a security violation.
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+use crate::models::{SandboxMode, ScriptMode, ScriptSandbox, SystemdSandboxConfig};
+
+mod landlock;
+mod systemd;
+
+/// Output kept per stream; anything beyond is read and dropped.
+pub const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// How often a running script is checked for exit.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Time between SIGTERM and SIGKILL when a script times out.
+const KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// A script and how to run it.
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptCommand<'a> {
+    /// Path to the script.
+    pub path: &'a Path,
+    /// Arguments to pass.
+    pub args: &'a [String],
+    /// Extra environment variables.
+    pub env: &'a HashMap<String, String>,
+    /// Working directory (writable inside the sandbox).
+    pub working_dir: Option<&'a Path>,
+    /// Whether to wait, wait with a timeout, or run in the background.
+    pub mode: &'a ScriptMode,
+    /// Paths and network access allowed in the Landlock mode.
+    pub sandbox: &'a ScriptSandbox,
+    /// What the script runs for (such as the profile and action), used to
+    /// label its output in the log.
+    pub label: &'a str,
+}
+
+/// Captured output of a script.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptOutput {
+    /// Exit status (`None` for background scripts, which are not waited for).
+    pub status: Option<ExitStatus>,
+    /// Standard output, up to [`MAX_OUTPUT_BYTES`].
+    pub stdout: String,
+    /// Standard error, up to [`MAX_OUTPUT_BYTES`].
+    pub stderr: String,
+    /// Whether output beyond the limit was dropped.
+    pub truncated: bool,
+}
+
+impl ScriptOutput {
+    /// Both streams for the log, leaving out empty ones.
+    pub fn combined(&self) -> String {
+        let mut combined = Vec::new();
+        if !self.stdout.trim().is_empty() {
+            combined.push(format!("stdout:\n{}", self.stdout.trim_end()));
+        }
+        if !self.stderr.trim().is_empty() {
+            combined.push(format!("stderr:\n{}", self.stderr.trim_end()));
+        }
+        if self.truncated {
+            combined.push(format!("(output truncated to {} bytes per stream)", MAX_OUTPUT_BYTES));
+        }
+        combined.join("\n")
+    }
+}
+
+/// Runner for executing scripts with optional sandboxing.
+#[allow(dead_code)]
+pub struct SandboxRunner {
+    mode: SandboxMode,
+    systemd: SystemdSandboxConfig,
+}
+
+impl Default for SandboxRunner {
+    fn default() -> Self {
+        Self::new(SandboxMode::None)
+    }
+}
+
+#[allow(dead_code)]
+impl SandboxRunner {
+    /// Create a new sandbox runner with the specified mode.
+    pub fn new(mode: SandboxMode) -> Self {
+        Self { mode, systemd: SystemdSandboxConfig::default() }
+    }
+
+    /// Use these limits for [`SandboxMode::Systemd`].
+    pub fn with_systemd_config(mut self, config: SystemdSandboxConfig) -> Self {
+        self.systemd = config;
+        self
+    }
+
+    /// Update the sandbox mode.
+    pub fn set_mode(&mut self, mode: SandboxMode) {
+        self.mode = mode;
+    }
+
+    /// Get the current sandbox mode.
+    pub fn mode(&self) -> SandboxMode {
+        self.mode
+    }
+
+    /// Check if the required sandbox tool is available.
+    pub fn is_available(&self) -> bool {
+        match self.mode {
+            SandboxMode::None => true,
+            SandboxMode::Bubblewrap => Self::command_exists("bwrap"),
+            SandboxMode::Firejail => Self::command_exists("firejail"),
+            SandboxMode::Systemd => systemd::is_available(),
+            SandboxMode::Landlock => landlock::is_available(),
+        }
+    }
+
+    /// Check if a command exists in PATH.
+    fn command_exists(cmd: &str) -> bool {
+        Command::new("which")
+            .arg(cmd)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Execute a script with the configured sandboxing.
+    ///
+    /// The script runs in its own process group so a timeout kills
+    /// everything it started. Output is kept and logged up to
+    /// [`MAX_OUTPUT_BYTES`] per stream.
+    pub fn execute(&self, script: &ScriptCommand) -> Result<ScriptOutput, SandboxError> {
+        if !script.path.exists() {
+            return Err(SandboxError::ScriptNotFound(script.path.display().to_string()));
+        }
+
+        let mut cmd = match self.mode {
+            SandboxMode::None => self.direct_command(script),
+            SandboxMode::Bubblewrap => self.bubblewrap_command(script)?,
+            SandboxMode::Firejail => self.firejail_command(script)?,
+            SandboxMode::Landlock => landlock::command(script)?,
+            // systemd starts the script itself.
+            SandboxMode::Systemd => return systemd::execute(script, &self.systemd),
+        };
+        cmd.args(script.args)
+            .envs(script.env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0);
+        if let Some(dir) = script.working_dir {
+            cmd.current_dir(dir);
+        }
+
+        let mut child = cmd.spawn().map_err(|e| SandboxError::ExecutionFailed(e.to_string()))?;
+        let name = script.path.display().to_string();
+        let stdout = child.stdout.take().map(|s| collect_output(s, format!("{} stdout", script.label)));
+        let stderr = child.stderr.take().map(|s| collect_output(s, format!("{} stderr", script.label)));
+
+        let status = match script.mode {
+            ScriptMode::Background => {
+                thread::spawn(move || {
+                    match child.wait() {
+                        Ok(status) => info!("Background script {} exited with {}", name, status),
+                        Err(e) => warn!("Failed to wait for background script {}: {}", name, e),
+                    }
+                    join_output(stdout);
+                    join_output(stderr);
+                });
+                return Ok(ScriptOutput::default());
+            }
+            ScriptMode::Wait => child.wait().map_err(|e| SandboxError::ExecutionFailed(e.to_string()))?,
+            ScriptMode::Timeout { seconds } => {
+                let timeout = Duration::from_secs(u64::from(*seconds));
+                match wait_timeout(&mut child, timeout).map_err(|e| SandboxError::ExecutionFailed(e.to_string()))? {
+                    Some(status) => status,
+                    None => {
+                        warn!("Script {} timed out after {}s; killing its process group", name, seconds);
+                        kill_process_group(&mut child);
+                        join_output(stdout);
+                        join_output(stderr);
+                        return Err(SandboxError::TimedOut(*seconds));
+                    }
+                }
+            }
+        };
+
+        let (stdout, stdout_truncated) = join_output(stdout);
+        let (stderr, stderr_truncated) = join_output(stderr);
+        Ok(ScriptOutput { status: Some(status), stdout, stderr, truncated: stdout_truncated || stderr_truncated })
+    }
+
+    /// Command running the script directly without sandboxing.
+    fn direct_command(&self, script: &ScriptCommand) -> Command {
+        debug!("Executing script directly: {}", script.path.display());
+        Command::new(script.path)
+    }
+
+    /// Command running the script under bubblewrap.
+    /// Returns an error if bwrap is not installed — never falls back silently.
+    fn bubblewrap_command(&self, script: &ScriptCommand) -> Result<Command, SandboxError> {
+        if !Self::command_exists("bwrap") {
+            error!("Bubblewrap (bwrap) is not installed but sandbox mode is set to Bubblewrap");
+            return Err(SandboxError::SandboxNotAvailable(
+                "bubblewrap (bwrap) is not installed. Install it or disable sandboxing.".to_string(),
+            ));
+        }
+
+        info!("Executing script with bubblewrap: {}", script.path.display());
+
+        // Build bubblewrap command with reasonable defaults.
+        // Use --ro-bind-try for paths that may not exist on all distros.
+        let mut cmd = Command::new("bwrap");
+        cmd.args([
+            // Bind essential directories read-only
+            "--ro-bind", "/usr", "/usr",
+            "--ro-bind", "/lib", "/lib",
+        ]);
+        // /lib64 may not exist on all distributions (e.g. Arch uses symlinks)
+        if Path::new("/lib64").exists() {
+            cmd.args(["--ro-bind", "/lib64", "/lib64"]);
+        }
+        cmd.args([
+            "--ro-bind", "/bin", "/bin",
+            "--ro-bind", "/sbin", "/sbin",
+            "--ro-bind", "/etc/resolv.conf", "/etc/resolv.conf",
+            "--ro-bind", "/etc/hosts", "/etc/hosts",
+            "--ro-bind", "/etc/passwd", "/etc/passwd",
+            "--ro-bind", "/etc/group", "/etc/group",
+        ]);
+        // Bind the script itself
+        cmd.arg("--ro-bind").arg(script.path).arg(script.path);
+        // The working directory is the one place the script may write to
+        if let Some(dir) = script.working_dir {
+            cmd.arg("--bind").arg(dir).arg(dir).arg("--chdir").arg(dir);
+        }
+        cmd.args([
+            // Create necessary directories
+            "--tmpfs", "/tmp",
+            "--proc", "/proc",
+            "--dev", "/dev",
+            // Network access (needed for network scripts)
+            "--share-net",
+            // Disable new privileges
+            "--new-session",
+            // Unshare namespaces for isolation
+            "--unshare-user",
+            "--unshare-pid",
+            "--unshare-ipc",
+            // Die with parent
+            "--die-with-parent",
+        ]);
+        // The script to execute
+        cmd.arg(script.path);
+        Ok(cmd)
+    }
+
+    /// Command running the script under firejail.
+    /// Returns an error if firejail is not installed — never falls back silently.
+    fn firejail_command(&self, script: &ScriptCommand) -> Result<Command, SandboxError> {
+        if !Self::command_exists("firejail") {
+            error!("Firejail is not installed but sandbox mode is set to Firejail");
+            return Err(SandboxError::SandboxNotAvailable(
+                "firejail is not installed. Install it or disable sandboxing.".to_string(),
+            ));
+        }
+
+        info!("Executing script with firejail: {}", script.path.display());
+
+        let mut cmd = Command::new("firejail");
+        cmd.args([
+            // Use a restrictive profile
+            "--quiet",
+            "--noprofile",
+            // Limit capabilities
+            "--caps.drop=all",
+            // No root access
+            "--noroot",
+            // Private /tmp
+            "--private-tmp",
+            // Read-only system
+            "--read-only=/",
+            // Allow network for network scripts
+            // Disable dbus
+            "--nodbus",
+        ]);
+        // The working directory is the one place the script may write to
+        if let Some(dir) = script.working_dir {
+            cmd.arg(format!("--read-write={}", dir.display()));
+        }
+        // The script
+        cmd.arg(script.path);
+        Ok(cmd)
+    }
+}
+
+/// Read a stream on its own thread, keeping and logging the first
+/// [`MAX_OUTPUT_BYTES`]. Anything beyond is read and dropped, so a script
+/// cannot fill memory or the log. Returns the kept output and whether any
+/// was dropped.
+fn collect_output(mut stream: impl Read + Send + 'static, label: String) -> JoinHandle<(String, bool)> {
+    thread::spawn(move || {
+        let log = |bytes: &[u8]| {
+            for line in String::from_utf8_lossy(bytes).lines() {
+                debug!("{}: {}", label, line);
+            }
+        };
+        let mut kept = Vec::new();
+        let mut logged = 0;
+        let mut truncated = false;
+        let mut chunk = [0u8; 4096];
+        loop {
+            let read = match stream.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            };
+            let room = MAX_OUTPUT_BYTES - kept.len();
+            truncated |= read > room;
+            kept.extend_from_slice(&chunk[..read.min(room)]);
+            // Log complete lines as they arrive.
+            if let Some(end) = kept[logged..].iter().rposition(|b| *b == b'\n') {
+                log(&kept[logged..logged + end + 1]);
+                logged += end + 1;
+            }
+        }
+        log(&kept[logged..]);
+        if truncated {
+            debug!("{}: output beyond {} bytes dropped", label, MAX_OUTPUT_BYTES);
+        }
+        (String::from_utf8_lossy(&kept).into_owned(), truncated)
+    })
+}
+
+/// Wait for an output reader to finish.
+fn join_output(reader: Option<JoinHandle<(String, bool)>>) -> (String, bool) {
+    reader.and_then(|r| r.join().ok()).unwrap_or_default()
+}
+
+/// Wait for a child to exit, giving up after `timeout`.
+fn wait_timeout(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(None);
+        }
+        thread::sleep(WAIT_POLL_INTERVAL.min(remaining));
+    }
+}
+
+/// Terminate a child's process group, escalating to SIGKILL for anything
+/// still running after [`KILL_GRACE`].
+fn kill_process_group(child: &mut Child) {
+    // The child was started with process_group(0), so its PID is the group ID.
+    let group = -(child.id() as libc::pid_t);
+    // SAFETY: kill() only takes plain integers.
+    unsafe { libc::kill(group, libc::SIGTERM) };
+    let _ = wait_timeout(child, KILL_GRACE);
+    // SAFETY: as above; the group is gone (ESRCH) if everything exited.
+    unsafe { libc::kill(group, libc::SIGKILL) };
+    let _ = child.wait();
+}
+
+/// Errors that can occur during sandboxed execution.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum SandboxError {
+    /// Script file not found.
+    ScriptNotFound(String),
+    /// Execution failed.
+    ExecutionFailed(String),
+    /// Sandbox tool not available.
+    SandboxNotAvailable(String),
+    /// The script ran longer than its timeout (in seconds) and was killed.
+    TimedOut(u32),
+}
+
+impl std::fmt::Display for SandboxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ScriptNotFound(path) => write!(f, "Script not found: {}", path),
+            Self::ExecutionFailed(msg) => write!(f, "Execution failed: {}", msg),
+            Self::SandboxNotAvailable(tool) => write!(f, "Sandbox tool not available: {}", tool),
+            Self::TimedOut(seconds) => write!(f, "Timed out after {}s", seconds),
+        }
+    }
+}
+
+impl std::error::Error for SandboxError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_script;
+
+    #[test]
+    fn test_sandbox_runner_none() {
+        let runner = SandboxRunner::new(SandboxMode::None);
+        assert!(runner.is_available());
+    }
+
+    #[test]
+    fn test_execute_with_env_and_working_dir() {
+        let path = temp_script("echo \"$GREETING from $(pwd)\"; echo oops >&2", 0o755);
+        let dir = path.parent().unwrap();
+        let env = HashMap::from([("GREETING".to_string(), "hello".to_string())]);
+        let command = ScriptCommand { path: &path, args: &[], env: &env, working_dir: Some(dir), mode: &ScriptMode::Wait, sandbox: &ScriptSandbox::default(), label: "test" };
+
+        let output = SandboxRunner::new(SandboxMode::None).execute(&command).unwrap();
+        assert!(output.status.unwrap().success());
+        assert_eq!(output.stdout, format!("hello from {}\n", dir.display()));
+        assert_eq!(output.stderr, "oops\n");
+        assert!(!output.truncated);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_execute_timeout_and_truncation() {
+        let path = temp_script("sleep 30 & wait", 0o755);
+        let env = HashMap::new();
+        let mode = ScriptMode::Timeout { seconds: 1 };
+        let command = ScriptCommand { path: &path, args: &[], env: &env, working_dir: None, mode: &mode, sandbox: &ScriptSandbox::default(), label: "test" };
+        let started = Instant::now();
+        // The background sleep holds the output pipes open, so this only
+        // returns once the whole process group is gone.
+        let result = SandboxRunner::new(SandboxMode::None).execute(&command);
+        assert!(matches!(result, Err(SandboxError::TimedOut(1))));
+        assert!(started.elapsed() < Duration::from_secs(10));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        let path = temp_script("yes | head -c 100000", 0o755);
+        let command = ScriptCommand { path: &path, args: &[], env: &env, working_dir: None, mode: &ScriptMode::Wait, sandbox: &ScriptSandbox::default(), label: "test" };
+        let output = SandboxRunner::new(SandboxMode::None).execute(&command).unwrap();
+        assert_eq!(output.stdout.len(), MAX_OUTPUT_BYTES);
+        assert!(output.truncated);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_command_exists() {
+        // 'sh' should exist on any Unix system
+        assert!(SandboxRunner::command_exists("sh"));
+        // Random non-existent command
+        assert!(!SandboxRunner::command_exists("nonexistent_command_xyz"));
+    }
+}
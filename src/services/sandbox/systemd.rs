@@ -0,0 +1,317 @@
+// Network Manager - systemd Script Sandbox
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Scripts run as transient systemd services.
+//!
+//! The script becomes a `.service` unit started with `StartTransientUnit`
+//! on the system manager (from the daemon) or the user manager (from the
+//! GUI), with a read-only file system, private `/tmp`, no new privileges,
+//! an optional IP allow-list and memory, CPU and run-time limits. The unit
+//! writes to pipes passed with the request, so output is logged as with
+//! the other modes, and its resource use shows up in systemd's accounting.
+//!
+//! The user manager cannot apply IP filtering and some namespacing without
+//! privileges; those settings are fully enforced when the daemon runs
+//! scripts.
+
+use std::fs::File;
+use std::net::IpAddr;
+use std::os::fd::{FromRawFd, OwnedFd};
+use std::os::unix::process::ExitStatusExt;
+use std::path::Path;
+use std::process::ExitStatus;
+use std::thread;
+
+use tracing::{info, warn};
+use zbus::blocking::Connection;
+use zbus::proxy;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{Fd, OwnedObjectPath, Value};
+
+use super::{collect_output, join_output, SandboxError, ScriptCommand, ScriptOutput, WAIT_POLL_INTERVAL};
+use crate::models::{ScriptMode, SystemdSandboxConfig};
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1",
+    gen_async = false
+)]
+trait Manager {
+    fn start_transient_unit(
+        &self,
+        name: &str,
+        mode: &str,
+        properties: &[(&str, Value<'_>)],
+        aux: &[(&str, &[(&str, Value<'_>)])],
+    ) -> zbus::Result<OwnedObjectPath>;
+
+    fn get_unit(&self, name: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn stop_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn reset_failed_unit(&self, name: &str) -> zbus::Result<()>;
+}
+
+#[proxy(interface = "org.freedesktop.systemd1.Unit", default_service = "org.freedesktop.systemd1", gen_async = false)]
+trait Unit {
+    #[zbus(property)]
+    fn active_state(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn sub_state(&self) -> zbus::Result<String>;
+}
+
+#[proxy(interface = "org.freedesktop.systemd1.Service", default_service = "org.freedesktop.systemd1", gen_async = false)]
+trait Service {
+    #[zbus(property)]
+    fn result(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn exec_main_code(&self) -> zbus::Result<i32>;
+
+    #[zbus(property)]
+    fn exec_main_status(&self) -> zbus::Result<i32>;
+}
+
+/// `si_code` of a child that exited normally (`CLD_EXITED`).
+const CLD_EXITED: i32 = 1;
+
+/// Whether this host runs systemd as its init system.
+pub fn is_available() -> bool {
+    Path::new("/run/systemd/system").exists()
+}
+
+/// The manager scripts are started on: the system manager for root,
+/// otherwise the calling user's manager.
+fn connect() -> Result<Connection, SandboxError> {
+    // SAFETY: geteuid() has no preconditions.
+    let connection = if unsafe { libc::geteuid() } == 0 { Connection::system() } else { Connection::session() };
+    connection.map_err(|e| SandboxError::SandboxNotAvailable(format!("systemd manager: {}", e)))
+}
+
+fn dbus_error(e: zbus::Error) -> SandboxError {
+    SandboxError::ExecutionFailed(e.to_string())
+}
+
+/// Parse an address or CIDR network into systemd's `(family, address,
+/// prefix)` form.
+fn ip_prefix(cidr: &str) -> Result<(i32, Vec<u8>, u32), SandboxError> {
+    let invalid = || SandboxError::ExecutionFailed(format!("Invalid IP address allow entry: {}", cidr));
+    let (address, prefix) = match cidr.trim().split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix.parse::<u32>().map_err(|_| invalid())?)),
+        None => (cidr.trim(), None),
+    };
+    let (family, bytes, bits) = match address.parse::<IpAddr>().map_err(|_| invalid())? {
+        IpAddr::V4(v4) => (libc::AF_INET, v4.octets().to_vec(), 32),
+        IpAddr::V6(v6) => (libc::AF_INET6, v6.octets().to_vec(), 128),
+    };
+    let prefix = prefix.unwrap_or(bits);
+    if prefix > bits {
+        return Err(invalid());
+    }
+    Ok((family, bytes, prefix))
+}
+
+/// Unit properties for a script, besides its output.
+fn properties(
+    script: &ScriptCommand,
+    config: &SystemdSandboxConfig,
+    runtime_max_secs: u32,
+) -> Result<Vec<(&'static str, Value<'static>)>, SandboxError> {
+    let path = script.path.display().to_string();
+    let mut argv = vec![path.clone()];
+    argv.extend(script.args.iter().cloned());
+    let environment: Vec<String> = script.env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+
+    let mut properties: Vec<(&'static str, Value<'static>)> = vec![
+        ("Description", Value::from(format!("CD Network Manager script {}", path))),
+        ("Type", Value::from("exec")),
+        ("ExecStart", Value::from(vec![(path, argv, false)])),
+        ("Environment", Value::from(environment)),
+        ("ProtectSystem", Value::from("strict")),
+        ("ProtectHome", Value::from("read-only")),
+        ("PrivateTmp", Value::from(true)),
+        ("PrivateDevices", Value::from(true)),
+        ("NoNewPrivileges", Value::from(true)),
+        ("ProtectKernelTunables", Value::from(true)),
+        ("ProtectKernelModules", Value::from(true)),
+        ("ProtectControlGroups", Value::from(true)),
+        ("RestrictSUIDSGID", Value::from(true)),
+    ];
+    // The working directory is the one place the script may write to
+    if let Some(dir) = script.working_dir {
+        let dir = dir.display().to_string();
+        properties.push(("WorkingDirectory", Value::from(dir.clone())));
+        properties.push(("ReadWritePaths", Value::from(vec![dir])));
+    }
+    if !config.ip_address_allow.is_empty() {
+        let allow = config.ip_address_allow.iter().map(|c| ip_prefix(c)).collect::<Result<Vec<_>, _>>()?;
+        let deny = vec![(libc::AF_INET, vec![0u8; 4], 0u32), (libc::AF_INET6, vec![0u8; 16], 0u32)];
+        properties.push(("IPAddressAllow", Value::from(allow)));
+        properties.push(("IPAddressDeny", Value::from(deny)));
+    }
+    if config.memory_max_mb > 0 {
+        properties.push(("MemoryMax", Value::from(config.memory_max_mb * 1024 * 1024)));
+    }
+    if config.cpu_quota_percent > 0 {
+        // CPU time per second of wall clock, in microseconds.
+        properties.push(("CPUQuotaPerSecUSec", Value::from(u64::from(config.cpu_quota_percent) * 10_000)));
+    }
+    if runtime_max_secs > 0 {
+        properties.push(("RuntimeMaxUSec", Value::from(u64::from(runtime_max_secs) * 1_000_000)));
+    }
+    Ok(properties)
+}
+
+/// A pipe whose ends are closed on exec.
+fn pipe() -> Result<(OwnedFd, OwnedFd), SandboxError> {
+    let mut fds = [0; 2];
+    // SAFETY: fds has room for the two descriptors pipe2() writes.
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(SandboxError::ExecutionFailed(std::io::Error::last_os_error().to_string()));
+    }
+    // SAFETY: pipe2() succeeded, so both descriptors are open and ours.
+    Ok(unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
+}
+
+/// Run a script as a transient service.
+pub(super) fn execute(script: &ScriptCommand, config: &SystemdSandboxConfig) -> Result<ScriptOutput, SandboxError> {
+    if !is_available() {
+        return Err(SandboxError::SandboxNotAvailable(
+            "systemd is not the init system. Choose another sandbox mode.".to_string(),
+        ));
+    }
+
+    let connection = connect()?;
+    let manager = ManagerProxy::new(&connection).map_err(dbus_error)?;
+    let unit = format!("cd-network-manager-script-{}.service", uuid::Uuid::new_v4().simple());
+    let name = script.path.display().to_string();
+    let runtime_max_secs = match script.mode {
+        ScriptMode::Timeout { seconds } => *seconds,
+        _ => config.runtime_max_secs,
+    };
+    let mut properties: Vec<(&str, Value<'_>)> = properties(script, config, runtime_max_secs)?;
+
+    info!("Executing script as systemd unit {}: {}", unit, name);
+
+    if let ScriptMode::Background = script.mode {
+        // Output goes to the journal; the unit is unloaded once it ends.
+        properties.push(("CollectMode", Value::from("inactive-or-failed")));
+        manager.start_transient_unit(&unit, "fail", &properties, &[]).map_err(dbus_error)?;
+        return Ok(ScriptOutput::default());
+    }
+
+    let (stdout_read, stdout_write) = pipe()?;
+    let (stderr_read, stderr_write) = pipe()?;
+    // Keep the unit loaded after the script exits so its result can be read.
+    properties.push(("RemainAfterExit", Value::from(true)));
+    properties.push(("StandardOutputFileDescriptor", Value::from(Fd::from(&stdout_write))));
+    properties.push(("StandardErrorFileDescriptor", Value::from(Fd::from(&stderr_write))));
+    manager.start_transient_unit(&unit, "fail", &properties, &[]).map_err(dbus_error)?;
+    // systemd holds its own copies now; the readers see EOF once they close.
+    drop(properties);
+    drop((stdout_write, stderr_write));
+    let stdout = collect_output(File::from(stdout_read), format!("{} stdout", script.label));
+    let stderr = collect_output(File::from(stderr_read), format!("{} stderr", script.label));
+
+    let status = wait(&connection, &manager, &unit);
+    // Stopping the unit also kills anything the script left running.
+    if let Err(e) = manager.stop_unit(&unit, "replace") {
+        warn!("Failed to stop systemd unit {}: {}", unit, e);
+    }
+    let _ = manager.reset_failed_unit(&unit);
+    let (stdout, stdout_truncated) = join_output(Some(stdout));
+    let (stderr, stderr_truncated) = join_output(Some(stderr));
+
+    let status = match status? {
+        Some(status) => status,
+        None => {
+            warn!("Script {} timed out after {}s; systemd stopped it", name, runtime_max_secs);
+            return Err(SandboxError::TimedOut(runtime_max_secs));
+        }
+    };
+    Ok(ScriptOutput { status: Some(status), stdout, stderr, truncated: stdout_truncated || stderr_truncated })
+}
+
+/// Wait for a unit's script to end. Returns its exit status, or `None` if
+/// systemd stopped it for running too long.
+fn wait(connection: &Connection, manager: &ManagerProxy, unit: &str) -> Result<Option<ExitStatus>, SandboxError> {
+    let path = manager.get_unit(unit).map_err(dbus_error)?;
+    let unit_proxy = UnitProxy::builder(connection)
+        .path(path.clone())
+        .and_then(|b| b.cache_properties(CacheProperties::No).build())
+        .map_err(dbus_error)?;
+    let service = ServiceProxy::builder(connection)
+        .path(path)
+        .and_then(|b| b.cache_properties(CacheProperties::No).build())
+        .map_err(dbus_error)?;
+
+    loop {
+        let active = unit_proxy.active_state().map_err(dbus_error)?;
+        let sub = unit_proxy.sub_state().map_err(dbus_error)?;
+        if active == "failed" || active == "inactive" || sub == "exited" {
+            break;
+        }
+        thread::sleep(WAIT_POLL_INTERVAL);
+    }
+
+    if service.result().map_err(dbus_error)? == "timeout" {
+        return Ok(None);
+    }
+    let code = service.exec_main_code().map_err(dbus_error)?;
+    let status = service.exec_main_status().map_err(dbus_error)?;
+    // Encode as a wait() status: exit codes sit in the second byte.
+    Ok(Some(if code == CLD_EXITED { ExitStatus::from_raw(status << 8) } else { ExitStatus::from_raw(status) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ScriptSandbox;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_ip_prefix() {
+        assert_eq!(ip_prefix("10.0.0.0/8").unwrap(), (libc::AF_INET, vec![10, 0, 0, 0], 8));
+        assert_eq!(ip_prefix("192.168.1.1").unwrap(), (libc::AF_INET, vec![192, 168, 1, 1], 32));
+        let (family, bytes, prefix) = ip_prefix("fd00::/64").unwrap();
+        assert_eq!((family, bytes.len(), prefix), (libc::AF_INET6, 16, 64));
+        assert!(ip_prefix("10.0.0.0/33").is_err());
+        assert!(ip_prefix("example.com").is_err());
+    }
+
+    #[test]
+    fn test_properties() {
+        let path = Path::new("/usr/local/bin/vpn-up");
+        let env = HashMap::from([("CDNM_PHASE".to_string(), "post".to_string())]);
+        let args = ["--quiet".to_string()];
+        let sandbox = ScriptSandbox::default();
+        let script = ScriptCommand {
+            path,
+            args: &args,
+            env: &env,
+            working_dir: None,
+            mode: &ScriptMode::Wait,
+            sandbox: &sandbox,
+            label: "test",
+        };
+        let config = SystemdSandboxConfig { ip_address_allow: vec!["10.0.0.0/8".to_string()], ..Default::default() };
+
+        let properties = properties(&script, &config, 30).unwrap();
+        let get = |name: &str| properties.iter().find(|(n, _)| *n == name).map(|(_, v)| v);
+        assert_eq!(get("ProtectSystem"), Some(&Value::from("strict")));
+        assert_eq!(get("NoNewPrivileges"), Some(&Value::from(true)));
+        assert_eq!(get("RuntimeMaxUSec"), Some(&Value::from(30_000_000u64)));
+        assert_eq!(get("MemoryMax"), Some(&Value::from(256u64 * 1024 * 1024)));
+        assert_eq!(get("CPUQuotaPerSecUSec"), Some(&Value::from(500_000u64)));
+        assert_eq!(get("Environment"), Some(&Value::from(vec!["CDNM_PHASE=post".to_string()])));
+        assert!(get("IPAddressDeny").is_some());
+        assert!(get("WorkingDirectory").is_none());
+
+        let config = SystemdSandboxConfig { memory_max_mb: 0, ..Default::default() };
+        let properties = super::properties(&script, &config, 0).unwrap();
+        assert!(!properties.iter().any(|(n, _)| ["MemoryMax", "RuntimeMaxUSec", "IPAddressAllow"].contains(n)));
+    }
+}
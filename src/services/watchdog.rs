@@ -13,6 +13,7 @@ use std::time::Duration;
 use tracing::{debug, info, warn};
 
 use crate::models::{WatchdogAction, WatchdogConfig};
+use crate::nm::NmClient;
 
 /// Watchdog service for monitoring connectivity.
 #[allow(dead_code)]
@@ -96,22 +97,16 @@ impl WatchdogService {
             }
             WatchdogAction::Reconnect => {
                 info!("Watchdog: attempting to reconnect");
-                // Try to restart NetworkManager connection
-                let result = Command::new("nmcli")
-                    .args(["networking", "off"])
-                    .status();
+                // Turn networking off and on again
+                let nm = NmClient::connect().map_err(|e| e.to_string())?;
+                let off = nm.set_networking(false);
                 
                 std::thread::sleep(Duration::from_secs(2));
                 
-                let result2 = Command::new("nmcli")
-                    .args(["networking", "on"])
-                    .status();
-                
-                if result.is_ok() && result2.is_ok() {
-                    Ok(())
-                } else {
-                    Err("Failed to restart networking".to_string())
-                }
+                // Networking is turned back on even if turning it off failed.
+                nm.set_networking(true)
+                    .and(off)
+                    .map_err(|e| format!("Failed to restart networking: {}", e))
             }
             WatchdogAction::SwitchProfile => {
                 // Profile switch is handled by the caller (needs profile ID)
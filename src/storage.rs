@@ -16,11 +16,12 @@
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 use tracing::{error, info, warn};
 
 use crate::models::{AppConfig, Profile, CONFIG_DIR_NAME};
+use crate::services::encryption::{EncryptionError, SECRETS_KEY_FILE};
 use crate::services::ProfileEncryption;
 
 /// A log entry with timestamp, level, and message.
@@ -40,6 +41,8 @@ pub struct DataStore {
     settings_file: PathBuf,
     /// Local profile cache file.
     profiles_cache_file: PathBuf,
+    /// Key file for secrets inside profiles.
+    secrets_key_file: PathBuf,
     /// Log file path.
     logs_file: PathBuf,
 
@@ -76,6 +79,7 @@ impl DataStore {
         let store = Self {
             settings_file: config_dir.join("settings.toml"),
             profiles_cache_file: config_dir.join("profiles_cache.json"),
+            secrets_key_file: config_dir.join(SECRETS_KEY_FILE),
             logs_file: config_dir.join("logs.json"),
             config_dir,
             profiles: RwLock::new(HashMap::new()),
@@ -198,7 +202,14 @@ impl DataStore {
     }
 
     /// Update the local profile cache.
-    pub fn update_profiles_cache(&self, profiles: Vec<Profile>) {
+    ///
+    /// Profile secrets are encrypted first; if that fails nothing is saved,
+    /// so secrets never reach the cache in plaintext.
+    pub fn update_profiles_cache(&self, mut profiles: Vec<Profile>) {
+        if let Err(e) = self.seal_secrets(&mut profiles) {
+            error!("Not saving profiles, their secrets could not be encrypted: {}", e);
+            return;
+        }
         self.write_lock(&self.profiles, "profiles", |cache| {
             cache.clear();
             for profile in profiles {
@@ -208,6 +219,34 @@ impl DataStore {
         self.save_profiles_cache();
     }
 
+    // ========================================================================
+    // Profile Secrets
+    // ========================================================================
+
+    /// Key file used to encrypt secrets inside profiles.
+    pub fn secrets_key_file(&self) -> &Path {
+        &self.secrets_key_file
+    }
+
+    /// Encrypt the plaintext secrets of profiles.
+    fn seal_secrets(&self, profiles: &mut [Profile]) -> Result<(), EncryptionError> {
+        let mut plain = profiles
+            .iter_mut()
+            .flat_map(Profile::secrets_mut)
+            .filter(|secret| secret.plaintext().is_some())
+            .peekable();
+        if plain.peek().is_none() {
+            return Ok(());
+        }
+        let encryption = ProfileEncryption::from_key_file(&self.secrets_key_file)?;
+        plain.try_for_each(|secret| encryption.seal(secret))
+    }
+
+    /// Decrypt the secrets of a profile (e.g., before handing it to the daemon).
+    pub fn reveal_secrets(&self, profile: &mut Profile) -> Result<(), EncryptionError> {
+        reveal_profile_secrets(&self.secrets_key_file, profile)
+    }
+
     // ========================================================================
     // Profiles Cache
     // ========================================================================
@@ -437,3 +476,15 @@ impl Default for DataStore {
         Self::new()
     }
 }
+
+/// Decrypt the secrets of a profile with the key stored in `key_file`.
+///
+/// Usable away from the [`DataStore`], e.g. from a background task.
+pub fn reveal_profile_secrets(key_file: &Path, profile: &mut Profile) -> Result<(), EncryptionError> {
+    let mut sealed = profile.secrets_mut().filter(|secret| secret.plaintext().is_none()).peekable();
+    if sealed.peek().is_none() {
+        return Ok(());
+    }
+    let encryption = ProfileEncryption::from_key_file(key_file)?;
+    sealed.try_for_each(|secret| encryption.reveal(secret))
+}
@@ -0,0 +1,63 @@
+// Network Manager - Test Support
+// Copyright (C) 2026 Christos A. Daggas
+// SPDX-License-Identifier: MIT
+
+//! Helpers shared by unit tests.
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+use crate::models::actions::ExecutionPhase;
+use crate::models::AutomationAction;
+
+/// Write a shell script running `body` with permissions `mode` to a fresh
+/// private temporary directory. Tests remove the directory when done.
+pub fn temp_script(body: &str, mode: u32) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("nm-test-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::set_permissions(&dir, fs::Permissions::from_mode(0o700)).unwrap();
+    let path = dir.join("script.sh");
+    fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+    path
+}
+
+/// A script running `path` in `phase` (pre- or post-script), or a program
+/// for any other phase, with every other field at its default.
+///
+/// Tests build script actions here so a new field is added in one place.
+pub fn script_action(path: impl Into<PathBuf>, phase: ExecutionPhase) -> AutomationAction {
+    let path = path.into();
+    match phase {
+        ExecutionPhase::Pre => AutomationAction::PreScript {
+            path,
+            args: Vec::new(),
+            env: Default::default(),
+            mode: Default::default(),
+            working_dir: None,
+            continue_on_error: false,
+            sandbox: Default::default(),
+            approved_sha256: None,
+        },
+        ExecutionPhase::Post => AutomationAction::PostScript {
+            path,
+            args: Vec::new(),
+            env: Default::default(),
+            mode: Default::default(),
+            working_dir: None,
+            continue_on_error: false,
+            sandbox: Default::default(),
+            approved_sha256: None,
+        },
+        _ => AutomationAction::RunProgram {
+            program: path.to_string_lossy().into_owned(),
+            args: Vec::new(),
+            env: Default::default(),
+            mode: Default::default(),
+            working_dir: None,
+            pin_digest: false,
+            approved_sha256: None,
+        },
+    }
+}
@@ -18,9 +18,13 @@ use std::sync::Arc;
 use std::rc::Rc;
 
 use crate::application::Application;
-use crate::storage::DataStore;
+use crate::executor::{ActionExecutor, SystemExecutor};
+use crate::storage::{self, DataStore};
 use crate::ui::pages::{DashboardPage, LogsPage, ProfilesPage, SettingsPage, HelpPage};
-use crate::models::Profile;
+use crate::dbus_client::DaemonClient;
+use crate::models::plan::ChangeKind;
+use crate::services::integrity::{self, PendingApproval};
+use crate::models::{ActionScope, CheckpointSettings, ExecutionPlan, ExecutionResult, Profile, ProxyBackend};
 
 /// Navigation items for the sidebar.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -654,6 +658,7 @@ impl MainWindow {
             "Public WiFi",
             "VPN Only",
             "Development",
+            "Wi-Fi Hotspot",
         ]);
         let template_row = adw::ComboRow::builder()
             .title("Template")
@@ -888,10 +893,47 @@ impl MainWindow {
 
         content.append(&vpn_group);
 
+        // === Network Safety ===
+        let safety_group = adw::PreferencesGroup::new();
+        safety_group.set_title("Network Safety");
+        safety_group.set_description(Some("Revert network changes unless they are confirmed"));
+
+        let confirm_changes = adw::SwitchRow::builder()
+            .title("Confirm Network Changes")
+            .subtitle("Restore the previous network settings if not confirmed in time")
+            .active(false)
+            .build();
+        safety_group.add(&confirm_changes);
+
+        let confirm_timeout = adw::SpinRow::builder()
+            .title("Revert After")
+            .subtitle("Seconds to wait for confirmation")
+            .adjustment(&gtk::Adjustment::new(
+                f64::from(CheckpointSettings::DEFAULT_TIMEOUT_SECS),
+                10.0, 600.0, 5.0, 30.0, 0.0,
+            ))
+            .sensitive(false)
+            .build();
+        safety_group.add(&confirm_timeout);
+
+        let probe_host = adw::EntryRow::new();
+        probe_host.set_title("Confirm Automatically If Host Is Reachable");
+        probe_host.set_sensitive(false);
+        safety_group.add(&probe_host);
+
+        let confirm_timeout_weak = confirm_timeout.downgrade();
+        let probe_host_weak = probe_host.downgrade();
+        confirm_changes.connect_active_notify(move |row| {
+            if let Some(r) = confirm_timeout_weak.upgrade() { r.set_sensitive(row.is_active()); }
+            if let Some(e) = probe_host_weak.upgrade() { e.set_sensitive(row.is_active()); }
+        });
+
+        content.append(&safety_group);
+
         // === Proxy Settings ===
         let proxy_group = adw::PreferencesGroup::new();
         proxy_group.set_title("Proxy Settings");
-        proxy_group.set_description(Some("Configure system proxy via gsettings"));
+        proxy_group.set_description(Some("Configure the proxy for desktops and tools"));
 
         let proxy_mode_model = gtk::StringList::new(&["None", "Manual", "Automatic (PAC)"]);
         let proxy_mode_row = adw::ComboRow::builder()
@@ -913,6 +955,16 @@ impl MainWindow {
         https_proxy.set_sensitive(false);
         proxy_group.add(&https_proxy);
 
+        let ftp_proxy = adw::EntryRow::new();
+        ftp_proxy.set_title("FTP Proxy");
+        ftp_proxy.set_sensitive(false);
+        proxy_group.add(&ftp_proxy);
+
+        let socks_proxy = adw::EntryRow::new();
+        socks_proxy.set_title("SOCKS Proxy");
+        socks_proxy.set_sensitive(false);
+        proxy_group.add(&socks_proxy);
+
         let no_proxy = adw::EntryRow::new();
         no_proxy.set_title("No Proxy For");
         no_proxy.set_text("localhost,127.0.0.1");
@@ -924,18 +976,28 @@ impl MainWindow {
         pac_url.set_sensitive(false);
         proxy_group.add(&pac_url);
 
+        let (proxy_backends, proxy_backend_rows) = Self::proxy_backend_rows(&[ProxyBackend::Gnome]);
+        proxy_backends.set_sensitive(false);
+        proxy_group.add(&proxy_backends);
+
         let http_proxy_weak = http_proxy.downgrade();
         let https_proxy_weak = https_proxy.downgrade();
+        let ftp_proxy_weak = ftp_proxy.downgrade();
+        let socks_proxy_weak = socks_proxy.downgrade();
         let no_proxy_weak = no_proxy.downgrade();
         let pac_url_weak = pac_url.downgrade();
+        let proxy_backends_weak = proxy_backends.downgrade();
         proxy_mode_row.connect_selected_notify(move |row| {
             let mode = row.selected();
             let is_manual = mode == 1;
             let is_auto = mode == 2;
             if let Some(e) = http_proxy_weak.upgrade() { e.set_sensitive(is_manual); }
             if let Some(e) = https_proxy_weak.upgrade() { e.set_sensitive(is_manual); }
+            if let Some(e) = ftp_proxy_weak.upgrade() { e.set_sensitive(is_manual); }
+            if let Some(e) = socks_proxy_weak.upgrade() { e.set_sensitive(is_manual); }
             if let Some(e) = no_proxy_weak.upgrade() { e.set_sensitive(is_manual); }
             if let Some(e) = pac_url_weak.upgrade() { e.set_sensitive(is_auto); }
+            if let Some(e) = proxy_backends_weak.upgrade() { e.set_sensitive(mode > 0); }
         });
 
         content.append(&proxy_group);
@@ -971,6 +1033,21 @@ impl MainWindow {
         post_script_path.set_sensitive(false);
         scripts_group.add(&post_script_path);
 
+        let script_read_paths = adw::EntryRow::new();
+        script_read_paths.set_title("Script Read-Only Paths");
+        scripts_group.add(&script_read_paths);
+
+        let script_write_paths = adw::EntryRow::new();
+        script_write_paths.set_title("Script Writable Paths");
+        scripts_group.add(&script_write_paths);
+
+        let script_network = adw::SwitchRow::builder()
+            .title("Allow Script Network Access")
+            .subtitle("Paths and network access apply in the Landlock sandbox mode")
+            .active(true)
+            .build();
+        scripts_group.add(&script_network);
+
         let run_program_enabled = adw::SwitchRow::builder()
             .title("Run Program")
             .subtitle("Launch an application when profile activates")
@@ -988,6 +1065,14 @@ impl MainWindow {
         run_program_args.set_sensitive(false);
         scripts_group.add(&run_program_args);
 
+        let run_program_pin = adw::SwitchRow::builder()
+            .title("Verify Program")
+            .subtitle("Only run the program while it matches the approved version")
+            .active(false)
+            .sensitive(false)
+            .build();
+        scripts_group.add(&run_program_pin);
+
         // Script toggle handlers
         let pre_script_path_weak = pre_script_path.downgrade();
         pre_script_enabled.connect_active_notify(move |row| {
@@ -1001,10 +1086,12 @@ impl MainWindow {
 
         let run_program_path_weak = run_program_path.downgrade();
         let run_program_args_weak = run_program_args.downgrade();
+        let run_program_pin_weak = run_program_pin.downgrade();
         run_program_enabled.connect_active_notify(move |row| {
             let enabled = row.is_active();
             if let Some(e) = run_program_path_weak.upgrade() { e.set_sensitive(enabled); }
             if let Some(e) = run_program_args_weak.upgrade() { e.set_sensitive(enabled); }
+            if let Some(e) = run_program_pin_weak.upgrade() { e.set_sensitive(enabled); }
         });
 
         content.append(&scripts_group);
@@ -1039,20 +1126,31 @@ impl MainWindow {
         // VPN
         let vpn_enabled_weak = vpn_enabled.downgrade();
         let vpn_name_weak = vpn_name.downgrade();
+        // Network safety
+        let confirm_changes_weak = confirm_changes.downgrade();
+        let confirm_timeout_weak = confirm_timeout.downgrade();
+        let probe_host_weak = probe_host.downgrade();
         // Proxy
         let proxy_mode_row_weak = proxy_mode_row.downgrade();
         let http_proxy_weak = http_proxy.downgrade();
         let https_proxy_weak = https_proxy.downgrade();
+        let ftp_proxy_weak = ftp_proxy.downgrade();
+        let socks_proxy_weak = socks_proxy.downgrade();
         let no_proxy_weak = no_proxy.downgrade();
         let pac_url_weak = pac_url.downgrade();
+        let proxy_backend_rows: Vec<_> = proxy_backend_rows.iter().map(|(b, r)| (*b, r.downgrade())).collect();
         // Scripts
         let pre_script_enabled_weak = pre_script_enabled.downgrade();
         let pre_script_path_weak = pre_script_path.downgrade();
         let post_script_enabled_weak = post_script_enabled.downgrade();
         let post_script_path_weak = post_script_path.downgrade();
+        let script_read_paths_weak = script_read_paths.downgrade();
+        let script_write_paths_weak = script_write_paths.downgrade();
+        let script_network_weak = script_network.downgrade();
         let run_program_enabled_weak = run_program_enabled.downgrade();
         let run_program_path_weak = run_program_path.downgrade();
         let run_program_args_weak = run_program_args.downgrade();
+        let run_program_pin_weak = run_program_pin.downgrade();
         // Adapter widgets
         let adapter_widgets_ref = adapter_widgets.clone();
         
@@ -1066,21 +1164,42 @@ impl MainWindow {
             let vpn_on = vpn_enabled_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
             let vpn_name_val = vpn_name_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             
+            // Network safety settings
+            let confirm_on = confirm_changes_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
+            let confirm_secs = confirm_timeout_weak.upgrade().map(|r| r.value() as u32).unwrap_or(CheckpointSettings::DEFAULT_TIMEOUT_SECS);
+            let probe_host_val = probe_host_weak.upgrade().map(|e| e.text().trim().to_string()).unwrap_or_default();
+            
             // Proxy settings
             let proxy_mode = proxy_mode_row_weak.upgrade().map(|r| r.selected()).unwrap_or(0);
             let http_proxy_val = http_proxy_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             let https_proxy_val = https_proxy_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
+            let ftp_proxy_val = ftp_proxy_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
+            let socks_proxy_val = socks_proxy_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             let no_proxy_val = no_proxy_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             let pac_url_val = pac_url_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
+            let proxy_backends_val: Vec<ProxyBackend> = proxy_backend_rows
+                .iter()
+                .filter(|(_, row)| row.upgrade().is_some_and(|r| r.is_active()))
+                .map(|(backend, _)| *backend)
+                .collect();
             
             // Script settings
             let pre_script_on = pre_script_enabled_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
             let pre_script = pre_script_path_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             let post_script_on = post_script_enabled_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
             let post_script = post_script_path_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
+            let path_list = |text: String| -> Vec<PathBuf> {
+                text.split(',').map(str::trim).filter(|s| !s.is_empty()).map(PathBuf::from).collect()
+            };
+            let script_sandbox = crate::models::ScriptSandbox {
+                read_only_paths: script_read_paths_weak.upgrade().map(|e| path_list(e.text().to_string())).unwrap_or_default(),
+                writable_paths: script_write_paths_weak.upgrade().map(|e| path_list(e.text().to_string())).unwrap_or_default(),
+                allow_network: script_network_weak.upgrade().map(|r| r.is_active()).unwrap_or(true),
+            };
             let run_prog_on = run_program_enabled_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
             let run_prog_path = run_program_path_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             let run_prog_args = run_program_args_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
+            let run_prog_pin = run_program_pin_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
             
             // Collect adapter configurations
             let adapter_configs: Vec<(String, bool, u32, String, String, String, u32, String, String, Option<String>)> = 
@@ -1120,6 +1239,7 @@ impl MainWindow {
                     3 => ProfileTemplate::PublicWifi.create_profile(&name),
                     4 => ProfileTemplate::VpnOnly.create_profile(&name),
                     5 => ProfileTemplate::Development.create_profile(&name),
+                    6 => ProfileTemplate::Hotspot.create_profile(&name),
                     _ => Profile::new(&name),
                 };
                 
@@ -1132,8 +1252,25 @@ impl MainWindow {
                     profile.metadata.group = Some(crate::models::ProfileGroup::new(&group_name));
                 }
                 
+                // A hotspot runs on the first Wi-Fi adapter, which is then left to it
+                let mut hotspot_iface = None;
+                if let Some(NetworkAction::Hotspot { interface, .. }) = profile
+                    .network_actions
+                    .iter_mut()
+                    .find(|a| matches!(a, NetworkAction::Hotspot { .. }))
+                {
+                    if let Some(config) = adapter_configs.iter().find(|c| c.9.is_some()) {
+                        *interface = config.0.clone();
+                    }
+                    hotspot_iface = Some(interface.clone());
+                }
+                
                 // === Process each adapter configuration ===
                 for (iface, enabled, ip_method, static_ip, subnet, gateway, dns_method, dns1, dns2, wifi_ssid) in adapter_configs {
+                    if hotspot_iface.as_ref() == Some(&iface) {
+                        continue;
+                    }
+                    
                     // Add interface enable/disable action
                     profile.network_actions.push(NetworkAction::InterfaceEnable(InterfaceState {
                         interface: iface.clone(),
@@ -1199,6 +1336,9 @@ impl MainWindow {
                                 profile.network_actions.push(NetworkAction::WifiConnect {
                                     ssid,
                                     interface: Some(iface.clone()),
+                                    security: None,
+                                    hidden: false,
+                                    bssid: None,
                                 });
                             }
                         }
@@ -1230,10 +1370,11 @@ impl MainWindow {
                         mode,
                         http_proxy: if proxy_mode == 1 && !http_proxy_val.is_empty() { Some(http_proxy_val) } else { None },
                         https_proxy: if proxy_mode == 1 && !https_proxy_val.is_empty() { Some(https_proxy_val) } else { None },
-                        ftp_proxy: None,
-                        socks_proxy: None,
+                        ftp_proxy: if proxy_mode == 1 && !ftp_proxy_val.is_empty() { Some(ftp_proxy_val) } else { None },
+                        socks_proxy: if proxy_mode == 1 && !socks_proxy_val.is_empty() { Some(socks_proxy_val) } else { None },
                         no_proxy: no_proxy_list,
                         pac_url: if proxy_mode == 2 && !pac_url_val.is_empty() { Some(pac_url_val) } else { None },
+                        backends: proxy_backends_val,
                     }));
                 }
                 
@@ -1246,6 +1387,8 @@ impl MainWindow {
                         mode: crate::models::ScriptMode::Wait,
                         working_dir: None,
                         continue_on_error: false,
+                        sandbox: script_sandbox.clone(),
+                        approved_sha256: None,
                     });
                 }
                 
@@ -1258,6 +1401,8 @@ impl MainWindow {
                         mode: crate::models::ScriptMode::Wait,
                         working_dir: None,
                         continue_on_error: false,
+                        sandbox: script_sandbox.clone(),
+                        approved_sha256: None,
                     });
                 }
                 
@@ -1274,9 +1419,17 @@ impl MainWindow {
                         env: std::collections::HashMap::new(),
                         mode: crate::models::ProgramMode::Background,
                         working_dir: None,
+                        pin_digest: run_prog_pin,
+                        approved_sha256: None,
                     });
                 }
                 
+                // === Network Safety ===
+                profile.checkpoint = confirm_on.then(|| CheckpointSettings {
+                    timeout_secs: confirm_secs,
+                    probe_host: (!probe_host_val.is_empty()).then_some(probe_host_val),
+                });
+                
                 // Store the profile
                 let imp = window.imp();
                 let profile_name_for_log = name.clone();
@@ -1316,6 +1469,26 @@ impl MainWindow {
         dialog.present(Some(self));
     }
     
+    /// Switch rows choosing where a proxy configuration is written.
+    fn proxy_backend_rows(selected: &[ProxyBackend]) -> (adw::ExpanderRow, Vec<(ProxyBackend, adw::SwitchRow)>) {
+        let expander = adw::ExpanderRow::builder()
+            .title("Apply To")
+            .subtitle("Desktops and tools that use the proxy")
+            .build();
+        let rows = ProxyBackend::ALL
+            .into_iter()
+            .map(|backend| {
+                let row = adw::SwitchRow::builder()
+                    .title(backend.display_name())
+                    .active(selected.contains(&backend))
+                    .build();
+                expander.add_row(&row);
+                (backend, row)
+            })
+            .collect();
+        (expander, rows)
+    }
+
     /// Helper to convert subnet mask string to prefix length
     fn subnet_to_prefix(subnet: &str) -> Option<u8> {
         match subnet.trim() {
@@ -1348,45 +1521,6 @@ impl MainWindow {
         }
     }
     
-    /// Get the NetworkManager connection name for a given interface
-    fn get_connection_for_interface(interface: &str) -> Option<String> {
-        use std::process::Command;
-        
-        // Use nmcli to get the connection name for this device
-        let output = Command::new("nmcli")
-            .args(["-t", "-f", "NAME,DEVICE", "connection", "show", "--active"])
-            .output()
-            .ok()?;
-        
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let parts: Vec<&str> = line.split(':').collect();
-                if parts.len() >= 2 && parts[1] == interface {
-                    return Some(parts[0].to_string());
-                }
-            }
-        }
-        
-        // Fallback: try to find any connection that uses this interface
-        let output = Command::new("nmcli")
-            .args(["-t", "-f", "NAME,DEVICE", "connection", "show"])
-            .output()
-            .ok()?;
-        
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let parts: Vec<&str> = line.split(':').collect();
-                if parts.len() >= 2 && parts[1] == interface {
-                    return Some(parts[0].to_string());
-                }
-            }
-        }
-        
-        None
-    }
-    
     /// Show edit profile dialog
     fn show_edit_profile_dialog(&self, profile_id: &str) {
         use crate::network_utils::detect_network_adapters;
@@ -1505,7 +1639,7 @@ impl MainWindow {
         
         let find_wifi_ssid = |iface: &str| -> Option<String> {
             for action in &profile.network_actions {
-                if let NetworkAction::WifiConnect { ssid, interface } = action {
+                if let NetworkAction::WifiConnect { ssid, interface, .. } = action {
                     if interface.as_deref() == Some(iface) {
                         return Some(ssid.clone());
                     }
@@ -1729,10 +1863,49 @@ impl MainWindow {
 
         content.append(&vpn_group);
 
+        // === Network Safety ===
+        let existing_checkpoint = profile.checkpoint.clone();
+        let safety_group = adw::PreferencesGroup::new();
+        safety_group.set_title("Network Safety");
+        safety_group.set_description(Some("Revert network changes unless they are confirmed"));
+
+        let confirm_changes = adw::SwitchRow::builder()
+            .title("Confirm Network Changes")
+            .subtitle("Restore the previous network settings if not confirmed in time")
+            .active(existing_checkpoint.is_some())
+            .build();
+        safety_group.add(&confirm_changes);
+
+        let confirm_timeout = adw::SpinRow::builder()
+            .title("Revert After")
+            .subtitle("Seconds to wait for confirmation")
+            .adjustment(&gtk::Adjustment::new(
+                f64::from(existing_checkpoint.as_ref().map(|c| c.timeout_secs).unwrap_or(CheckpointSettings::DEFAULT_TIMEOUT_SECS)),
+                10.0, 600.0, 5.0, 30.0, 0.0,
+            ))
+            .sensitive(confirm_changes.is_active())
+            .build();
+        safety_group.add(&confirm_timeout);
+
+        let probe_host = adw::EntryRow::new();
+        probe_host.set_title("Confirm Automatically If Host Is Reachable");
+        probe_host.set_text(&existing_checkpoint.and_then(|c| c.probe_host).unwrap_or_default());
+        probe_host.set_sensitive(confirm_changes.is_active());
+        safety_group.add(&probe_host);
+
+        let confirm_timeout_weak = confirm_timeout.downgrade();
+        let probe_host_weak = probe_host.downgrade();
+        confirm_changes.connect_active_notify(move |row| {
+            if let Some(r) = confirm_timeout_weak.upgrade() { r.set_sensitive(row.is_active()); }
+            if let Some(e) = probe_host_weak.upgrade() { e.set_sensitive(row.is_active()); }
+        });
+
+        content.append(&safety_group);
+
         // === Proxy Settings ===
         let proxy_group = adw::PreferencesGroup::new();
         proxy_group.set_title("Proxy Settings");
-        proxy_group.set_description(Some("Configure system proxy via gsettings"));
+        proxy_group.set_description(Some("Configure the proxy for desktops and tools"));
 
         // Find existing proxy config
         let existing_proxy = profile.system_actions.iter().find_map(|a| {
@@ -1769,6 +1942,18 @@ impl MainWindow {
         https_proxy.set_sensitive(proxy_mode_idx == 1);
         proxy_group.add(&https_proxy);
 
+        let ftp_proxy = adw::EntryRow::new();
+        ftp_proxy.set_title("FTP Proxy");
+        ftp_proxy.set_text(&existing_proxy.as_ref().and_then(|p| p.ftp_proxy.clone()).unwrap_or_default());
+        ftp_proxy.set_sensitive(proxy_mode_idx == 1);
+        proxy_group.add(&ftp_proxy);
+
+        let socks_proxy = adw::EntryRow::new();
+        socks_proxy.set_title("SOCKS Proxy");
+        socks_proxy.set_text(&existing_proxy.as_ref().and_then(|p| p.socks_proxy.clone()).unwrap_or_default());
+        socks_proxy.set_sensitive(proxy_mode_idx == 1);
+        proxy_group.add(&socks_proxy);
+
         let no_proxy = adw::EntryRow::new();
         no_proxy.set_title("No Proxy For");
         no_proxy.set_text(&existing_proxy.as_ref().map(|p| p.no_proxy.join(",")).unwrap_or_else(|| "localhost,127.0.0.1".to_string()));
@@ -1781,18 +1966,29 @@ impl MainWindow {
         pac_url.set_sensitive(proxy_mode_idx == 2);
         proxy_group.add(&pac_url);
 
+        let selected_backends = existing_proxy.as_ref().map(|p| p.backends.clone()).unwrap_or_else(|| vec![ProxyBackend::Gnome]);
+        let (proxy_backends, proxy_backend_rows) = Self::proxy_backend_rows(&selected_backends);
+        proxy_backends.set_sensitive(proxy_mode_idx > 0);
+        proxy_group.add(&proxy_backends);
+
         let http_proxy_weak = http_proxy.downgrade();
         let https_proxy_weak = https_proxy.downgrade();
+        let ftp_proxy_weak = ftp_proxy.downgrade();
+        let socks_proxy_weak = socks_proxy.downgrade();
         let no_proxy_weak = no_proxy.downgrade();
         let pac_url_weak = pac_url.downgrade();
+        let proxy_backends_weak = proxy_backends.downgrade();
         proxy_mode_row.connect_selected_notify(move |row| {
             let mode = row.selected();
             let is_manual = mode == 1;
             let is_auto = mode == 2;
             if let Some(e) = http_proxy_weak.upgrade() { e.set_sensitive(is_manual); }
             if let Some(e) = https_proxy_weak.upgrade() { e.set_sensitive(is_manual); }
+            if let Some(e) = ftp_proxy_weak.upgrade() { e.set_sensitive(is_manual); }
+            if let Some(e) = socks_proxy_weak.upgrade() { e.set_sensitive(is_manual); }
             if let Some(e) = no_proxy_weak.upgrade() { e.set_sensitive(is_manual); }
             if let Some(e) = pac_url_weak.upgrade() { e.set_sensitive(is_auto); }
+            if let Some(e) = proxy_backends_weak.upgrade() { e.set_sensitive(mode > 0); }
         });
 
         content.append(&proxy_group);
@@ -1820,8 +2016,8 @@ impl MainWindow {
         });
 
         let existing_program = profile.automation_actions.iter().find_map(|a| {
-            if let AutomationAction::RunProgram { program, args, .. } = a {
-                Some((program.clone(), args.join(" ")))
+            if let AutomationAction::RunProgram { program, args, pin_digest, .. } = a {
+                Some((program.clone(), args.join(" "), *pin_digest))
             } else {
                 None
             }
@@ -1853,6 +2049,29 @@ impl MainWindow {
         post_script_path.set_sensitive(post_script_enabled.is_active());
         scripts_group.add(&post_script_path);
 
+        let existing_sandbox = profile.automation_actions.iter().find_map(|a| match a {
+            AutomationAction::PreScript { sandbox, .. } | AutomationAction::PostScript { sandbox, .. } => Some(sandbox.clone()),
+            _ => None,
+        }).unwrap_or_default();
+        let join_paths = |paths: &[PathBuf]| paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(",");
+
+        let script_read_paths = adw::EntryRow::new();
+        script_read_paths.set_title("Script Read-Only Paths");
+        script_read_paths.set_text(&join_paths(&existing_sandbox.read_only_paths));
+        scripts_group.add(&script_read_paths);
+
+        let script_write_paths = adw::EntryRow::new();
+        script_write_paths.set_title("Script Writable Paths");
+        script_write_paths.set_text(&join_paths(&existing_sandbox.writable_paths));
+        scripts_group.add(&script_write_paths);
+
+        let script_network = adw::SwitchRow::builder()
+            .title("Allow Script Network Access")
+            .subtitle("Paths and network access apply in the Landlock sandbox mode")
+            .active(existing_sandbox.allow_network)
+            .build();
+        scripts_group.add(&script_network);
+
         let run_program_enabled = adw::SwitchRow::builder()
             .title("Run Program")
             .subtitle("Launch an application when profile activates")
@@ -1862,16 +2081,24 @@ impl MainWindow {
 
         let run_program_path = adw::EntryRow::new();
         run_program_path.set_title("Program Path");
-        run_program_path.set_text(&existing_program.as_ref().map(|(p, _)| p.clone()).unwrap_or_default());
+        run_program_path.set_text(&existing_program.as_ref().map(|(p, _, _)| p.clone()).unwrap_or_default());
         run_program_path.set_sensitive(run_program_enabled.is_active());
         scripts_group.add(&run_program_path);
 
         let run_program_args = adw::EntryRow::new();
         run_program_args.set_title("Program Arguments");
-        run_program_args.set_text(&existing_program.as_ref().map(|(_, a)| a.clone()).unwrap_or_default());
+        run_program_args.set_text(&existing_program.as_ref().map(|(_, a, _)| a.clone()).unwrap_or_default());
         run_program_args.set_sensitive(run_program_enabled.is_active());
         scripts_group.add(&run_program_args);
 
+        let run_program_pin = adw::SwitchRow::builder()
+            .title("Verify Program")
+            .subtitle("Only run the program while it matches the approved version")
+            .active(existing_program.as_ref().is_some_and(|(_, _, pin)| *pin))
+            .sensitive(run_program_enabled.is_active())
+            .build();
+        scripts_group.add(&run_program_pin);
+
         // Script toggle handlers
         let pre_script_path_weak = pre_script_path.downgrade();
         pre_script_enabled.connect_active_notify(move |row| {
@@ -1885,10 +2112,12 @@ impl MainWindow {
 
         let run_program_path_weak = run_program_path.downgrade();
         let run_program_args_weak = run_program_args.downgrade();
+        let run_program_pin_weak = run_program_pin.downgrade();
         run_program_enabled.connect_active_notify(move |row| {
             let enabled = row.is_active();
             if let Some(e) = run_program_path_weak.upgrade() { e.set_sensitive(enabled); }
             if let Some(e) = run_program_args_weak.upgrade() { e.set_sensitive(enabled); }
+            if let Some(e) = run_program_pin_weak.upgrade() { e.set_sensitive(enabled); }
         });
 
         content.append(&scripts_group);
@@ -1915,20 +2144,31 @@ impl MainWindow {
         // VPN
         let vpn_enabled_weak = vpn_enabled.downgrade();
         let vpn_name_weak = vpn_name.downgrade();
+        // Network safety
+        let confirm_changes_weak = confirm_changes.downgrade();
+        let confirm_timeout_weak = confirm_timeout.downgrade();
+        let probe_host_weak = probe_host.downgrade();
         // Proxy
         let proxy_mode_row_weak = proxy_mode_row.downgrade();
         let http_proxy_weak = http_proxy.downgrade();
         let https_proxy_weak = https_proxy.downgrade();
+        let ftp_proxy_weak = ftp_proxy.downgrade();
+        let socks_proxy_weak = socks_proxy.downgrade();
         let no_proxy_weak = no_proxy.downgrade();
         let pac_url_weak = pac_url.downgrade();
+        let proxy_backend_rows: Vec<_> = proxy_backend_rows.iter().map(|(b, r)| (*b, r.downgrade())).collect();
         // Scripts
         let pre_script_enabled_weak = pre_script_enabled.downgrade();
         let pre_script_path_weak = pre_script_path.downgrade();
         let post_script_enabled_weak = post_script_enabled.downgrade();
         let post_script_path_weak = post_script_path.downgrade();
+        let script_read_paths_weak = script_read_paths.downgrade();
+        let script_write_paths_weak = script_write_paths.downgrade();
+        let script_network_weak = script_network.downgrade();
         let run_program_enabled_weak = run_program_enabled.downgrade();
         let run_program_path_weak = run_program_path.downgrade();
         let run_program_args_weak = run_program_args.downgrade();
+        let run_program_pin_weak = run_program_pin.downgrade();
         // Adapter widgets
         let adapter_widgets_ref = adapter_widgets.clone();
         
@@ -1941,21 +2181,42 @@ impl MainWindow {
             let vpn_on = vpn_enabled_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
             let vpn_name_val = vpn_name_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             
+            // Network safety settings
+            let confirm_on = confirm_changes_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
+            let confirm_secs = confirm_timeout_weak.upgrade().map(|r| r.value() as u32).unwrap_or(CheckpointSettings::DEFAULT_TIMEOUT_SECS);
+            let probe_host_val = probe_host_weak.upgrade().map(|e| e.text().trim().to_string()).unwrap_or_default();
+            
             // Proxy settings
             let proxy_mode = proxy_mode_row_weak.upgrade().map(|r| r.selected()).unwrap_or(0);
             let http_proxy_val = http_proxy_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             let https_proxy_val = https_proxy_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
+            let ftp_proxy_val = ftp_proxy_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
+            let socks_proxy_val = socks_proxy_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             let no_proxy_val = no_proxy_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             let pac_url_val = pac_url_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
+            let proxy_backends_val: Vec<ProxyBackend> = proxy_backend_rows
+                .iter()
+                .filter(|(_, row)| row.upgrade().is_some_and(|r| r.is_active()))
+                .map(|(backend, _)| *backend)
+                .collect();
             
             // Script settings
             let pre_script_on = pre_script_enabled_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
             let pre_script = pre_script_path_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             let post_script_on = post_script_enabled_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
             let post_script = post_script_path_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
+            let path_list = |text: String| -> Vec<PathBuf> {
+                text.split(',').map(str::trim).filter(|s| !s.is_empty()).map(PathBuf::from).collect()
+            };
+            let script_sandbox = crate::models::ScriptSandbox {
+                read_only_paths: script_read_paths_weak.upgrade().map(|e| path_list(e.text().to_string())).unwrap_or_default(),
+                writable_paths: script_write_paths_weak.upgrade().map(|e| path_list(e.text().to_string())).unwrap_or_default(),
+                allow_network: script_network_weak.upgrade().map(|r| r.is_active()).unwrap_or(true),
+            };
             let run_prog_on = run_program_enabled_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
             let run_prog_path = run_program_path_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
             let run_prog_args = run_program_args_weak.upgrade().map(|e| e.text().to_string()).unwrap_or_default();
+            let run_prog_pin = run_program_pin_weak.upgrade().map(|r| r.is_active()).unwrap_or(false);
             
             // Collect adapter configurations
             let adapter_configs: Vec<(String, bool, u32, String, String, String, u32, String, String, Option<String>)> = 
@@ -2001,13 +2262,42 @@ impl MainWindow {
                             Some(crate::models::ProfileGroup::new(&new_group)) 
                         };
                         
+                        profile.checkpoint = confirm_on.then(|| CheckpointSettings {
+                            timeout_secs: confirm_secs,
+                            probe_host: (!probe_host_val.is_empty()).then_some(probe_host_val),
+                        });
+                        profile.metadata.updated_at = chrono::Utc::now();
+                        
+                        // Wi-Fi security settings are not edited here; keep them
+                        // for networks that stay the same.
+                        let previous_wifi: Vec<NetworkAction> = profile
+                            .network_actions
+                            .iter()
+                            .filter(|a| matches!(a, NetworkAction::WifiConnect { .. }))
+                            .cloned()
+                            .collect();
+                        // Hotspots are kept as they are, along with their adapters.
+                        let hotspots: Vec<NetworkAction> = profile
+                            .network_actions
+                            .iter()
+                            .filter(|a| matches!(a, NetworkAction::Hotspot { .. }))
+                            .cloned()
+                            .collect();
+                        let hotspot_ifaces: Vec<String> =
+                            hotspots.iter().filter_map(NetworkAction::created_interface).collect();
+                        
                         // Clear existing actions and rebuild
                         profile.network_actions.clear();
                         profile.system_actions.clear();
-                        profile.automation_actions.clear();
+                        let previous_automation = std::mem::take(&mut profile.automation_actions);
+                        profile.network_actions.extend(hotspots);
                         
                         // === Process each adapter configuration ===
                         for (iface, enabled, ip_method, static_ip, subnet, gateway, dns_method, dns1, dns2, wifi_ssid) in adapter_configs {
+                            if hotspot_ifaces.contains(&iface) {
+                                continue;
+                            }
+                            
                             profile.network_actions.push(NetworkAction::InterfaceEnable(InterfaceState {
                                 interface: iface.clone(),
                                 enabled,
@@ -2063,9 +2353,20 @@ impl MainWindow {
                                 
                                 if let Some(ssid) = wifi_ssid {
                                     if !ssid.is_empty() {
-                                        profile.network_actions.push(NetworkAction::WifiConnect {
-                                            ssid,
-                                            interface: Some(iface.clone()),
+                                        let kept = previous_wifi.iter().find(|a| matches!(
+                                            a,
+                                            NetworkAction::WifiConnect { ssid: s, interface: Some(i), .. }
+                                                if *s == ssid && *i == iface
+                                        ));
+                                        profile.network_actions.push(match kept {
+                                            Some(action) => action.clone(),
+                                            None => NetworkAction::WifiConnect {
+                                                ssid,
+                                                interface: Some(iface.clone()),
+                                                security: None,
+                                                hidden: false,
+                                                bssid: None,
+                                            },
                                         });
                                     }
                                 }
@@ -2097,10 +2398,11 @@ impl MainWindow {
                                 mode,
                                 http_proxy: if proxy_mode == 1 && !http_proxy_val.is_empty() { Some(http_proxy_val) } else { None },
                                 https_proxy: if proxy_mode == 1 && !https_proxy_val.is_empty() { Some(https_proxy_val) } else { None },
-                                ftp_proxy: None,
-                                socks_proxy: None,
+                                ftp_proxy: if proxy_mode == 1 && !ftp_proxy_val.is_empty() { Some(ftp_proxy_val) } else { None },
+                                socks_proxy: if proxy_mode == 1 && !socks_proxy_val.is_empty() { Some(socks_proxy_val) } else { None },
                                 no_proxy: no_proxy_list,
                                 pac_url: if proxy_mode == 2 && !pac_url_val.is_empty() { Some(pac_url_val) } else { None },
+                                backends: proxy_backends_val,
                             }));
                         }
                         
@@ -2113,6 +2415,8 @@ impl MainWindow {
                                 mode: crate::models::ScriptMode::Wait,
                                 working_dir: None,
                                 continue_on_error: false,
+                                sandbox: script_sandbox.clone(),
+                                approved_sha256: None,
                             });
                         }
                         
@@ -2125,6 +2429,8 @@ impl MainWindow {
                                 mode: crate::models::ScriptMode::Wait,
                                 working_dir: None,
                                 continue_on_error: false,
+                                sandbox: script_sandbox.clone(),
+                                approved_sha256: None,
                             });
                         }
                         
@@ -2141,8 +2447,12 @@ impl MainWindow {
                                 env: std::collections::HashMap::new(),
                                 mode: crate::models::ProgramMode::Background,
                                 working_dir: None,
+                                pin_digest: run_prog_pin,
+                                approved_sha256: None,
                             });
                         }
+                        // Unchanged scripts stay approved; their digest is still checked.
+                        profile.carry_over_approvals(&previous_automation);
                     }
                 }
                 
@@ -2153,6 +2463,11 @@ impl MainWindow {
                 }
                 
                 window.show_toast(&format!("Profile '{}' updated", new_name));
+
+                let saved = imp.profiles.borrow().iter().find(|p| p.id().to_string() == profile_id).cloned();
+                if let Some(saved) = saved {
+                    window.save_profile_to_daemon(&saved, false);
+                }
             }
         });
 
@@ -2387,39 +2702,267 @@ impl MainWindow {
             self.show_toast("Profile not found");
             return;
         };
-        
-        // Check if confirmation is required
+
+        // Scripts only run once the user approved exactly these files.
+        let pending = integrity::pending_approvals(&profile);
+        if !pending.is_empty() {
+            self.show_script_consent_dialog(&profile, pending);
+            return;
+        }
+
+        self.confirm_and_apply_profile(&profile);
+    }
+
+    /// Apply a profile, asking for confirmation first if configured.
+    fn confirm_and_apply_profile(&self, profile: &Profile) {
         let confirm_required = self.application()
             .and_downcast_ref::<crate::application::Application>()
             .map(|app| app.config().confirm_profile_switch)
             .unwrap_or(true);
         
         if confirm_required && profile.has_actions() {
-            self.show_apply_confirmation_dialog(&profile);
+            self.show_apply_confirmation_dialog(profile);
         } else {
-            self.do_apply_profile(&profile);
+            self.do_apply_profile(profile);
         }
     }
+
+    /// Show the scripts a profile would run and ask the user to approve them.
+    ///
+    /// Lists each file with its owner, permissions and SHA-256 digest.
+    /// Files that others can modify cannot be approved.
+    fn show_script_consent_dialog(&self, profile: &Profile, pending: Vec<PendingApproval>) {
+        let dialog = adw::AlertDialog::builder()
+            .heading("Allow Scripts to Run?")
+            .body(&format!(
+                "'{}' runs these files with administrator privileges. Approve only files you trust; \
+                 if one changes later, the profile will not run it until it is approved again.",
+                profile.name()
+            ))
+            .build();
+
+        let list_box = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .build();
+        list_box.add_css_class("boxed-list");
+
+        let mut all_approvable = true;
+        for item in &pending {
+            let (title, subtitle, approvable) = match &item.file {
+                Ok(info) => {
+                    let mut subtitle = format!(
+                        "Owner {} · {} · SHA-256 {}",
+                        info.owner,
+                        info.permissions(),
+                        info.sha256
+                    );
+                    if let Some(problem) = &info.problem {
+                        subtitle.push_str(&format!("\nCannot be approved: {}", problem));
+                    }
+                    (info.path.display().to_string(), subtitle, info.problem.is_none())
+                }
+                Err(e) => (item.target.clone(), format!("Cannot be approved: {}", e), false),
+            };
+            all_approvable &= approvable;
+
+            let row = adw::ActionRow::builder()
+                .title(title.as_str())
+                .subtitle(subtitle.as_str())
+                .subtitle_lines(0)
+                .use_markup(false)
+                .build();
+            let icon = if approvable { "utilities-terminal-symbolic" } else { "dialog-warning-symbolic" };
+            row.add_prefix(&gtk::Image::from_icon_name(icon));
+            list_box.append(&row);
+        }
+
+        let scrolled = gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .vscrollbar_policy(gtk::PolicyType::Automatic)
+            .propagate_natural_height(true)
+            .max_content_height(360)
+            .child(&list_box)
+            .build();
+        dialog.set_extra_child(Some(&scrolled));
+
+        dialog.add_response("cancel", "Cancel");
+        dialog.add_response("approve", "Approve and Apply");
+        dialog.set_response_appearance("approve", adw::ResponseAppearance::Destructive);
+        dialog.set_response_enabled("approve", all_approvable);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        let profile_clone = profile.clone();
+        let window_weak = self.downgrade();
+        dialog.connect_response(None, move |_, response| {
+            if response == "approve" {
+                if let Some(window) = window_weak.upgrade() {
+                    window.approve_scripts(&profile_clone, &pending);
+                }
+            }
+        });
+
+        dialog.present(Some(self));
+    }
+
+    /// Record approved script digests in a profile, then apply it.
+    fn approve_scripts(&self, profile: &Profile, pending: &[PendingApproval]) {
+        let mut approved = profile.clone();
+        integrity::approve(&mut approved.automation_actions, pending);
+
+        if let Some(stored) = self.imp().profiles.borrow_mut().iter_mut().find(|p| p.id() == approved.id()) {
+            stored.automation_actions = approved.automation_actions.clone();
+        }
+        self.save_profiles_to_cache();
+
+        for info in pending.iter().filter_map(|item| item.file.as_ref().ok()) {
+            self.append_log(
+                "INFO",
+                &format!("Approved {} for '{}' (SHA-256 {})", info.path.display(), profile.name(), info.sha256),
+            );
+        }
+        // The daemon runs its own copy, which needs the approvals too.
+        self.save_profile_to_daemon(&approved, true);
+    }
     
+    /// Store a saved profile in the daemon, if it is running, and then
+    /// apply it if `apply` is set.
+    ///
+    /// Saving is the only time the daemon's copy is replaced, as that needs
+    /// the manage-profiles authorization; activation uses the stored copy.
+    fn save_profile_to_daemon(&self, profile: &Profile, apply: bool) {
+        // The daemon encrypts secrets with its own key, so it gets them in plaintext.
+        let mut daemon_profile = profile.clone();
+        if let Some(store) = self.imp().data_store.borrow().as_ref() {
+            if let Err(e) = store.reveal_secrets(&mut daemon_profile) {
+                tracing::error!("Cannot decrypt the secrets of '{}': {}", profile.name(), e);
+                self.show_toast(&format!("Cannot decrypt the secrets of '{}'", profile.name()));
+                return;
+            }
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        crate::application::tokio_runtime().spawn(async move {
+            let mut client = DaemonClient::new();
+            let saved = match client.connect().await {
+                Ok(()) => client.save_profile(&daemon_profile).await,
+                Err(e) => Err(e),
+            };
+            let _ = tx.send(saved);
+        });
+
+        let window_weak = self.downgrade();
+        let profile = profile.clone();
+        glib::spawn_future_local(async move {
+            let Ok(saved) = rx.await else { return };
+            let Some(window) = window_weak.upgrade() else { return };
+
+            match saved {
+                Ok(()) => tracing::info!("Stored profile '{}' in the daemon", profile.name()),
+                // Without the daemon, profiles are applied locally.
+                Err(e) if e.is_daemon_not_running() => {}
+                Err(e) => {
+                    tracing::error!("Failed to store '{}' in the daemon: {}", profile.name(), e);
+                    window.append_log("ERROR", &format!("Profile '{}' not stored in the daemon: {}", profile.name(), e));
+                    let message = if e.is_authorization_error() {
+                        format!("Not authorized to save '{}' for the system", profile.name())
+                    } else {
+                        format!("Failed to save '{}' for the system: {}", profile.name(), e)
+                    };
+                    window.show_toast(&message);
+                    return;
+                }
+            }
+            if apply {
+                window.confirm_and_apply_profile(&profile);
+            }
+        });
+    }
+
     /// Show confirmation dialog before applying a profile.
+    ///
+    /// The profile is planned first (a dry run reading the current system
+    /// state) so the dialog can list what would actually change.
     fn show_apply_confirmation_dialog(&self, profile: &Profile) {
+        let sandbox_mode = self.application()
+            .and_downcast_ref::<crate::application::Application>()
+            .map(|app| app.config().sandbox_mode)
+            .unwrap_or_default();
+
+        let plan_profile = profile.clone();
+        let planning = crate::application::tokio_runtime()
+            .spawn_blocking(move || SystemExecutor::new(sandbox_mode).plan(&plan_profile));
+
+        let window_weak = self.downgrade();
+        let profile = profile.clone();
+        glib::spawn_future_local(async move {
+            let Ok(plan) = planning.await else { return };
+            let Some(window) = window_weak.upgrade() else { return };
+            window.present_apply_plan(&profile, &plan);
+        });
+    }
+
+    /// Present a profile's plan and apply it on confirmation.
+    fn present_apply_plan(&self, profile: &Profile, plan: &ExecutionPlan) {
         let dialog = adw::AlertDialog::builder()
             .heading(&format!("Apply '{}'?", profile.name()))
             .body(&format!(
-                "This will apply {} action(s) to your system:\n• {} network action(s)\n• {} system action(s)\n• {} automation action(s)",
-                profile.action_count(),
-                profile.network_actions.len(),
-                profile.system_actions.len(),
-                profile.automation_actions.len()
+                "{} change(s), {} already up to date, {} unsupported.",
+                plan.count(ChangeKind::Change),
+                plan.count(ChangeKind::NoOp),
+                plan.count(ChangeKind::Unsupported)
             ))
             .build();
-        
+
+        let list_box = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .build();
+        list_box.add_css_class("boxed-list");
+
+        for change in &plan.changes {
+            let subtitle = match change.kind {
+                ChangeKind::Change => format!(
+                    "{} → {}",
+                    change.current.as_deref().unwrap_or("unknown"),
+                    change.desired
+                ),
+                ChangeKind::NoOp => change.desired.clone(),
+                ChangeKind::Unsupported => change.note.clone().unwrap_or_default(),
+            };
+            let row = adw::ActionRow::builder()
+                .title(change.action.name())
+                .subtitle(subtitle.as_str())
+                .use_markup(false)
+                .build();
+            row.add_prefix(&gtk::Image::from_icon_name(change.action.icon_name()));
+
+            let kind_label = gtk::Label::new(Some(change.kind.label()));
+            kind_label.add_css_class("caption");
+            match change.kind {
+                ChangeKind::Change => kind_label.add_css_class("accent"),
+                ChangeKind::NoOp => kind_label.add_css_class("dim-label"),
+                ChangeKind::Unsupported => kind_label.add_css_class("warning"),
+            }
+            row.add_suffix(&kind_label);
+
+            list_box.append(&row);
+        }
+
+        let scrolled = gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .vscrollbar_policy(gtk::PolicyType::Automatic)
+            .propagate_natural_height(true)
+            .max_content_height(360)
+            .child(&list_box)
+            .build();
+        dialog.set_extra_child(Some(&scrolled));
+
         dialog.add_response("cancel", "Cancel");
         dialog.add_response("apply", "Apply");
         dialog.set_response_appearance("apply", adw::ResponseAppearance::Suggested);
         dialog.set_default_response(Some("apply"));
         dialog.set_close_response("cancel");
-        
+
         let profile_clone = profile.clone();
         let window_weak = self.downgrade();
         dialog.connect_response(None, move |_, response| {
@@ -2429,56 +2972,146 @@ impl MainWindow {
                 }
             }
         });
-        
+
         dialog.present(Some(self));
     }
-    
-    /// Actually apply a profile's actions.
+
+    /// Apply a profile through the daemon, falling back to local execution.
+    ///
+    /// The daemon is the only path covered by the Polkit policy and the
+    /// system audit trail. Actions are run in this process only when the
+    /// daemon is not installed or not running, and the user is told so.
     fn do_apply_profile(&self, profile: &Profile) {
+        // Secrets stay encrypted: the daemon only needs them to register the
+        // profile, and the local executor decrypts each right before its action.
+        let secrets_key = self.imp().data_store.borrow().as_ref().map(|store| store.secrets_key_file().to_path_buf());
+
+        // The daemon refuses scripts unless it sandboxes them as configured here.
+        let (sandbox_mode, systemd_sandbox) = self.application()
+            .and_downcast_ref::<crate::application::Application>()
+            .map(|app| {
+                let config = app.config();
+                (config.sandbox_mode, config.systemd_sandbox.clone())
+            })
+            .unwrap_or_default();
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let daemon_profile = profile.clone();
+        let daemon_secrets_key = secrets_key.clone();
+        crate::application::tokio_runtime().spawn(async move {
+            let _ = tx.send(activate_via_daemon(&daemon_profile, daemon_secrets_key.as_deref(), sandbox_mode).await);
+        });
+
         let imp = self.imp();
-        let profile_id = profile.id().to_string();
-        let profile_name = profile.name().to_string();
-        
-        // Apply network actions using nmcli
-        let mut success_count = 0;
-        let mut error_messages = Vec::new();
-        
-        for action in &profile.network_actions {
-            match self.apply_network_action(action) {
-                Ok(_) => success_count += 1,
-                Err(e) => error_messages.push(e),
-            }
+        let previous = imp.profiles_page.borrow().as_ref()
+            .and_then(|page| page.active_profile_id())
+            .filter(|id| *id != profile.id().to_string())
+            .and_then(|id| imp.profiles.borrow().iter().find(|p| p.id().to_string() == id).cloned());
+        let mut executor = SystemExecutor::new(sandbox_mode).with_systemd_sandbox(systemd_sandbox);
+        if let Some(secrets_key) = secrets_key {
+            executor = executor.with_secrets_key(secrets_key);
         }
-        
-        // Apply system actions 
-        for action in &profile.system_actions {
-            match self.apply_system_action(action) {
-                Ok(_) => success_count += 1,
-                Err(e) => error_messages.push(e),
+
+        let window_weak = self.downgrade();
+        let profile = profile.clone();
+        glib::spawn_future_local(async move {
+            let Ok(outcome) = rx.await else { return };
+            let Some(window) = window_weak.upgrade() else { return };
+
+            match outcome {
+                Ok(mut result) => {
+                    // The daemon cannot reach this session; its settings are applied here.
+                    let session = profile.scoped(ActionScope::Session);
+                    if result.is_success() && session.has_actions() {
+                        let previous = previous.map(|p| p.scoped(ActionScope::Session));
+                        let local = crate::application::tokio_runtime()
+                            .spawn_blocking(move || apply_locally(&executor, &session, previous.as_ref()));
+                        match local.await {
+                            Ok(session_result) => result.merge(session_result),
+                            Err(e) => tracing::error!("Applying the session actions of '{}' failed: {}", profile.name(), e),
+                        }
+                    }
+                    window.finish_apply_profile(&result, true);
+                }
+                Err(e) if e.is_daemon_not_running() => {
+                    tracing::warn!("Daemon unavailable ({}); applying '{}' locally", e, profile.name());
+                    window.append_log(
+                        "WARN",
+                        &format!("Daemon unavailable, applying profile '{}' locally without Polkit authorization", profile.name()),
+                    );
+
+                    let local = crate::application::tokio_runtime()
+                        .spawn_blocking(move || apply_locally(&executor, &profile, previous.as_ref()));
+                    if let Ok(result) = local.await {
+                        window.finish_apply_profile(&result, false);
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Daemon refused to apply '{}': {}", profile.name(), e);
+                    window.append_log("ERROR", &format!("Profile '{}' not applied: {}", profile.name(), e));
+                    let message = if e.is_authorization_error() {
+                        format!("Not authorized to apply '{}'", profile.name())
+                    } else {
+                        format!("Failed to apply '{}': {}", profile.name(), e)
+                    };
+                    window.show_toast(&message);
+                }
             }
-        }
-        
-        // Run automation actions
-        for action in &profile.automation_actions {
-            match self.run_automation_action(action) {
-                Ok(_) => success_count += 1,
-                Err(e) => error_messages.push(e),
+        });
+    }
+
+    /// Update the UI and log store with the outcome of a profile activation.
+    fn finish_apply_profile(&self, result: &ExecutionResult, via_daemon: bool) {
+        let imp = self.imp();
+        let profile_name = &result.profile_name;
+
+        self.append_log(
+            if result.is_success() { "INFO" } else { "WARN" },
+            &format!(
+                "Profile '{}' applied {}{}: {} ({} ms)",
+                profile_name,
+                if via_daemon { "by daemon" } else { "locally" },
+                result.initiated_by.as_deref().map(|u| format!(" for {}", u)).unwrap_or_default(),
+                result.message,
+                result.total_duration_ms
+            ),
+        );
+        for action in &result.actions {
+            if let Some(output) = &action.output {
+                self.append_log("INFO", &format!("[{}] {} output:\n{}", profile_name, action.action.name(), output));
             }
         }
-        
-        // Update active profile in profiles page
-        if let Some(profiles_page) = imp.profiles_page.borrow().as_ref() {
-            profiles_page.set_active_profile(Some(&profile_id));
-            let profiles = imp.profiles.borrow().clone();
-            profiles_page.update_profiles(profiles);
+        for action in result.actions.iter().filter(|a| a.status.is_error()) {
+            let detail = action.error_detail.as_deref().unwrap_or(&action.message);
+            tracing::warn!("Profile apply error: {}: {}", action.action.name(), detail);
+            self.append_log("ERROR", &format!("{}: {}", action.action.name(), detail));
         }
-        
-        // Update the dashboard with active profile info
+        for step in &result.rollback {
+            let level = if step.status.is_error() { "ERROR" } else { "INFO" };
+            let detail = step.error_detail.as_deref().unwrap_or(&step.message);
+            self.append_log(level, &format!("Rollback {}: {}", step.action.name(), detail));
+        }
+
+        if result.is_success() {
+            // Update active profile in profiles page
+            if let Some(profiles_page) = imp.profiles_page.borrow().as_ref() {
+                profiles_page.set_active_profile(Some(&result.profile_id));
+                let profiles = imp.profiles.borrow().clone();
+                profiles_page.update_profiles(profiles);
+            }
+
+            // Update the dashboard with active profile info
+            if let Some(dashboard_page) = imp.dashboard_page.borrow().as_ref() {
+                let applied_time = result.completed_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string();
+                dashboard_page.update_active_profile(Some(profile_name), Some(&applied_time));
+            }
+        }
+
+        // Immediate refresh to update UI
         if let Some(dashboard_page) = imp.dashboard_page.borrow().as_ref() {
-            let now = chrono::Local::now();
-            let applied_time = now.format("%Y-%m-%d %H:%M:%S").to_string();
-            dashboard_page.update_active_profile(Some(&profile_name), Some(&applied_time));
-            // Immediate refresh to update UI
             dashboard_page.update_network_info();
         }
         
@@ -2505,388 +3138,156 @@ impl MainWindow {
         });
         
         // Show result
-        if error_messages.is_empty() {
-            if success_count > 0 {
-                self.show_toast(&format!("Profile '{}' applied ({} actions)", profile_name, success_count));
-            } else {
-                self.show_toast(&format!("Profile '{}' activated", profile_name));
+        let error_count = result.error_count();
+        if result.checkpoint.is_some() {
+            // Errors of actions that may continue on error still need showing;
+            // the countdown dialog goes on top.
+            if error_count > 0 {
+                self.show_toast(&format!("Profile '{}' applied with {} error(s)", profile_name, error_count));
+                self.show_apply_errors_dialog(result);
             }
+            self.show_keep_changes_dialog(result, via_daemon);
+        } else if result.is_rolled_back() {
+            self.show_toast(&format!("Profile '{}' failed; changes were rolled back", profile_name));
+            self.show_apply_errors_dialog(result);
+        } else if error_count > 0 {
+            self.show_toast(&format!("Profile '{}' applied with {} error(s)", profile_name, error_count));
+            self.show_apply_errors_dialog(result);
+        } else if !via_daemon {
+            self.show_toast(&format!("Profile '{}' applied locally (daemon not running)", profile_name));
+        } else if result.actions.is_empty() {
+            self.show_toast(&format!("Profile '{}' activated", profile_name));
         } else {
-            self.show_toast(&format!("Profile '{}' applied with {} error(s)", profile_name, error_messages.len()));
-            for msg in error_messages {
-                tracing::warn!("Profile apply error: {}", msg);
-            }
+            self.show_toast(&format!("Profile '{}' applied ({} actions)", profile_name, result.success_count()));
         }
     }
-    
-    /// Apply a network action using nmcli.
-    ///
-    /// # Privilege model
+
+    /// Ask whether to keep network changes protected by a checkpoint.
     ///
-    /// These calls invoke `nmcli` directly from the GUI process. NetworkManager
-    /// itself enforces Polkit authorisation, so most operations will prompt for
-    /// credentials when required. However, this means the Polkit policy shipped
-    /// with this application (com.chrisdaggas.network-manager.policy) only
-    /// governs the D-Bus daemon — the GUI is **not** mediated by our own D-Bus
-    /// service. A future release should route privileged operations through the
-    /// daemon so that a single Polkit policy covers both paths.
-    fn apply_network_action(&self, action: &crate::models::NetworkAction) -> std::result::Result<(), String> {
-        use crate::models::NetworkAction;
-        use std::process::Command;
-        
-        match action {
-            NetworkAction::Ipv4Config { interface, method, addresses, gateway } => {
-                let iface = interface.as_deref().unwrap_or("eth0");
-                
-                // Find connection name for this interface
-                let conn_name = match Self::get_connection_for_interface(iface) {
-                    Some(name) => name,
-                    None => {
-                        // Skip virtual interfaces that aren't managed by NetworkManager
-                        tracing::debug!("Skipping unmanaged interface: {}", iface);
-                        return Ok(());
-                    }
-                };
-                
-                match method {
-                    crate::models::Ipv4Method::Auto => {
-                        // Set to DHCP
-                        let output = Command::new("nmcli")
-                            .args(["connection", "modify", &conn_name, "ipv4.method", "auto"])
-                            .output()
-                            .map_err(|e| format!("Failed to run nmcli: {}", e))?;
-                        
-                        if !output.status.success() {
-                            // Try using device instead
-                            let _ = Command::new("nmcli")
-                                .args(["device", "reapply", iface])
-                                .output();
-                        }
-                    }
-                    crate::models::Ipv4Method::Manual => {
-                        // Set static IP
-                        if let Some(addr) = addresses.first() {
-                            let ip_str = format!("{}/{}", addr.address, addr.prefix);
-                            let _ = Command::new("nmcli")
-                                .args(["connection", "modify", &conn_name, "ipv4.method", "manual", "ipv4.addresses", &ip_str])
-                                .output();
-                        }
-                        
-                        if let Some(gw) = gateway {
-                            let _ = Command::new("nmcli")
-                                .args(["connection", "modify", &conn_name, "ipv4.gateway", &gw.to_string()])
-                                .output();
-                        }
-                    }
-                    _ => {}
-                }
-                
-                // Reapply the connection (only if we found a valid connection)
-                if Self::get_connection_for_interface(iface).is_some() {
-                    let _ = Command::new("nmcli")
-                        .args(["connection", "up", &conn_name])
-                        .output();
-                }
-                    
-                Ok(())
-            }
-            
-            NetworkAction::DnsServers { interface, servers } => {
-                let iface = interface.as_deref().unwrap_or("eth0");
-                
-                // Only configure DNS if there's an actual NetworkManager connection
-                if let Some(conn_name) = Self::get_connection_for_interface(iface) {
-                    let dns_str: Vec<String> = servers.iter().map(|s| s.to_string()).collect();
-                    
-                    let _ = Command::new("nmcli")
-                        .args(["connection", "modify", &conn_name, "ipv4.dns", &dns_str.join(" ")])
-                        .output();
-                }
-                    
-                Ok(())
-            }
-            
-            NetworkAction::WifiConnect { ssid, interface: _ } => {
-                let output = Command::new("nmcli")
-                    .args(["connection", "up", ssid])
-                    .output()
-                    .map_err(|e| format!("Failed to connect to WiFi: {}", e))?;
-                
-                if !output.status.success() {
-                    return Err(format!("WiFi connection failed: {}", String::from_utf8_lossy(&output.stderr)));
-                }
-                Ok(())
-            }
-            
-            NetworkAction::VpnConnect { connection_name } => {
-                let output = Command::new("nmcli")
-                    .args(["connection", "up", connection_name])
-                    .output()
-                    .map_err(|e| format!("Failed to connect VPN: {}", e))?;
-                
-                if !output.status.success() {
-                    return Err(format!("VPN connection failed: {}", String::from_utf8_lossy(&output.stderr)));
-                }
-                Ok(())
-            }
-            
-            NetworkAction::InterfaceEnable(state) => {
-                let action = if state.enabled { "connect" } else { "disconnect" };
-                let _ = Command::new("nmcli")
-                    .args(["device", action, &state.interface])
-                    .output();
-                Ok(())
-            }
-            
-            NetworkAction::VpnDisconnect { connection_name } => {
-                let output = Command::new("nmcli")
-                    .args(["connection", "down", connection_name])
-                    .output()
-                    .map_err(|e| format!("Failed to disconnect VPN: {}", e))?;
-                
-                if !output.status.success() {
-                    return Err(format!("VPN disconnect failed: {}", String::from_utf8_lossy(&output.stderr)));
-                }
-                Ok(())
-            }
-            
-            NetworkAction::SetMtu { interface, mtu } => {
-                if let Some(conn_name) = Self::get_connection_for_interface(interface) {
-                    let _ = Command::new("nmcli")
-                        .args(["connection", "modify", &conn_name, "802-3-ethernet.mtu", &mtu.to_string()])
-                        .output();
-                    let _ = Command::new("nmcli")
-                        .args(["connection", "up", &conn_name])
-                        .output();
-                }
-                Ok(())
-            }
-            
-            NetworkAction::SetMacAddress { interface, mac_address } => {
-                if let Some(conn_name) = Self::get_connection_for_interface(interface) {
-                    let _ = Command::new("nmcli")
-                        .args(["connection", "modify", &conn_name, "802-3-ethernet.cloned-mac-address", mac_address])
-                        .output();
-                    let _ = Command::new("nmcli")
-                        .args(["connection", "up", &conn_name])
-                        .output();
-                }
-                Ok(())
-            }
-            
-            NetworkAction::DnsSearchDomains { interface, domains } => {
-                let iface = interface.as_deref().unwrap_or("eth0");
-                if let Some(conn_name) = Self::get_connection_for_interface(iface) {
-                    let _ = Command::new("nmcli")
-                        .args(["connection", "modify", &conn_name, "ipv4.dns-search", &domains.join(",")])
-                        .output();
-                }
-                Ok(())
+    /// NetworkManager reverts the changes by itself when the countdown
+    /// ends, so a lost session or an unanswered dialog is safe.
+    fn show_keep_changes_dialog(&self, result: &ExecutionResult, via_daemon: bool) {
+        let Some(checkpoint) = result.checkpoint.clone() else { return };
+        let profile_name = result.profile_name.clone();
+
+        let expires_at = checkpoint.expires_at;
+        let remaining = move || (expires_at - chrono::Utc::now()).num_seconds().max(0);
+        let body = |secs: i64| {
+            format!("Network settings will be reverted in {} seconds unless you keep them.", secs)
+        };
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(&format!("Keep Network Settings of '{}'?", profile_name))
+            .body(&body(remaining()))
+            .build();
+        dialog.add_response("revert", "Revert");
+        dialog.add_response("keep", "Keep Changes");
+        dialog.set_response_appearance("keep", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("revert"));
+        dialog.set_close_response("revert");
+
+        // Once NetworkManager has reverted, answering no longer does anything.
+        let expired = Rc::new(Cell::new(false));
+
+        let dialog_weak = dialog.downgrade();
+        let expired_tick = expired.clone();
+        glib::timeout_add_seconds_local(1, move || {
+            let Some(dialog) = dialog_weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            let secs = remaining();
+            if secs == 0 {
+                expired_tick.set(true);
+                dialog.close();
+                return glib::ControlFlow::Break;
             }
-            
-            // These require more complex handling or are less common
-            NetworkAction::Ipv6Config { .. } | 
-            NetworkAction::StaticRoutes { .. } | 
-            NetworkAction::VlanConfig { .. } => {
-                tracing::debug!("Action {:?} not yet implemented", action);
-                Ok(())
+            dialog.set_body(&body(secs));
+            glib::ControlFlow::Continue
+        });
+
+        let window_weak = self.downgrade();
+        dialog.connect_response(None, move |_, response| {
+            let Some(window) = window_weak.upgrade() else { return };
+            if expired.get() {
+                window.append_log("WARN", &format!("Network settings of '{}' were not confirmed and have been reverted", profile_name));
+                window.show_toast("Network settings reverted");
+                return;
             }
-        }
-    }
-    
-    /// Apply a system action
-    fn apply_system_action(&self, action: &crate::models::SystemAction) -> std::result::Result<(), String> {
-        use crate::models::SystemAction;
-        use std::process::Command;
-        
-        match action {
-            SystemAction::ProxyConfig(config) => {
-                // Verify the gsettings schema exists (may not on non-GNOME desktops)
-                let schema_exists = Command::new("gsettings")
-                    .args(["list-keys", "org.gnome.system.proxy"])
-                    .output()
-                    .map(|o| o.status.success())
-                    .unwrap_or(false);
-                if !schema_exists {
-                    return Err("Proxy settings require GNOME — org.gnome.system.proxy schema not found.".to_string());
-                }
 
-                // Set GNOME proxy settings via gsettings
-                match config.mode {
-                    crate::models::ProxyMode::None => {
-                        let _ = Command::new("gsettings")
-                            .args(["set", "org.gnome.system.proxy", "mode", "none"])
-                            .output();
+            let keep = response == "keep";
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let path = checkpoint.path.clone();
+            crate::application::tokio_runtime().spawn(async move {
+                let _ = tx.send(finish_checkpoint(path, keep, via_daemon).await);
+            });
+
+            let profile_name = profile_name.clone();
+            glib::spawn_future_local(async move {
+                let Ok(outcome) = rx.await else { return };
+                match outcome {
+                    Ok(()) if keep => {
+                        window.append_log("INFO", &format!("Network settings of '{}' confirmed", profile_name));
+                        window.show_toast("Network settings kept");
                     }
-                    crate::models::ProxyMode::Manual => {
-                        let _ = Command::new("gsettings")
-                            .args(["set", "org.gnome.system.proxy", "mode", "manual"])
-                            .output();
-                        
-                        if let Some(http) = &config.http_proxy {
-                            // Parse http://host:port
-                            if let Some(stripped) = http.strip_prefix("http://") {
-                                let parts: Vec<&str> = stripped.split(':').collect();
-                                if parts.len() >= 2 {
-                                    let _ = Command::new("gsettings")
-                                        .args(["set", "org.gnome.system.proxy.http", "host", parts[0]])
-                                        .output();
-                                    let _ = Command::new("gsettings")
-                                        .args(["set", "org.gnome.system.proxy.http", "port", parts[1]])
-                                        .output();
-                                }
-                            }
-                        }
+                    Ok(()) => {
+                        window.append_log("INFO", &format!("Network settings of '{}' reverted", profile_name));
+                        window.show_toast("Network settings reverted");
                     }
-                    crate::models::ProxyMode::Auto => {
-                        let _ = Command::new("gsettings")
-                            .args(["set", "org.gnome.system.proxy", "mode", "auto"])
-                            .output();
-                        
-                        if let Some(pac) = &config.pac_url {
-                            let _ = Command::new("gsettings")
-                                .args(["set", "org.gnome.system.proxy", "autoconfig-url", pac])
-                                .output();
-                        }
+                    Err(e) => {
+                        tracing::error!("Failed to settle checkpoint: {}", e);
+                        window.append_log("ERROR", &format!("Failed to settle network checkpoint: {}", e));
+                        window.show_toast(&format!("Failed to {} network settings: {}", if keep { "keep" } else { "revert" }, e));
                     }
                 }
-                Ok(())
-            }
-            
-            SystemAction::SetHostname { hostname, pretty_hostname } => {
-                // Set static hostname via hostnamectl
-                let output = Command::new("hostnamectl")
-                    .args(["set-hostname", hostname])
-                    .output()
-                    .map_err(|e| format!("Failed to set hostname: {}", e))?;
-                
-                if !output.status.success() {
-                    return Err(format!("Hostname change failed: {}", String::from_utf8_lossy(&output.stderr)));
-                }
-                
-                // Set pretty hostname if provided
-                if let Some(pretty) = pretty_hostname {
-                    let _ = Command::new("hostnamectl")
-                        .args(["set-hostname", "--pretty", pretty])
-                        .output();
-                }
-                Ok(())
-            }
-            
-            SystemAction::SetTimezone { timezone } => {
-                let output = Command::new("timedatectl")
-                    .args(["set-timezone", timezone])
-                    .output()
-                    .map_err(|e| format!("Failed to set timezone: {}", e))?;
-                
-                if !output.status.success() {
-                    return Err(format!("Timezone change failed: {}", String::from_utf8_lossy(&output.stderr)));
-                }
-                Ok(())
-            }
-            
-            SystemAction::DefaultPrinter { printer_name } => {
-                let output = Command::new("lpoptions")
-                    .args(["-d", printer_name])
-                    .output()
-                    .map_err(|e| format!("Failed to set default printer: {}", e))?;
-                
-                if !output.status.success() {
-                    return Err(format!("Printer change failed: {}", String::from_utf8_lossy(&output.stderr)));
-                }
-                Ok(())
-            }
-            
-            SystemAction::EnvironmentVariables { variables } => {
-                // Export variables to user's environment file
-                let _env_file = dirs::home_dir()
-                    .map(|h| h.join(".profile"))
-                    .unwrap_or_else(|| std::path::PathBuf::from("/tmp/.profile"));
-                
-                for (key, value) in variables {
-                    let line = format!("export {}=\"{}\"", key, value);
-                    tracing::info!("Setting environment: {}", line);
-                    // Note: Modifying .profile requires shell reload to take effect
+                if let Some(dashboard_page) = window.imp().dashboard_page.borrow().as_ref() {
+                    dashboard_page.update_network_info();
                 }
-                Ok(())
-            }
-            
-            // These require root privileges and are more complex
-            SystemAction::HostsEntries { .. } | 
-            SystemAction::FirewallConfig(_) => {
-                tracing::debug!("Action {:?} requires privilege escalation", action);
-                Ok(())
-            }
-        }
+            });
+        });
+
+        dialog.present(Some(self));
     }
-    
-    /// Run an automation action
-    fn run_automation_action(&self, action: &crate::models::AutomationAction) -> std::result::Result<(), String> {
-        use crate::models::AutomationAction;
-        use crate::services::SandboxRunner;
-        use std::process::Command;
-        
-        // Get sandbox mode from config
-        let sandbox_mode = self.application()
-            .and_downcast_ref::<crate::application::Application>()
-            .map(|app| app.config().sandbox_mode)
-            .unwrap_or_default();
-        
-        let sandbox = SandboxRunner::new(sandbox_mode);
-        
-        match action {
-            AutomationAction::PreScript { path, args, working_dir, .. } | 
-            AutomationAction::PostScript { path, args, working_dir, .. } => {
-                let path_str = path.to_string_lossy().to_string();
-                let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-                
-                // Use sandbox runner if sandboxing is enabled
-                if sandbox_mode != crate::models::SandboxMode::None {
-                    match sandbox.execute(&path_str, &args_refs) {
-                        Ok(output) => {
-                            if !output.status.success() {
-                                return Err(format!("Script failed: {}", String::from_utf8_lossy(&output.stderr)));
-                            }
-                        }
-                        Err(e) => return Err(format!("Sandbox execution failed: {:?}", e)),
-                    }
-                } else {
-                    // Direct execution without sandboxing
-                    let mut cmd = Command::new(path);
-                    cmd.args(args);
-                    if let Some(wd) = working_dir {
-                        cmd.current_dir(wd);
-                    }
-                    
-                    let output = cmd.output()
-                        .map_err(|e| format!("Failed to run script: {}", e))?;
-                    
-                    if !output.status.success() {
-                        return Err(format!("Script failed: {}", String::from_utf8_lossy(&output.stderr)));
-                    }
-                }
-                Ok(())
-            }
-            
-            AutomationAction::RunProgram { program, args, working_dir, .. } => {
-                // NOTE: RunProgram intentionally spawns without sandboxing.
-                // The user explicitly configures the program path; sandboxing
-                // would break legitimate program launches (e.g. GUI apps).
-                let mut cmd = Command::new(program);
-                cmd.args(args);
-                if let Some(wd) = working_dir {
-                    cmd.current_dir(wd);
-                }
-                
-                cmd.spawn()
-                    .map_err(|e| format!("Failed to launch program: {}", e))?;
-                Ok(())
-            }
-            
-            _ => Ok(())
+
+    /// List the failed actions of an activation and any rollback steps.
+    fn show_apply_errors_dialog(&self, result: &ExecutionResult) {
+        let mut body = result.actions
+            .iter()
+            .filter(|a| a.status.is_error())
+            .map(|a| format!("• {}: {}", a.action.name(), a.error_detail.as_deref().unwrap_or(&a.message)))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if result.is_rolled_back() {
+            body.push_str("\n\nRolled back:\n");
+            body.push_str(&result.rollback
+                .iter()
+                .map(|r| format!("• {}: {}", r.action.name(), r.error_detail.as_deref().unwrap_or(&r.message)))
+                .collect::<Vec<_>>()
+                .join("\n"));
         }
+
+        let heading = if result.is_rolled_back() {
+            format!("'{}' Was Rolled Back", result.profile_name)
+        } else {
+            format!("'{}' Applied With Errors", result.profile_name)
+        };
+        let dialog = adw::AlertDialog::builder()
+            .heading(&heading)
+            .body(&body)
+            .build();
+        dialog.add_response("close", "Close");
+        dialog.set_default_response(Some("close"));
+        dialog.set_close_response("close");
+        dialog.present(Some(self));
     }
 
+    /// Append an entry to the persistent log store.
+    fn append_log(&self, level: &str, message: &str) {
+        if let Some(store) = self.imp().data_store.borrow().as_ref() {
+            store.append_log(level, message);
+        }
+    }
+    
     fn create_main_menu_popover(&self) -> gtk::Popover {
         let popover = gtk::Popover::new();
         popover.add_css_class("menu");
@@ -3798,6 +4199,8 @@ impl MainWindow {
                             p.metadata.created_at = chrono::Utc::now();
                             p.metadata.updated_at = chrono::Utc::now();
                             p.status = crate::models::profile::ProfileStatus::Inactive;
+                            // Scripts from elsewhere must be reviewed before they run.
+                            p.revoke_approvals();
                             p
                         }).collect();
                         
@@ -3827,3 +4230,63 @@ impl MainWindow {
         }
     }
 }
+
+/// Keep or revert the network changes of a pending checkpoint.
+async fn finish_checkpoint(checkpoint: String, keep: bool, via_daemon: bool) -> crate::models::Result<()> {
+    if via_daemon {
+        let mut client = DaemonClient::new();
+        client.connect().await?;
+        return client.finish_checkpoint(&checkpoint, keep).await;
+    }
+    tokio::task::spawn_blocking(move || {
+        let executor = SystemExecutor::default();
+        if keep {
+            executor.confirm_checkpoint(&checkpoint)
+        } else {
+            executor.rollback_checkpoint(&checkpoint)
+        }
+    })
+    .await
+    .map_err(|e| crate::models::Error::Internal(e.to_string()))?
+}
+
+/// Apply a profile in this process and release what `previous` created.
+fn apply_locally(executor: &SystemExecutor, profile: &Profile, previous: Option<&Profile>) -> ExecutionResult {
+    let result = executor.execute(profile, previous);
+    // Switching away from a profile removes what only it created.
+    if let (true, Some(previous)) = (result.is_success(), previous) {
+        for released in executor.deactivate(previous, Some(profile)) {
+            tracing::info!("Deactivating '{}': {}", previous.name(), released.message);
+        }
+    }
+    result
+}
+
+/// Activate a profile through the daemon, registering it first if needed.
+///
+/// Only registering decrypts the profile's secrets, with `secrets_key`: the
+/// daemon encrypts them with its own key, so it gets them in plaintext.
+async fn activate_via_daemon(
+    profile: &Profile,
+    secrets_key: Option<&std::path::Path>,
+    sandbox_mode: crate::models::SandboxMode,
+) -> crate::models::Result<ExecutionResult> {
+    let mut client = DaemonClient::new();
+    client.connect().await?;
+    if !client.ping().await? {
+        return Err(crate::models::Error::DaemonNotRunning);
+    }
+    // The daemon activates its own copy; it only gets this one if it has none.
+    let id = profile.id().to_string();
+    match client.activate_profile(&id, sandbox_mode).await {
+        Err(crate::models::Error::ProfileNotFound(_)) => {
+            let mut daemon_profile = profile.clone();
+            if let Some(secrets_key) = secrets_key {
+                storage::reveal_profile_secrets(secrets_key, &mut daemon_profile)?;
+            }
+            client.save_profile(&daemon_profile).await?;
+            client.activate_profile(&id, sandbox_mode).await
+        }
+        result => result,
+    }
+}
@@ -13,6 +13,8 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::fs;
 
+use crate::nm::NmClient;
+
 mod imp {
     use super::*;
     use once_cell::sync::OnceCell;
@@ -32,6 +34,7 @@ mod imp {
         // Daemon status
         pub daemon_status_icon: OnceCell<gtk::Image>,
         pub daemon_status_label: OnceCell<gtk::Label>,
+        pub daemon_status_desc: OnceCell<gtk::Label>,
         pub daemon_restart_button: OnceCell<gtk::Button>,
     }
 
@@ -335,6 +338,7 @@ impl DashboardPage {
         status_desc.add_css_class("caption");
         status_desc.set_halign(gtk::Align::Start);
         status_box.append(&status_desc);
+        let _ = imp.daemon_status_desc.set(status_desc);
         
         status_row.append(&status_box);
         
@@ -825,7 +829,14 @@ impl DashboardPage {
                 .map(|s| s.success())
                 .unwrap_or(false);
             
-            let _ = tx.send(is_running);
+            // Query our own profile daemon (cd-network-managerd)
+            let daemon_status = crate::application::tokio_runtime().block_on(async {
+                let mut client = crate::dbus_client::DaemonClient::new();
+                client.connect().await.ok()?;
+                client.get_status().await.ok()
+            });
+            
+            let _ = tx.send((is_running, daemon_status));
         });
         
         // Poll for result on main thread
@@ -833,14 +844,18 @@ impl DashboardPage {
         glib::timeout_add_local_once(std::time::Duration::from_millis(100), move || {
             if let Some(page) = page_weak.upgrade() {
                 match rx.try_recv() {
-                    Ok(is_running) => page.update_daemon_status(is_running),
+                    Ok((is_running, daemon_status)) => {
+                        page.update_daemon_status(is_running);
+                        page.update_profile_daemon_status(daemon_status.as_ref());
+                    }
                     Err(std::sync::mpsc::TryRecvError::Empty) => {
                         // Still waiting, check again
                         let page_weak2 = page.downgrade();
                         glib::timeout_add_local_once(std::time::Duration::from_millis(100), move || {
                             if let Some(page) = page_weak2.upgrade() {
-                                if let Ok(is_running) = rx.try_recv() {
+                                if let Ok((is_running, daemon_status)) = rx.try_recv() {
                                     page.update_daemon_status(is_running);
+                                    page.update_profile_daemon_status(daemon_status.as_ref());
                                 }
                             }
                         });
@@ -879,6 +894,29 @@ impl DashboardPage {
             button.set_visible(!is_running);
         }
     }
+    
+    /// Update the profile daemon (cd-network-managerd) line of the status card
+    pub fn update_profile_daemon_status(&self, status: Option<&crate::dbus_client::DaemonStatus>) {
+        let imp = self.imp();
+        
+        if let Some(desc) = imp.daemon_status_desc.get() {
+            match status {
+                Some(status) if status.healthy => {
+                    desc.set_text(&format!("Profile daemon v{} is running", status.version));
+                }
+                Some(status) => {
+                    desc.set_text(&format!(
+                        "Profile daemon v{} reported an error: {}",
+                        status.version,
+                        status.last_error.as_deref().unwrap_or("unknown")
+                    ));
+                }
+                None => {
+                    desc.set_text("Profile daemon not running — profiles are applied locally");
+                }
+            }
+        }
+    }
 }
 
 impl Default for DashboardPage {
@@ -940,7 +978,7 @@ struct NetworkInfo {
     connection_type: String,
 }
 
-/// Get primary network interface information using nmcli and ip commands
+/// Get primary network interface information from NetworkManager and the ip command
 fn get_primary_network_info() -> NetworkInfo {
     use std::process::Command;
     
@@ -951,59 +989,48 @@ fn get_primary_network_info() -> NetworkInfo {
         connection_type: "Disconnected".to_string(),
     };
     
-    // Get active connection info using nmcli
-    if let Ok(output) = Command::new("nmcli")
-        .args(["-t", "-f", "NAME,TYPE,DEVICE", "connection", "show", "--active"])
-        .output()
-    {
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let parts: Vec<&str> = line.split(':').collect();
-                if parts.len() >= 3 {
-                    let conn_name = parts[0];
-                    let conn_type = parts[1];
-                    let device = parts[2];
-                    
-                    // Skip loopback and virtual interfaces
-                    if device == "lo" || device.starts_with("virbr") || device.starts_with("docker") {
-                        continue;
-                    }
-                    
-                    // Determine connection type display name
-                    info.connection_type = match conn_type {
-                        "802-11-wireless" => format!("Wi-Fi ({})", conn_name),
-                        "802-3-ethernet" => format!("Ethernet ({})", conn_name),
-                        "vpn" => format!("VPN ({})", conn_name),
-                        "bridge" => format!("Bridge ({})", conn_name),
-                        _ => format!("{} ({})", conn_type, conn_name),
-                    };
-                    
-                    // Get IP address for this device
-                    if let Ok(ip_output) = Command::new("ip")
-                        .args(["-4", "-o", "addr", "show", device])
-                        .output()
-                    {
-                        if ip_output.status.success() {
-                            let ip_stdout = String::from_utf8_lossy(&ip_output.stdout);
-                            for ip_line in ip_stdout.lines() {
-                                // Format: 2: eth0    inet 192.168.1.100/24 brd 192.168.1.255 scope global eth0
-                                if let Some(inet_pos) = ip_line.find("inet ") {
-                                    let after_inet = &ip_line[inet_pos + 5..];
-                                    if let Some(space_pos) = after_inet.find(' ') {
-                                        info.ip_address = after_inet[..space_pos].to_string();
-                                        break;
-                                    }
-                                }
-                            }
+    // Get active connection info from NetworkManager
+    let nm = NmClient::connect().ok();
+    let active = nm.as_ref().and_then(|nm| nm.active_connections().ok()).unwrap_or_default();
+    for conn in active {
+        let Some(device) = conn.interfaces.first() else { continue };
+
+        // Skip loopback and virtual interfaces
+        if device == "lo" || device.starts_with("virbr") || device.starts_with("docker") {
+            continue;
+        }
+
+        // Determine connection type display name
+        info.connection_type = match conn.connection_type.as_str() {
+            "802-11-wireless" => format!("Wi-Fi ({})", conn.id),
+            "802-3-ethernet" => format!("Ethernet ({})", conn.id),
+            "vpn" => format!("VPN ({})", conn.id),
+            "bridge" => format!("Bridge ({})", conn.id),
+            other => format!("{} ({})", other, conn.id),
+        };
+
+        // Get IP address for this device
+        if let Ok(ip_output) = Command::new("ip")
+            .args(["-4", "-o", "addr", "show", device.as_str()])
+            .output()
+        {
+            if ip_output.status.success() {
+                let ip_stdout = String::from_utf8_lossy(&ip_output.stdout);
+                for ip_line in ip_stdout.lines() {
+                    // Format: 2: eth0    inet 192.168.1.100/24 brd 192.168.1.255 scope global eth0
+                    if let Some(inet_pos) = ip_line.find("inet ") {
+                        let after_inet = &ip_line[inet_pos + 5..];
+                        if let Some(space_pos) = after_inet.find(' ') {
+                            info.ip_address = after_inet[..space_pos].to_string();
+                            break;
                         }
                     }
-                    
-                    // Found a valid connection, break
-                    break;
                 }
             }
         }
+
+        // Found a valid connection, break
+        break;
     }
     
     // Get default gateway
@@ -1026,30 +1053,13 @@ fn get_primary_network_info() -> NetworkInfo {
         }
     }
     
-    // Get DNS servers from resolv.conf or nmcli
-    if let Ok(output) = Command::new("nmcli")
-        .args(["-t", "-f", "IP4.DNS", "device", "show"])
-        .output()
-    {
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let mut dns_servers: Vec<String> = Vec::new();
-            for line in stdout.lines() {
-                if line.starts_with("IP4.DNS") {
-                    if let Some(dns) = line.split(':').nth(1) {
-                        if !dns.is_empty() && !dns_servers.contains(&dns.to_string()) {
-                            dns_servers.push(dns.to_string());
-                        }
-                    }
-                }
-            }
-            if !dns_servers.is_empty() {
-                info.dns_servers = dns_servers.join(", ");
-            }
-        }
+    // Get DNS servers from NetworkManager
+    let dns_servers = nm.as_ref().and_then(|nm| nm.dns_servers().ok()).unwrap_or_default();
+    if !dns_servers.is_empty() {
+        info.dns_servers = dns_servers.join(", ");
     }
     
-    // Fallback to resolv.conf if nmcli didn't provide DNS
+    // Fallback to resolv.conf if NetworkManager didn't provide DNS
     if info.dns_servers == "—" {
         if let Ok(content) = fs::read_to_string("/etc/resolv.conf") {
             let mut dns_servers: Vec<String> = Vec::new();
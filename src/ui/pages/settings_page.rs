@@ -418,7 +418,8 @@ impl SettingsPage {
             .description("Script execution and profile security settings")
             .build();
 
-        let sandbox_model = gtk::StringList::new(&["No Sandboxing", "Bubblewrap", "Firejail"]);
+        let sandbox_names: Vec<&str> = SandboxMode::ALL.iter().map(|m| m.display_name()).collect();
+        let sandbox_model = gtk::StringList::new(&sandbox_names);
         let sandbox_row = adw::ComboRow::builder()
             .title("Script Sandboxing")
             .subtitle("Method for isolating script execution")
@@ -773,7 +774,9 @@ impl SettingsPage {
             existing_profiles.drain(..).map(|p| (p.id().to_string(), p)).collect();
         
         let imported_count = imported_profiles.len();
-        for profile in imported_profiles {
+        for mut profile in imported_profiles {
+            // Scripts from elsewhere must be reviewed before they run.
+            profile.revoke_approvals();
             profiles_map.insert(profile.id().to_string(), profile);
         }
         
@@ -1521,12 +1524,7 @@ impl SettingsPage {
         let key = imp.encryption_key_row.borrow().as_ref()
             .map(|r| r.text().to_string()).unwrap_or_default();
         
-        let sandbox_mode = match sandbox_idx {
-            0 => SandboxMode::None,
-            1 => SandboxMode::Bubblewrap,
-            2 => SandboxMode::Firejail,
-            _ => SandboxMode::None,
-        };
+        let sandbox_mode = SandboxMode::ALL.get(sandbox_idx as usize).copied().unwrap_or_default();
         
         self.update_app_config(|config| {
             config.sandbox_mode = sandbox_mode;
@@ -1604,12 +1602,8 @@ impl SettingsPage {
         
         // Security
         if let Some(row) = imp.sandbox_row.borrow().as_ref() {
-            let idx = match config.sandbox_mode {
-                SandboxMode::None => 0,
-                SandboxMode::Bubblewrap => 1,
-                SandboxMode::Firejail => 2,
-            };
-            row.set_selected(idx);
+            let idx = SandboxMode::ALL.iter().position(|m| *m == config.sandbox_mode).unwrap_or(0);
+            row.set_selected(idx as u32);
         }
         if let Some(row) = imp.encryption_row.borrow().as_ref() {
             row.set_active(config.encrypt_profiles);